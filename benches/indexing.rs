@@ -0,0 +1,63 @@
+//! Benchmarks of indexing a synthetic repository, e.g. `cargo bench --bench indexing`.
+//!
+//! The graph is stored both in memory, so that the parse cost is isolated from the database, and in Kuzu,
+//! which adds the insert cost.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use codegraph::{generate_synthetic_repo, CodeGraph, Config, MemoryStore, Parser};
+
+fn bench_indexing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("indexing");
+    group.sample_size(10);
+
+    for num_files in [100, 1000] {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_path_buf();
+        generate_synthetic_repo(&repo_path, num_files).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("parse", num_files),
+            &repo_path,
+            |b, path| {
+                b.iter(|| {
+                    let mut parser = Parser::new(path.clone(), Config::default());
+                    parser.parse(path, None).unwrap();
+                    parser.resolve_pending_edges(None).unwrap()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("parse_and_insert", num_files),
+            &repo_path,
+            |b, path| {
+                b.iter(|| {
+                    let mut graph =
+                        CodeGraph::with_store(MemoryStore::new(), path.clone(), Config::default());
+                    graph.index(path.clone(), false).unwrap();
+                })
+            },
+        );
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_path = db_dir.path().join("kuzu_db");
+        group.bench_with_input(
+            BenchmarkId::new("parse_and_insert_kuzu", num_files),
+            &repo_path,
+            |b, path| {
+                b.iter(|| {
+                    let mut graph =
+                        CodeGraph::new(db_path.clone(), path.clone(), Config::default());
+                    graph.clean(true).unwrap();
+                    graph.index(path.clone(), false).unwrap();
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_indexing);
+criterion_main!(benches);
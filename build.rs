@@ -1,5 +1,53 @@
+use std::env;
+use std::process::Command;
+
 fn main() {
     // Make it possible to use the JSON extension in binaries (binary crates or tests).
     // See https://docs.rs/kuzu/latest/kuzu/#using-extensions
     println!("cargo:rustc-link-arg=-rdynamic");
+
+    // Expose the resolved versions of the tree-sitter grammars as environment variables
+    // (e.g. `tree-sitter-go 0.23.4` => `TREE_SITTER_GO_VERSION=0.23.4`).
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1"])
+        .current_dir(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .output()
+        .expect("Should have been able to run cargo metadata");
+    assert!(
+        output.status.success(),
+        "cargo metadata failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let metadata: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Should have been valid metadata");
+
+    // Only the direct dependencies of this package, whose versions are looked up by package ID.
+    let root = &metadata["resolve"]["root"];
+    let Some(root_node) = metadata["resolve"]["nodes"]
+        .as_array()
+        .and_then(|nodes| nodes.iter().find(|node| &node["id"] == root))
+    else {
+        return;
+    };
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+    for dep in root_node["deps"].as_array().into_iter().flatten() {
+        let Some(package) = packages.iter().find(|p| p["id"] == dep["pkg"]) else {
+            continue;
+        };
+        let (Some(name), Some(version)) = (package["name"].as_str(), package["version"].as_str())
+        else {
+            continue;
+        };
+        if name.starts_with("tree-sitter-") {
+            println!(
+                "cargo:rustc-env={}_VERSION={}",
+                name.to_uppercase().replace('-', "_"),
+                version
+            );
+        }
+    }
 }
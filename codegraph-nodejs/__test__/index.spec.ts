@@ -29,4 +29,15 @@ test('parsing nodes', (t) => {
   t.deepEqual(types, ['types.go:3:6', 'types.go:8:11'], 'unexpected types');
 
   graph.clean(true);
-})
\ No newline at end of file
+})
+
+test('finding references', (t) => {
+  const repoDir = path.join(path.dirname(REPO_DIR), 'references');
+  const refGraph = new codegraph.CodeGraph(path.join(repoDir, "kuzu_db"), repoDir, {});
+  refGraph.index(repoDir, false);
+
+  const names = refGraph.findReferences("types.go:Order").map((n) => n.name);
+  t.deepEqual(names, ['main.go:Cancel', 'main.go:Create'], 'unexpected references');
+
+  refGraph.clean(true);
+})
@@ -10,13 +10,15 @@ export const enum NodeType {
   Interface = 'Interface',
   Class = 'Class',
   Function = 'Function',
-  OtherType = 'OtherType'
+  OtherType = 'OtherType',
+  Namespace = 'Namespace'
 }
 export const enum EdgeType {
   Contains = 'Contains',
   Imports = 'Imports',
   Inherits = 'Inherits',
-  References = 'References'
+  References = 'References',
+  Calls = 'Calls'
 }
 export interface Node {
   /** File path */
@@ -51,6 +53,10 @@ export interface Snippet {
   endLine: number
   content: string
 }
+export interface DirtyFile {
+  path: string
+  content: string
+}
 export interface Config {
   /** Whether to recursively traverse subdirectories (default is true) */
   recursive?: boolean
@@ -74,6 +80,11 @@ export interface Config {
   ignorePatterns?: Array<string>
   /** Whether to use .gitignore files found in directories (default is true) */
   useGitignoreFiles?: boolean
+  /**
+   * The milliseconds to sleep after parsing each file, to throttle the CPU usage of indexing,
+   * 0 means no throttling (default is 0)
+   */
+  throttleMs?: number
 }
 export interface ParseResult {
   nodes: Array<Node>
@@ -91,6 +102,8 @@ export declare class CodeGraph {
   constructor(dbPath: string, repoPath: string, config: Config)
   index(path: string, force: boolean): void
   indexDirtyFile(path: string, content: string): void
+  indexDirtyFiles(entries: Array<DirtyFile>): void
   getFuncParamTypes(filePath: string, line: number): Array<Snippet>
+  findReferences(nodeName: string): Array<Node>
   clean(del: boolean): void
 }
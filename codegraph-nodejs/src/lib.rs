@@ -11,6 +11,7 @@ pub enum NodeType {
     Class,
     Function,
     OtherType,
+    Namespace,
 }
 
 impl From<codegraph::NodeType> for NodeType {
@@ -23,6 +24,7 @@ impl From<codegraph::NodeType> for NodeType {
             codegraph::NodeType::Class => NodeType::Class,
             codegraph::NodeType::Function => NodeType::Function,
             codegraph::NodeType::OtherType => NodeType::OtherType,
+            codegraph::NodeType::Namespace => NodeType::Namespace,
         }
     }
 }
@@ -37,6 +39,7 @@ impl Into<codegraph::NodeType> for NodeType {
             NodeType::Class => codegraph::NodeType::Class,
             NodeType::Function => codegraph::NodeType::Function,
             NodeType::OtherType => codegraph::NodeType::OtherType,
+            NodeType::Namespace => codegraph::NodeType::Namespace,
         }
     }
 }
@@ -47,6 +50,7 @@ pub enum EdgeType {
     Imports,
     Inherits,
     References,
+    Calls,
 }
 
 impl From<codegraph::EdgeType> for EdgeType {
@@ -56,6 +60,7 @@ impl From<codegraph::EdgeType> for EdgeType {
             codegraph::EdgeType::Imports => EdgeType::Imports,
             codegraph::EdgeType::Inherits => EdgeType::Inherits,
             codegraph::EdgeType::References => EdgeType::References,
+            codegraph::EdgeType::Calls => EdgeType::Calls,
         }
     }
 }
@@ -67,6 +72,7 @@ impl Into<codegraph::EdgeType> for EdgeType {
             EdgeType::Imports => codegraph::EdgeType::Imports,
             EdgeType::Inherits => codegraph::EdgeType::Inherits,
             EdgeType::References => codegraph::EdgeType::References,
+            EdgeType::Calls => codegraph::EdgeType::Calls,
         }
     }
 }
@@ -118,6 +124,7 @@ impl Into<codegraph::Node> for Node {
             end_line: self.end_line as usize,
             code: self.code,
             skeleton_code: self.skeleton_code,
+            ..Default::default()
         }
     }
 }
@@ -157,6 +164,7 @@ impl Into<codegraph::Edge> for Edge {
             to: self.to.into(),
             import: self.import,
             alias: self.alias,
+            properties: Default::default(),
         }
     }
 }
@@ -170,6 +178,13 @@ pub struct Snippet {
     pub content: String,
 }
 
+#[napi(object)]
+#[derive(Clone)]
+pub struct DirtyFile {
+    pub path: String,
+    pub content: String,
+}
+
 impl From<codegraph::Snippet> for Snippet {
     fn from(s: codegraph::Snippet) -> Self {
         Self {
@@ -204,6 +219,9 @@ pub struct Config {
     pub ignore_patterns: Option<Vec<String>>,
     /// Whether to use .gitignore files found in directories (default is true)
     pub use_gitignore_files: Option<bool>,
+    /// The milliseconds to sleep after parsing each file, to throttle the CPU usage of indexing,
+    /// 0 means no throttling (default is 0)
+    pub throttle_ms: Option<u32>,
 }
 
 impl Into<codegraph::Config> for Config {
@@ -227,6 +245,9 @@ impl Into<codegraph::Config> for Config {
         if let Some(use_gitignore_files) = self.use_gitignore_files {
             cfg = cfg.use_gitignore_files(use_gitignore_files);
         }
+        if let Some(throttle_ms) = self.throttle_ms {
+            cfg = cfg.throttle_ms(throttle_ms as u64);
+        }
         cfg
     }
 }
@@ -269,17 +290,19 @@ impl CodeGraph {
     // let graph = new codegraph.Parser("path/to/db", "/path/to/repo", config);
     // ```
     #[napi(constructor)]
-    pub fn new(db_path: String, repo_path: String, config: Config) -> Self {
-        Self {
-            db_path: db_path.clone(),
-            repo_path: repo_path.clone(),
-            config: config.clone(),
-            graph: codegraph::CodeGraph::new(
-                PathBuf::from(db_path),
-                PathBuf::from(repo_path),
-                config.into(),
-            ),
-        }
+    pub fn new(db_path: String, repo_path: String, config: Config) -> napi::Result<Self> {
+        let graph = codegraph::CodeGraph::try_new(
+            PathBuf::from(db_path.clone()),
+            PathBuf::from(repo_path.clone()),
+            config.clone().into(),
+        )
+        .map_err(|e| napi::Error::from_reason(format!("Opening the database failed: {}", e)))?;
+        Ok(Self {
+            db_path,
+            repo_path,
+            config,
+            graph,
+        })
     }
 
     #[napi]
@@ -302,6 +325,19 @@ impl CodeGraph {
         }
     }
 
+    #[napi]
+    pub fn index_dirty_files(&mut self, entries: Vec<DirtyFile>) -> napi::Result<()> {
+        let files = entries
+            .into_iter()
+            .map(|entry| (PathBuf::from(entry.path), entry.content.into_bytes()))
+            .collect();
+        let result = self.graph.index_dirty_files(files);
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(napi::Error::from_reason(format!("Indexing failed: {}", e))),
+        }
+    }
+
     #[napi]
     pub fn get_func_param_types(
         &mut self,
@@ -321,6 +357,18 @@ impl CodeGraph {
         }
     }
 
+    #[napi]
+    pub fn find_references(&mut self, node_name: String) -> napi::Result<Vec<Node>> {
+        let result = self.graph.find_references(node_name);
+        match result {
+            Ok(nodes) => Ok(nodes.into_iter().map(Node::from).collect()),
+            Err(e) => Err(napi::Error::from_reason(format!(
+                "Failed to find references: {}",
+                e
+            ))),
+        }
+    }
+
     #[napi]
     pub fn clean(&mut self, del: bool) -> napi::Result<()> {
         match self.graph.clean(del) {
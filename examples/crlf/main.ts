@@ -0,0 +1,8 @@
+export interface Greeter {
+    greet(name: string): string;
+}
+
+export function hello(name: string): string {
+    const greeting = "Hello";
+    return `${greeting}, ${name}!`;
+}
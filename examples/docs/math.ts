@@ -0,0 +1,26 @@
+/**
+ * Computes the greatest common divisor of two integers
+ * using the Euclidean algorithm.
+ */
+export function gcd(a: number, b: number): number {
+  return b === 0 ? a : gcd(b, a % b);
+}
+
+// Returns the least common multiple of two integers.
+export function lcm(a: number, b: number): number {
+  return (a / gcd(a, b)) * b;
+}
+
+export function square(x: number): number {
+  return x * x;
+}
+
+/** A 2D vector. */
+export class Vector {
+  constructor(public x: number, public y: number) {}
+
+  /** Returns the Euclidean length of the vector. */
+  length(): number {
+    return Math.sqrt(square(this.x) + square(this.y));
+  }
+}
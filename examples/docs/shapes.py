@@ -0,0 +1,9 @@
+class Circle:
+    """A circle with the given radius."""
+
+    def __init__(self, radius):
+        self.radius = radius
+
+
+class Square:
+    pass
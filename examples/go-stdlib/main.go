@@ -0,0 +1,14 @@
+package main
+
+import (
+	"fmt"
+	"time"
+)
+
+func Elapsed(start time.Time) time.Duration {
+	return time.Since(start)
+}
+
+func main() {
+	fmt.Println(Elapsed(time.Now()))
+}
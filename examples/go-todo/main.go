@@ -0,0 +1,13 @@
+package main
+
+import "fmt"
+
+// FIXME: read the greeting from the config
+const greeting = "Hello"
+
+func main() {
+	// TODO: support multiple names
+	name := "world"
+	fmt.Println(greeting + ", " + name) // TODOS are not captured
+	fmt.Println("// TODO: not a comment, but a string")
+}
@@ -0,0 +1,21 @@
+package main
+
+import "fmt"
+
+func init() {
+	fmt.Println("init")
+}
+
+// Greet is exported but never called.
+func Greet(name string) string {
+	return "Hello, " + name
+}
+
+// greet is unexported, which is left to the linters.
+func greet(name string) string {
+	return "Hi, " + name
+}
+
+func main() {
+	fmt.Println("main")
+}
@@ -0,0 +1,5 @@
+package models
+
+type Item struct {
+	ID int
+}
@@ -0,0 +1,13 @@
+package main
+
+import (
+	m "example.com/alias/models"
+	models "example.com/alias/legacy/models"
+)
+
+func Save(u *m.User, i *models.Item) {
+}
+
+func main() {
+	Save(&m.User{}, &models.Item{})
+}
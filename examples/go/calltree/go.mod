@@ -0,0 +1,3 @@
+module example.com/calltree
+
+go 1.22.4
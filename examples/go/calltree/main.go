@@ -0,0 +1,19 @@
+package main
+
+import "fmt"
+
+func main() {
+	fmt.Println(a(3))
+}
+
+func a(n int) int {
+	return b(n) + 1
+}
+
+func b(n int) int {
+	if n <= 0 {
+		return 0
+	}
+	// Recursive call back to a.
+	return a(n - 1)
+}
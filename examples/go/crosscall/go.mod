@@ -0,0 +1,3 @@
+module example.com/crosscall
+
+go 1.22.4
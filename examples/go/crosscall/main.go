@@ -0,0 +1,16 @@
+package main
+
+import (
+	"fmt"
+
+	"example.com/crosscall/models"
+)
+
+func greet(name string) string {
+	return fmt.Sprintf("Hello, %s!", name)
+}
+
+func main() {
+	user := models.NewUser("gopher")
+	fmt.Println(greet(user.Name))
+}
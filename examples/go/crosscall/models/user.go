@@ -0,0 +1,9 @@
+package models
+
+type User struct {
+	Name string
+}
+
+func NewUser(name string) *User {
+	return &User{Name: name}
+}
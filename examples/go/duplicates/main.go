@@ -0,0 +1,33 @@
+package main
+
+import "fmt"
+
+func addAll(nums []int) int {
+	total := 0
+	for _, n := range nums {
+		total += n
+	}
+	return total
+}
+
+func one() int { return 1 }
+
+func main() {
+	nums := []int{1, 2, 3}
+	fmt.Println(addAll(nums), sum(nums), maximum(nums), one(), uno())
+}
+
+type Counter struct {
+	n int
+}
+
+func (c *Counter) Count() int {
+	c.n++
+	return c.n
+}
+
+// Total is a copy of Count, whose name is a prefix of the receiver type.
+func (c *Counter) Total() int {
+	c.n++
+	return c.n
+}
@@ -0,0 +1,22 @@
+package main
+
+// sum is a copy of addAll with a different name and indentation.
+func sum(nums []int) int {
+    total := 0
+    for _, n := range nums {
+        total += n
+    }
+    return total
+}
+
+func maximum(nums []int) int {
+	result := nums[0]
+	for _, n := range nums {
+		if n > result {
+			result = n
+		}
+	}
+	return result
+}
+
+func uno() int { return 1 }
@@ -0,0 +1,8 @@
+package models
+
+import "time"
+
+type Audit struct {
+	CreatedAt time.Time
+	UpdatedAt time.Time
+}
@@ -0,0 +1,30 @@
+package main
+
+import (
+	"example.com/embedding/models"
+)
+
+type User struct {
+	Name string
+}
+
+// Admin is a user with an audit trail.
+type Admin struct {
+	User
+	*models.Audit
+	Level int
+}
+
+type Reader interface {
+	Read(p []byte) (n int, err error)
+}
+
+type Writer interface {
+	Write(p []byte) (n int, err error)
+}
+
+// ReadWriter groups the basic Read and Write methods.
+type ReadWriter interface {
+	Reader
+	Writer
+}
@@ -0,0 +1,22 @@
+package main
+
+import "time"
+
+type Address struct {
+	Country string
+	City    string
+}
+
+type Tag struct {
+	Name string
+}
+
+type User struct {
+	ID        int
+	Name      string
+	Address   *Address
+	Tags      []Tag
+	Settings  map[string]Tag
+	Extra     struct{ Note string }
+	CreatedAt time.Time
+}
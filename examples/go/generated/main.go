@@ -0,0 +1,10 @@
+package main
+
+type Status int
+
+type User struct {
+	Status Status
+}
+
+func main() {
+}
@@ -0,0 +1,13 @@
+// Code generated by "stringer -type=Status"; DO NOT EDIT.
+
+package main
+
+import "strconv"
+
+func (s Status) String() string {
+	return "Status(" + strconv.Itoa(int(s)) + ")"
+}
+
+type statusNames struct {
+	names []string
+}
@@ -0,0 +1,9 @@
+package main
+
+type Ordered interface {
+	~int | ~float64 | ~string
+}
+
+type Number interface {
+	~int | ~float64
+}
@@ -0,0 +1,22 @@
+package main
+
+import "fmt"
+
+func Max[T Ordered](a, b T) T {
+	if a > b {
+		return a
+	}
+	return b
+}
+
+func Sum[K comparable, V Number | ~int8](m map[K]V) V {
+	var sum V
+	for _, v := range m {
+		sum += v
+	}
+	return sum
+}
+
+func main() {
+	fmt.Println(Max(1, 2), Sum(map[string]int{"a": 1}))
+}
@@ -0,0 +1,6 @@
+package main
+
+// Close is declared apart from its receiver type File.
+func (f *File) Close() error {
+	return nil
+}
@@ -0,0 +1,36 @@
+package main
+
+type Reader interface {
+	Read() string
+}
+
+type Closer interface {
+	Close() error
+}
+
+// ReadCloser only requires the methods of the embedded interfaces.
+type ReadCloser interface {
+	Reader
+	Closer
+}
+
+type File struct{}
+
+func (f *File) Read() string {
+	return ""
+}
+
+type Base struct{}
+
+func (b *Base) Close() error {
+	return nil
+}
+
+// LoggedFile satisfies ReadCloser via the Close method promoted from Base.
+type LoggedFile struct {
+	Base
+}
+
+func (l *LoggedFile) Read() string {
+	return ""
+}
@@ -0,0 +1,3 @@
+module example.com/importgroups
+
+go 1.22.4
@@ -0,0 +1,21 @@
+package main
+
+import (
+	"fmt"
+
+	"example.com/importgroups/models"
+)
+
+// The alias `models` shadows the package name of the first import, and `u` is another local name of it.
+import (
+	models "example.com/importgroups/legacy/models"
+	u "example.com/importgroups/models"
+)
+
+func Save(i *models.Item, user *u.User) {
+	fmt.Println(i, user)
+}
+
+func main() {
+	Save(&models.Item{}, &u.User{})
+}
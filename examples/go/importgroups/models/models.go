@@ -0,0 +1,9 @@
+package models
+
+type User struct {
+	Name string
+}
+
+type Item struct {
+	ID int
+}
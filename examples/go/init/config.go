@@ -0,0 +1,7 @@
+package main
+
+var debug bool
+
+func init() {
+	debug = true
+}
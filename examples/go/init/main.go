@@ -0,0 +1,17 @@
+package main
+
+import "fmt"
+
+var registry = map[string]string{}
+
+func init() {
+	registry["a"] = "alpha"
+}
+
+func init() {
+	registry["b"] = "beta"
+}
+
+func main() {
+	fmt.Println(registry)
+}
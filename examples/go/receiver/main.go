@@ -0,0 +1,22 @@
+package main
+
+import "fmt"
+
+// SetAddress is a free function with the same name as the method of User.
+func SetAddress(address string) string {
+	return address
+}
+
+func register(u *User) {
+	u.SetAddress(SetAddress("Beijing"))
+}
+
+func main() {
+	u := NewUser("gopher")
+	u.SetAddress("Shanghai")
+
+	var guest User
+	fmt.Println(guest.Greeting())
+
+	register(&User{Name: "admin"})
+}
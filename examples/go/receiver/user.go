@@ -0,0 +1,18 @@
+package main
+
+type User struct {
+	Name    string
+	Address string
+}
+
+func NewUser(name string) *User {
+	return &User{Name: name}
+}
+
+func (u *User) SetAddress(address string) {
+	u.Address = address
+}
+
+func (u User) Greeting() string {
+	return "Hello, " + u.Name
+}
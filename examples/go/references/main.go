@@ -0,0 +1,17 @@
+package main
+
+import "fmt"
+
+func Create(order *Order) {
+	fmt.Printf("Order %d created\n", order.ID)
+}
+
+func Cancel(order Order) {
+	fmt.Printf("Order %d cancelled\n", order.ID)
+}
+
+func main() {
+	order := &Order{ID: 1, Total: 9.9}
+	Create(order)
+	Cancel(*order)
+}
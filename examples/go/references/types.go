@@ -0,0 +1,6 @@
+package main
+
+type Order struct {
+	ID    int
+	Total float64
+}
@@ -0,0 +1,32 @@
+package main
+
+import "fmt"
+
+// Shape is implemented by all the shapes.
+type Shape interface {
+	Area() float64
+	Perimeter() float64
+	Scale(factor float64) Shape
+}
+
+type Rect struct {
+	Width  float64
+	Height float64
+}
+
+func (r *Rect) Area() float64 {
+	return r.Width * r.Height
+}
+
+func (r *Rect) Perimeter() float64 {
+	return 2 * (r.Width + r.Height)
+}
+
+func (r *Rect) Scale(factor float64) Shape {
+	return &Rect{Width: r.Width * factor, Height: r.Height * factor}
+}
+
+func main() {
+	var s Shape = &Rect{Width: 2, Height: 3}
+	fmt.Println(s.Area(), s.Perimeter())
+}
@@ -0,0 +1,10 @@
+import { Button } from './components';
+import { formatLabel } from './utils';
+
+export function App() {
+  return (
+    <div className="app">
+      <Button label={formatLabel(' click me ')} onClick={() => alert('clicked')} />
+    </div>
+  );
+}
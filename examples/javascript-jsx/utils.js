@@ -0,0 +1,3 @@
+export function formatLabel(label) {
+  return label.trim().toUpperCase();
+}
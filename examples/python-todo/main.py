@@ -0,0 +1,8 @@
+def greet(name):
+    # TODO: support multiple names
+    return "Hello, " + name
+# FIXME: add a farewell
+
+
+def farewell():
+    pass
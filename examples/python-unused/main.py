@@ -0,0 +1,6 @@
+def greet(name):
+    return _format("Hello", name)
+
+
+def _format(greeting, name):
+    return greeting + ", " + name
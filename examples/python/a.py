@@ -3,3 +3,9 @@ class A:
 
     def greet(self) -> str:
         return f"Hello, {self.name}!"
+
+
+def make_a(name: str) -> A:
+    a = A()
+    a.name = name
+    return a
@@ -0,0 +1,14 @@
+import os
+import pkg.utils as utils
+from pkg import helpers
+from typing import List
+
+from .models import User
+
+
+def greet(user: User, greeting: str) -> str:
+    return helpers.shout(utils.slugify(f"{greeting} {user.name}")) + os.linesep
+
+
+def greet_all(users: List[User]) -> List[str]:
+    return [greet(user, "hello") for user in users]
@@ -0,0 +1,18 @@
+class Outer:
+    class Inner:
+        class Deepest:
+            pass
+
+    def describe(self):
+        return "outer"
+
+
+def make_local():
+    class Local:
+        pass
+
+    return Local
+
+
+class Other:
+    pass
@@ -0,0 +1,13 @@
+mod models;
+
+use crate::models::{Shape, User};
+
+fn describe(shape: &dyn Shape) -> String {
+    format!("{} with area {}", shape.name(), shape.area())
+}
+
+fn main() {
+    let user = User::new("alice");
+    println!("{}", user.greet());
+    println!("{}", describe(&user));
+}
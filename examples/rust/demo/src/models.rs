@@ -0,0 +1,39 @@
+/// A registered user.
+#[derive(Debug, Clone)]
+pub struct User {
+    pub name: String,
+    pub role: Role,
+}
+
+impl User {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            role: Role::Member,
+        }
+    }
+
+    pub fn greet(&self) -> String {
+        format!("Hello, {}!", self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Role {
+    Admin,
+    Member,
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+
+    fn name(&self) -> String {
+        "shape".to_string()
+    }
+}
+
+impl Shape for User {
+    fn area(&self) -> f64 {
+        0.0
+    }
+}
@@ -0,0 +1,40 @@
+mod models;
+mod user_impl;
+
+use std::fmt;
+
+pub struct Point {
+    pub x: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({})", self.x)
+    }
+}
+
+impl fmt::Debug for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Point({})", self.x)
+    }
+}
+
+impl From<i32> for Point {
+    fn from(x: i32) -> Self {
+        Point { x }
+    }
+}
+
+impl From<u8> for Point {
+    fn from(x: u8) -> Self {
+        Point { x: x.into() }
+    }
+}
+
+pub mod utils {
+    pub fn helper() {}
+
+    mod nested {
+        fn deep() {}
+    }
+}
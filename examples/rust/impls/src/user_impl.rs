@@ -0,0 +1,9 @@
+use crate::models::User;
+
+impl User {
+    pub fn new(name: &str) -> Self {
+        User {
+            name: name.to_string(),
+        }
+    }
+}
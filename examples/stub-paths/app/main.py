@@ -0,0 +1,5 @@
+from requests import Session
+
+
+def fetch(session: Session, url: str) -> str:
+    return session.get(url).text
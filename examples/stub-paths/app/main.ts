@@ -0,0 +1,5 @@
+import { AxiosInstance } from 'axios';
+
+export function fetch(client: AxiosInstance, url: string): Promise<unknown> {
+  return client.get(url);
+}
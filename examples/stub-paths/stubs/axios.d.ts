@@ -0,0 +1,3 @@
+export interface AxiosInstance {
+  get(url: string): Promise<unknown>;
+}
@@ -0,0 +1,5 @@
+class Response:
+    text: str
+
+class Session:
+    def get(self, url: str) -> Response: ...
@@ -0,0 +1,26 @@
+export class Temperature {
+  private celsius: number = 0;
+
+  static fromFahrenheit(fahrenheit: number): Temperature {
+    const temperature = new Temperature();
+    temperature.fahrenheit = fahrenheit;
+    return temperature;
+  }
+
+  static get zero(): Temperature {
+    return new Temperature();
+  }
+
+  get fahrenheit(): number {
+    return this.celsius * 1.8 + 32;
+  }
+
+  set fahrenheit(value: number) {
+    this.celsius = (value - 32) / 1.8;
+  }
+
+  // A plain method named `get`, which is not an accessor.
+  get(unit: string): number {
+    return unit === "F" ? this.fahrenheit : this.celsius;
+  }
+}
@@ -0,0 +1,12 @@
+import { formatName } from "./utils";
+import * as utils from "./utils";
+
+function log(message: string): void {
+  console.log(message);
+}
+
+export function run(name: string): void {
+  const formatted = formatName(name);
+  log(utils.shout(formatted));
+  log(formatted);
+}
@@ -0,0 +1,7 @@
+export function formatName(name: string): string {
+  return name.trim();
+}
+
+export function shout(text: string): string {
+  return text.toUpperCase();
+}
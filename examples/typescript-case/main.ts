@@ -0,0 +1,8 @@
+import { User } from "./Types";
+import * as Models from "./TYPES.ts";
+
+export function greet(user: User): string {
+  return `Hello, ${user.name}!`;
+}
+
+export const users: Models.User[] = [];
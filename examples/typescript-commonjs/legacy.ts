@@ -0,0 +1,5 @@
+import types = require('./types');
+
+export function legacyUser(): types.User {
+    return { id: 0, name: 'legacy' };
+}
@@ -0,0 +1,11 @@
+const types = require('./types');
+const { createUser } = require('./types');
+
+declare function loadConfig(path: string): unknown;
+// Not a require call, so it is not an import.
+const config = loadConfig('./types');
+
+export function newAdmin(): void {
+    const admin = createUser(1, 'admin');
+    console.log(types, admin, config);
+}
@@ -0,0 +1,8 @@
+export enum TaskStatus {
+  TODO = 'todo',
+  DONE = 'done',
+}
+
+export function isDone(status: TaskStatus): boolean {
+  return status === TaskStatus.DONE;
+}
@@ -0,0 +1,5 @@
+import { TaskStatus } from './status';
+
+export function updateStatus(id: string, status: TaskStatus): void {
+  console.log(`${id}: ${status}`);
+}
@@ -0,0 +1,14 @@
+export function area(shape: Shape): number {
+  return shape.width * shape.height;
+}
+
+export function describe(shape: Shape, unit: Unit): string {
+  return `${shape.width}x${shape.height} ${unit}`;
+}
+
+export interface Shape {
+  width: number;
+  height: number;
+}
+
+export type Unit = "cm" | "in";
@@ -0,0 +1,23 @@
+export interface Shape {
+  area(): number;
+}
+
+export class Circle implements Shape {
+  constructor(private radius: number) {}
+
+  area(): number {
+    return Math.PI * this.radius * this.radius;
+  }
+}
+
+export class Square implements Shape {
+  constructor(private side: number) {}
+
+  area(): number {
+    return this.side * this.side;
+  }
+}
+
+export class Label {
+  constructor(public text: string) {}
+}
@@ -0,0 +1,3 @@
+export class Animal {
+  constructor(public name: string) {}
+}
@@ -0,0 +1,13 @@
+import { Animal } from './animal';
+
+interface Pet {
+  owner: string;
+}
+
+export class Dog extends Animal implements Pet {
+  owner = 'alice';
+
+  bark(): string {
+    return `${this.name} says woof`;
+  }
+}
@@ -0,0 +1,8 @@
+import { User } from '../core/types';
+import { formatUser } from '../core/format';
+import { UserService } from '../service/userService';
+
+export function handleCreate(service: UserService, user: User): string {
+    service.add(user);
+    return formatUser(user);
+}
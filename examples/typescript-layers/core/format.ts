@@ -0,0 +1,5 @@
+import { User } from './types';
+
+export function formatUser(user: User): string {
+    return `${user.id}: ${user.name}`;
+}
@@ -0,0 +1,9 @@
+import { User } from '../core/types';
+
+export class UserService {
+    private users: User[] = [];
+
+    add(user: User): void {
+        this.users.push(user);
+    }
+}
@@ -0,0 +1,7 @@
+export namespace Geo {
+  export namespace Shapes {
+    export class Point {
+      constructor(public x: number, public y: number) {}
+    }
+  }
+}
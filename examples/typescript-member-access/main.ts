@@ -0,0 +1,19 @@
+import * as geo from "./geo";
+import { Geo } from "./geo";
+
+namespace Local {
+  export namespace Inner {
+    export interface Options {
+      precision: number;
+    }
+  }
+}
+
+export function distance(a: geo.Geo.Shapes.Point, b: Geo.Shapes.Point, options: Local.Inner.Options): number {
+  const d = Math.hypot(a.x - b.x, a.y - b.y);
+  return Number(d.toFixed(options.precision));
+}
+
+export function describe(value: Unknown.Deeply.Nested.Type): string {
+  return String(value);
+}
@@ -0,0 +1,7 @@
+import { User } from '@myorg/models';
+import { formatName } from '@myorg/utils';
+import { capitalize } from 'lodash';
+
+export function greet(user: User): string {
+    return `Hello, ${capitalize(formatName(user.firstName, user.lastName))}!`;
+}
@@ -0,0 +1,24 @@
+namespace Geo {
+  export interface Shape {
+    area(): number;
+  }
+
+  export class Point {
+    constructor(public x: number, public y: number) {}
+
+    distanceTo(other: Point): number {
+      return Math.hypot(this.x - other.x, this.y - other.y);
+    }
+  }
+
+  export namespace Units {
+    export enum Length {
+      Meter,
+      Foot,
+    }
+  }
+}
+
+export function distance(a: Geo.Point, b: Geo.Point): number {
+  return a.distanceTo(b);
+}
@@ -0,0 +1 @@
+export { User as Account } from "./types";
@@ -0,0 +1,5 @@
+import { Account } from "./index";
+
+export function greet(account: Account): string {
+  return `Hello, ${account.name}!`;
+}
@@ -0,0 +1,7 @@
+interface Logger {
+  log(message: string): void;
+}
+
+export function start(config: AppConfig, logger: Logger): void {
+  logger.log(config.name);
+}
@@ -0,0 +1,5 @@
+// A global type, which is available everywhere without being imported.
+interface AppConfig {
+  name: string;
+  debug: boolean;
+}
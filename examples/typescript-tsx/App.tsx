@@ -0,0 +1,14 @@
+import { UserCard } from './components/UserCard';
+
+function Badge({ label }: { label: string }) {
+  return <span className="badge">{label}</span>;
+}
+
+export function App() {
+  return (
+    <main>
+      <Badge label="admin" />
+      <UserCard name="gopher"></UserCard>
+    </main>
+  );
+}
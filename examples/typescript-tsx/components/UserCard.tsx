@@ -0,0 +1,7 @@
+export interface UserCardProps {
+  name: string;
+}
+
+export function UserCard({ name }: UserCardProps) {
+  return <div className="user-card">{name}</div>;
+}
@@ -0,0 +1,5 @@
+import { User } from './types';
+
+export function greet(user: User): string {
+    return `Hello, ${user.name}!`;
+}
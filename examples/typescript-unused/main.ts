@@ -0,0 +1,7 @@
+import { greet } from './greet';
+
+function main(): void {
+    console.log(greet({ name: 'world' }));
+}
+
+main();
@@ -0,0 +1,11 @@
+export interface User {
+    name: string;
+}
+
+export function unusedHelper(user: User): string {
+    return user.name.toUpperCase();
+}
+
+function localHelper(user: User): string {
+    return user.name.toLowerCase();
+}
@@ -1,20 +1,86 @@
-use crate::{Edge, EdgeType, Language, Node, NodeType};
+use crate::util;
+use crate::{CodeGraphError, Edge, EdgeType, GraphStats, Language, Node, NodeType};
 use indexmap::IndexMap;
 use kuzu;
 use log;
+use regex::Regex;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use tempfile;
 
 // The database schema.
 pub const CREATE_DATABASE_SCHEMA: &str = include_str!("schema.cypher");
 
+/// Returns whether the given Cypher statement may write to the database, which makes the cached stats stale.
+fn is_write_statement(stmt: &str) -> bool {
+    static WRITE_CLAUSE: OnceLock<Regex> = OnceLock::new();
+    WRITE_CLAUSE
+        .get_or_init(|| {
+            Regex::new(r"(?i)\b(CREATE|MERGE|DELETE|SET|REMOVE|COPY|DROP|ALTER)\b").unwrap()
+        })
+        .is_match(stmt)
+}
+
+/// The labels of the node tables with the `metadata` property.
+const METADATA_NODE_LABELS: &str = "File:Interface:Class:Function:OtherType:Namespace";
+
 pub struct Database {
     initialized: bool,
     db_path: PathBuf,
     db: Option<kuzu::Database>,
+    bulk_insert_batch_size: usize,
+    hash_names: bool,
+    prepare_count: usize,
+    stats: Option<GraphStats>,      // Computed on first use.
+    stale_tables: BTreeSet<String>, // The tables written since the stats were (re)counted.
+}
+
+/// Prepared statements keyed by their parameterized query.
+///
+/// A prepared statement belongs to the connection that prepared it, and connections are created
+/// per call (see `upsert_nodes`), so a cache only lives as long as its connection.
+struct StatementCache<'a> {
+    conn: kuzu::Connection<'a>,
+    statements: HashMap<String, kuzu::PreparedStatement>,
+}
+
+impl<'a> StatementCache<'a> {
+    fn new(db: &'a kuzu::Database) -> Result<Self, CodeGraphError> {
+        Ok(Self {
+            conn: kuzu::Connection::new(db)?,
+            statements: HashMap::new(),
+        })
+    }
+
+    /// Execute the given parameterized query, preparing it only on first use.
+    fn execute(
+        &mut self,
+        query: String,
+        params: Vec<(String, kuzu::Value)>,
+    ) -> Result<(), CodeGraphError> {
+        let stmt = match self.statements.entry(query) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                log::debug!("prepare query: {}", entry.key());
+                let stmt = self.conn.prepare(entry.key())?;
+                entry.insert(stmt)
+            }
+        };
+        let params = params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect();
+        self.conn.execute(stmt, params)?;
+        Ok(())
+    }
+
+    /// The number of statements prepared so far.
+    fn len(&self) -> usize {
+        self.statements.len()
+    }
 }
 
 impl Database {
@@ -23,10 +89,196 @@ impl Database {
             initialized: false,
             db_path: db_path,
             db: None,
+            bulk_insert_batch_size: 0,
+            hash_names: false,
+            prepare_count: 0,
+            stats: None,
+            stale_tables: BTreeSet::new(),
+        }
+    }
+
+    /// Set whether to use a hash of the node name as the primary key.
+    ///
+    /// Long names (i.e. deeply nested paths plus long symbol names) are slow as primary keys,
+    /// hashing them bounds the key length at the cost of readability. The human-readable name
+    /// is always stored in the `full_name` property.
+    pub fn set_hash_names(&mut self, hash_names: bool) {
+        self.hash_names = hash_names;
+    }
+
+    /// Get whether a hash of the node name is used as the primary key (see `set_hash_names`).
+    pub fn hash_names(&self) -> bool {
+        self.hash_names
+    }
+
+    /// Get the primary key of the node with the given name.
+    pub fn node_key(&self, name: &str) -> String {
+        if self.hash_names {
+            // The root directory is also referred to by its placeholder name ".".
+            util::stable_hash(if name == "." { "" } else { name })
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn node_to_dict(&self, node: &Node) -> IndexMap<String, serde_json::Value> {
+        let mut dict = node.to_dict();
+        if self.hash_names {
+            dict.insert(
+                "name".to_string(),
+                serde_json::Value::String(self.node_key(&node.name)),
+            );
+        }
+        dict
+    }
+
+    fn edge_to_dict(&self, edge: &Edge) -> IndexMap<String, serde_json::Value> {
+        let mut dict = edge.to_dict();
+        if self.hash_names {
+            dict.insert(
+                "from".to_string(),
+                serde_json::Value::String(self.node_key(&edge.from.name)),
+            );
+            dict.insert(
+                "to".to_string(),
+                serde_json::Value::String(self.node_key(&edge.to.name)),
+            );
+        }
+        dict
+    }
+
+    /// Get the number of statements prepared by `upsert_nodes` and `upsert_edges` so far.
+    pub fn prepare_count(&self) -> usize {
+        self.prepare_count
+    }
+
+    /// Get the numbers of nodes and edges by table.
+    ///
+    /// The stats are computed on first use, and then maintained incrementally, i.e. only the tables written
+    /// by this database since the last call are recounted, so polling them is cheap. Writes by other means
+    /// (e.g. raw statements via `query`) are not tracked, in which case `recompute` recounts all the tables,
+    /// and any drift of the cached stats is logged.
+    pub fn stats(&mut self, recompute: bool) -> Result<GraphStats, CodeGraphError> {
+        self.init()?;
+
+        let mut stats = match (recompute, self.stats.take()) {
+            (false, Some(stats)) => stats,
+            (_, cached) => {
+                let stats = self.count_all_tables()?;
+                if let Some(cached) = cached.filter(|cached| *cached != stats) {
+                    log::warn!(
+                        "The cached stats drifted from the database: {:?} != {:?}",
+                        cached,
+                        stats
+                    );
+                }
+                self.stale_tables.clear();
+                stats
+            }
+        };
+
+        for table in std::mem::take(&mut self.stale_tables) {
+            if let Some(count) = stats.nodes.get_mut(&table) {
+                *count = self.count_rows(&format!("MATCH (n:{}) RETURN count(n);", table))?;
+            } else if let Some(count) = stats.edges.get_mut(&table) {
+                *count =
+                    self.count_rows(&format!("MATCH ()-[e:{}]->() RETURN count(e);", table))?;
+            }
+        }
+
+        self.stats = Some(stats.clone());
+        Ok(stats)
+    }
+
+    /// Count the rows of all the node and relationship tables.
+    fn count_all_tables(&mut self) -> Result<GraphStats, CodeGraphError> {
+        let mut tables: Vec<(String, String)> = Vec::new();
+        if let Some(result) = self.query("CALL show_tables() RETURN name, type;")? {
+            for row in result {
+                if let [kuzu::Value::String(name), kuzu::Value::String(table_type)] = row.as_slice()
+                {
+                    tables.push((name.clone(), table_type.clone()));
+                }
+            }
+        }
+
+        let mut stats = GraphStats::default();
+        for (name, table_type) in tables {
+            match table_type.as_str() {
+                "NODE" => {
+                    let count = self.count_rows(&format!("MATCH (n:{}) RETURN count(n);", name))?;
+                    stats.nodes.insert(name, count);
+                }
+                "REL" => {
+                    let count =
+                        self.count_rows(&format!("MATCH ()-[e:{}]->() RETURN count(e);", name))?;
+                    stats.edges.insert(name, count);
+                }
+                _ => {}
+            }
         }
+        Ok(stats)
+    }
+
+    /// Run the given statement, which returns a single count.
+    fn count_rows(&mut self, stmt: &str) -> Result<u64, CodeGraphError> {
+        let count = self
+            .query(stmt)?
+            .and_then(|mut result| result.next())
+            .and_then(|row| match row.first() {
+                Some(kuzu::Value::Int64(count)) => Some(*count as u64),
+                _ => None,
+            });
+        Ok(count.unwrap_or(0))
+    }
+
+    /// Mark the given tables as written, whose counts in the cached stats (if any) are stale.
+    fn mark_stale_tables(&mut self, tables: impl IntoIterator<Item = String>) {
+        if self.stats.is_some() {
+            self.stale_tables.extend(tables);
+        }
+    }
+
+    /// Mark all the tables as written, e.g. after deleting nodes along with their edges.
+    fn mark_all_tables_stale(&mut self) {
+        if let Some(stats) = &self.stats {
+            let tables: Vec<String> = stats
+                .nodes
+                .keys()
+                .chain(stats.edges.keys())
+                .cloned()
+                .collect();
+            self.stale_tables.extend(tables);
+        }
+    }
+
+    /// Set the maximum number of rows per `COPY FROM` statement in CSV bulk insertion.
+    ///
+    /// Rows of each node (or edge) type are split into batches of the given size, so that the memory usage
+    /// stays bounded and a failure only affects one batch. 0 means no limit.
+    pub fn set_bulk_insert_batch_size(&mut self, batch_size: usize) {
+        self.bulk_insert_batch_size = batch_size;
+    }
+
+    /// Split the given items into groups by key, and then split each group into batches of the bulk insert batch size.
+    fn into_batches<'a, T>(&self, items: &'a [T], key: impl Fn(&T) -> String) -> Vec<Vec<&'a T>> {
+        let mut grouped_items: IndexMap<String, Vec<&T>> = IndexMap::new();
+        for item in items {
+            grouped_items.entry(key(item)).or_default().push(item);
+        }
+
+        grouped_items
+            .into_values()
+            .flat_map(|group| {
+                group
+                    .chunks(self.bulk_insert_batch_size.max(1))
+                    .map(|chunk| chunk.to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
     }
 
-    fn init(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    fn init(&mut self) -> Result<(), CodeGraphError> {
         if self.initialized {
             return Ok(());
         }
@@ -49,18 +301,14 @@ impl Database {
     }
 
     /// 将解析的节点按类型分组写入JSON文件
-    fn write_nodes_to_json(
-        &self,
-        nodes: &[Node],
-        out_dir: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn write_nodes_to_json(&self, nodes: &[Node], out_dir: &Path) -> Result<(), CodeGraphError> {
         // 按节点类型分组
         let mut grouped_nodes: HashMap<String, Vec<IndexMap<String, serde_json::Value>>> =
             HashMap::new();
 
         for node in nodes {
             let type_key = node.r#type.to_string();
-            let node_dict = node.to_dict();
+            let node_dict = self.node_to_dict(node);
             grouped_nodes
                 .entry(type_key)
                 .or_insert_with(Vec::new)
@@ -88,11 +336,7 @@ impl Database {
     }
 
     /// 将解析的关系按类型分组写入JSON文件
-    fn write_edges_to_json(
-        &self,
-        edges: &[Edge],
-        out_dir: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn write_edges_to_json(&self, edges: &[Edge], out_dir: &Path) -> Result<(), CodeGraphError> {
         // 确保输出目录存在
         std::fs::create_dir_all(out_dir)?;
 
@@ -107,7 +351,7 @@ impl Database {
                 edge.from.r#type.to_string(),
                 edge.to.r#type.to_string()
             );
-            let edge_dict = edge.to_dict();
+            let edge_dict = self.edge_to_dict(edge);
             grouped_edges
                 .entry(key)
                 .or_insert_with(Vec::new)
@@ -135,11 +379,7 @@ impl Database {
     }
 
     /// 将解析的节点按类型分组写入CSV文件
-    fn write_nodes_to_csv(
-        &self,
-        nodes: &[Node],
-        out_dir: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn write_nodes_to_csv(&self, nodes: &[Node], out_dir: &Path) -> Result<(), CodeGraphError> {
         // 确保输出目录存在
         std::fs::create_dir_all(out_dir)?;
 
@@ -147,7 +387,7 @@ impl Database {
         let mut grouped_nodes: HashMap<String, Vec<IndexMap<String, serde_json::Value>>> =
             HashMap::new();
         for node in nodes {
-            let node_dict = node.to_dict();
+            let node_dict = self.node_to_dict(node);
             grouped_nodes
                 .entry(node.r#type.to_string())
                 .or_insert_with(Vec::new)
@@ -207,11 +447,7 @@ impl Database {
     }
 
     /// 将解析的关系按类型分组写入CSV文件
-    fn write_edges_to_csv(
-        &self,
-        edges: &[Edge],
-        out_dir: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn write_edges_to_csv(&self, edges: &[Edge], out_dir: &Path) -> Result<(), CodeGraphError> {
         // 确保输出目录存在
         std::fs::create_dir_all(out_dir)?;
 
@@ -225,7 +461,7 @@ impl Database {
                 edge.from.r#type.to_string(),
                 edge.to.r#type.to_string()
             );
-            let edge_dict = edge.to_dict();
+            let edge_dict = self.edge_to_dict(edge);
             grouped_edges
                 .entry(key)
                 .or_insert_with(Vec::new)
@@ -283,16 +519,14 @@ impl Database {
         Ok(())
     }
 
-    pub fn bulk_insert_nodes(
-        &mut self,
-        nodes: &Vec<Node>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn bulk_insert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), CodeGraphError> {
         self.init()?;
 
         let temp_dir = tempfile::tempdir()?;
         let temp_dir_path = temp_dir.path();
         log::info!("bulk-insert {} nodes", nodes.len());
         self.write_nodes_to_json(nodes, &temp_dir_path)?;
+        self.mark_stale_tables(nodes.iter().map(node_table_name));
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
@@ -320,21 +554,42 @@ impl Database {
         Ok(())
     }
 
+    /// Bulk insert nodes via CSV files, and return the number of `COPY FROM` statements executed.
     pub fn bulk_insert_nodes_via_csv(
         &mut self,
         nodes: &Vec<Node>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        self.init()?;
-
-        let temp_dir = tempfile::tempdir()?;
-        let temp_dir_path = temp_dir.path();
+    ) -> Result<usize, CodeGraphError> {
         if log::log_enabled!(log::Level::Trace) {
             for node in nodes {
                 log::trace!("node: {:?}", node);
             }
         }
         log::info!("bulk-insert {} nodes", nodes.len());
+
+        if self.bulk_insert_batch_size == 0 {
+            // Insert all nodes at once, with one `COPY FROM` statement per node type.
+            return self.copy_nodes_from_csv(nodes);
+        }
+
+        let mut copy_count = 0;
+        for batch in self.into_batches(nodes, |node| node.r#type.to_string()) {
+            let batch: Vec<Node> = batch.into_iter().cloned().collect();
+            copy_count += self.copy_nodes_from_csv(&batch)?;
+        }
+
+        Ok(copy_count)
+    }
+
+    /// Write the given nodes to CSV files and copy them into the database, one `COPY FROM` statement per node type.
+    fn copy_nodes_from_csv(&mut self, nodes: &[Node]) -> Result<usize, CodeGraphError> {
+        self.init()?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let temp_dir_path = temp_dir.path();
         self.write_nodes_to_csv(nodes, &temp_dir_path)?;
+        self.mark_stale_tables(nodes.iter().map(node_table_name));
+
+        let mut copy_count = 0;
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
@@ -358,6 +613,7 @@ impl Database {
                         );
                         log::trace!("bulk-insert query: {query}");
                         conn.query(query.as_str())?;
+                        copy_count += 1;
                     }
                 }
             }
@@ -365,13 +621,10 @@ impl Database {
 
         temp_dir.close()?;
 
-        Ok(())
+        Ok(copy_count)
     }
 
-    pub fn bulk_insert_edges(
-        &mut self,
-        edges: &Vec<Edge>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn bulk_insert_edges(&mut self, edges: &Vec<Edge>) -> Result<(), CodeGraphError> {
         self.init()?;
 
         let temp_dir = tempfile::tempdir()?;
@@ -383,61 +636,59 @@ impl Database {
         );
         log::info!("bulk-insert {} edges", edges.len());
         self.write_edges_to_json(edges, &temp_dir_path)?;
+        self.mark_stale_tables(edges.iter().map(rel_table_name));
 
+        let edge_files = Self::edge_files(temp_dir_path, "json")?;
+
+        let mut failures: Vec<String> = Vec::new();
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
 
-            let node_files = std::fs::read_dir(&temp_dir_path)?;
-            for entry in node_files {
-                let entry = entry?;
-                let file_path = entry.path();
-
-                if let Some(extension) = file_path.extension() {
-                    if extension == "json" {
-                        let file_stem = file_path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .ok_or("Invalid file name")?;
-
-                        let parts: Vec<&str> = file_stem.split('_').collect();
-                        if parts.len() != 3 {
-                            return Err(format!(
-                                "Invalid filename format for edges file: {}",
-                                file_stem
-                            )
-                            .into());
-                        }
-
-                        let table_name = parts[0].to_uppercase();
-                        let from_type = to_title_case(parts[1]);
-                        let to_type = to_title_case(parts[2]);
-
-                        let query = format!(
-                            r#"COPY {} FROM {:?} (from={:?}, to={:?})"#,
-                            table_name, file_path, from_type, to_type
-                        );
-                        match conn.query(query.as_str()) {
-                            Err(e) => {
-                                log::error!("Failed to copy file {} :{}", file_path.display(), e);
-                                log::error!("Error query: {}", query);
-                            }
-                            Ok(_) => {}
-                        }
-                    }
+            for (file_path, table_name, from_type, to_type) in edge_files {
+                let query = format!(
+                    r#"COPY {} FROM {:?} (from={:?}, to={:?})"#,
+                    table_name, file_path, from_type, to_type
+                );
+                if let Err(e) = conn.query(query.as_str()) {
+                    log::error!("Failed to copy file {} :{}", file_path.display(), e);
+                    log::error!("Error query: {}", query);
+                    failures.push(format!("{}: {}", file_path.display(), e));
                 }
             }
         }
 
         temp_dir.close()?;
 
+        Self::copy_failures_to_result(failures)?;
         Ok(())
     }
 
     /// 批量通过CSV文件导入关系数据
+    ///
+    /// Returns the number of `COPY FROM` statements executed.
     pub fn bulk_insert_edges_via_csv(
         &mut self,
         edges: &Vec<Edge>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<usize, CodeGraphError> {
+        log::info!("bulk-insert {} edges", edges.len());
+
+        if self.bulk_insert_batch_size == 0 {
+            // Insert all edges at once, with one `COPY FROM` statement per edge type.
+            return self.copy_edges_from_csv(edges);
+        }
+
+        let mut copy_count = 0;
+        for batch in self.into_batches(edges, |edge| format!("{}_{}", edge.r#type, edge.from_to()))
+        {
+            let batch: Vec<Edge> = batch.into_iter().cloned().collect();
+            copy_count += self.copy_edges_from_csv(&batch)?;
+        }
+
+        Ok(copy_count)
+    }
+
+    /// Write the given edges to CSV files and copy them into the database, one `COPY FROM` statement per edge type.
+    fn copy_edges_from_csv(&mut self, edges: &[Edge]) -> Result<usize, CodeGraphError> {
         self.init()?;
 
         let temp_dir = tempfile::tempdir()?;
@@ -447,62 +698,101 @@ impl Database {
             edges.len(),
             temp_dir_path
         );
-        log::info!("bulk-insert {} edges", edges.len());
         self.write_edges_to_csv(edges, &temp_dir_path)?;
+        self.mark_stale_tables(edges.iter().map(rel_table_name));
+
+        // Validate all the file names before copying any of them.
+        let edge_files = Self::edge_files(temp_dir_path, "csv")?;
+
+        let mut copy_count = 0;
+        let mut failures: Vec<String> = Vec::new();
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
 
-            let node_files = std::fs::read_dir(&temp_dir_path)?;
-            for entry in node_files {
-                let entry = entry?;
-                let file_path = entry.path();
+            for (file_path, table_name, from_type, to_type) in edge_files {
+                // Quoted newlines are not supported in parallel CSV reader, thus we have to specify PARALLEL=FALSE in the options.
+                let query = format!(
+                    r#"COPY {} FROM {:?} (from={:?}, to={:?}, HEADER=true, PARALLEL=false)"#,
+                    table_name, file_path, from_type, to_type
+                );
+                match conn.query(query.as_str()) {
+                    Err(e) => {
+                        log::error!("Failed to copy file {} :{}", file_path.display(), e);
+                        log::error!("Error query: {}", query);
+                        failures.push(format!("{}: {}", file_path.display(), e));
+                    }
+                    Ok(_) => copy_count += 1,
+                }
+            }
+        }
 
-                if let Some(extension) = file_path.extension() {
-                    if extension == "csv" {
-                        let file_stem = file_path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .ok_or("Invalid file name")?;
+        temp_dir.close()?;
 
-                        let parts: Vec<&str> = file_stem.split('_').collect();
-                        if parts.len() != 3 {
-                            return Err(format!(
-                                "Invalid filename format for edges file: {}",
-                                file_stem
-                            )
-                            .into());
-                        }
+        Self::copy_failures_to_result(failures)?;
+        Ok(copy_count)
+    }
 
-                        let table_name = parts[0].to_uppercase();
-                        let from_type = to_title_case(parts[1]);
-                        let to_type = to_title_case(parts[2]);
+    /// Returns the edge files with the given extension in `dir`, along with the relationship table, and the
+    /// from and to node tables parsed from their names (e.g. `contains_File_Function.csv`), ordered by file name.
+    ///
+    /// All the file names are validated up front, so nothing is copied if any of them is malformed.
+    fn edge_files(
+        dir: &Path,
+        extension: &str,
+    ) -> Result<Vec<(PathBuf, String, String, String)>, CodeGraphError> {
+        let mut edge_files: Vec<(PathBuf, String, String, String)> = Vec::new();
+        let mut invalid_names: Vec<String> = Vec::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let file_path = entry?.path();
+            if file_path.extension().map_or(true, |ext| ext != extension) {
+                continue;
+            }
 
-                        // Quoted newlines are not supported in parallel CSV reader, thus we have to specify PARALLEL=FALSE in the options.
-                        let query = format!(
-                            r#"COPY {} FROM {:?} (from={:?}, to={:?}, HEADER=true, PARALLEL=false)"#,
-                            table_name, file_path, from_type, to_type
-                        );
-                        match conn.query(query.as_str()) {
-                            Err(e) => {
-                                println!("Failed to copy file {} :{}", file_path.display(), e);
-                                println!("Error query: {}", query);
-                            }
-                            Ok(_) => {}
-                        }
-                    }
-                }
+            let file_stem = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .ok_or("Invalid file name")?;
+            let parts: Vec<&str> = file_stem.split('_').collect();
+            if parts.len() != 3 {
+                invalid_names.push(file_stem.to_string());
+                continue;
             }
+
+            let table_name = parts[0].to_uppercase();
+            let from_type = to_title_case(parts[1]);
+            let to_type = to_title_case(parts[2]);
+            edge_files.push((file_path, table_name, from_type, to_type));
         }
 
-        temp_dir.close()?;
+        if !invalid_names.is_empty() {
+            invalid_names.sort();
+            return Err(format!(
+                "Invalid filename format for edges files: {}",
+                invalid_names.join(", ")
+            )
+            .into());
+        }
 
-        Ok(())
+        edge_files.sort();
+        Ok(edge_files)
     }
 
-    fn to_merge_data(
-        m: &IndexMap<String, serde_json::Value>,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    /// Returns an error with all the failures of `COPY FROM` statements, if any.
+    fn copy_failures_to_result(failures: Vec<String>) -> Result<(), CodeGraphError> {
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Failed to copy {} edges file(s):\n{}",
+            failures.len(),
+            failures.join("\n")
+        )
+        .into())
+    }
+
+    fn to_merge_data(m: &IndexMap<String, serde_json::Value>) -> Result<String, CodeGraphError> {
         // 将 HashMap 中的键值对转换为 Cypher 查询中的键值对字符串
         let mut parts = Vec::new();
 
@@ -521,75 +811,86 @@ impl Database {
         Ok(parts.join(", "))
     }
 
-    fn to_set_data(
+    /// Convert the given key-value pairs into a parameterized `SET` clause and its parameters.
+    ///
+    /// Each key is bound to the parameter of the same name, so the clause only depends on the keys,
+    /// which makes it suitable as (part of) the key of a `StatementCache`.
+    fn to_set_params(
         tag: &str,
         pk: &str,
         m: &IndexMap<String, serde_json::Value>,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        // 将 HashMap 中的键值对转换为 Cypher 查询中的键值对字符串
+    ) -> Result<(String, Vec<(String, kuzu::Value)>), CodeGraphError> {
         let mut parts = Vec::new();
+        let mut params = Vec::new();
 
         for (key, value) in m {
-            let formatted_value = match value {
-                serde_json::Value::String(s) => string_repr(s), //repr_string(s),
-                serde_json::Value::Number(n) => n.to_string(),
-                serde_json::Value::Bool(b) => b.to_string(),
-                serde_json::Value::Array(_) => serde_json::to_string(value)?,
-                serde_json::Value::Object(_) => serde_json::to_string(value)?,
-                serde_json::Value::Null => "null".to_string(),
-            };
             // Ignore primary key to avoid errors:
             //
             // Runtime exception: Found duplicated primary key value '<pk>',
             // which violates the uniqueness constraint of the primary key column.
             if key != pk {
-                parts.push(format!("{}.{} = {}", tag, key, formatted_value));
+                parts.push(format!("{}.{} = ${}", tag, key, key));
+                params.push((key.clone(), to_kuzu_value(value)?));
             }
         }
 
-        Ok(parts.join(", "))
+        Ok((parts.join(", "), params))
     }
 
-    pub fn upsert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Insert or update the given nodes.
+    ///
+    /// Each statement is committed on return (i.e. auto-commit), and all the connections share the same
+    /// database, so the nodes are visible to any subsequent query without an explicit flush.
+    pub fn upsert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), CodeGraphError> {
         self.init()?;
 
         log::info!("upsert {} nodes", nodes.len());
+        self.mark_stale_tables(nodes.iter().map(node_table_name));
 
         // 每次需要连接时创建新的连接，避免生命周期问题
         if let Some(db) = &self.db {
-            let conn = kuzu::Connection::new(db)?;
+            // Nodes of the same type share the same query, which is prepared only once.
+            let mut cache = StatementCache::new(db)?;
 
             for node in nodes {
-                let table_name = to_title_case(node.r#type.to_string().as_str());
-                let node_dict = node.to_dict();
-                let set_data = Self::to_set_data(&"n", &"name", &node_dict)?;
+                let table_name = node_table_name(node);
+                let node_dict = self.node_to_dict(node);
+                let (set_data, mut params) = Self::to_set_params("n", "name", &node_dict)?;
                 let query = format!(
                     r#"
-MERGE (n:{} {{ name: "{}" }})
+MERGE (n:{} {{ name: $name }})
 ON CREATE SET {}
 ON MATCH SET {}
 "#,
-                    table_name, node.name, set_data, set_data
+                    table_name, set_data, set_data
                 );
-                log::debug!("upsert_nodes query: {}", query);
-                conn.query(query.as_str())?;
+                log::debug!("upsert_nodes node: {}", node.name);
+                params.push((
+                    "name".to_string(),
+                    kuzu::Value::String(self.node_key(&node.name)),
+                ));
+                cache.execute(query, params)?;
             }
+            self.prepare_count += cache.len();
         }
 
         Ok(())
     }
 
-    pub fn upsert_edges(&mut self, rels: &Vec<Edge>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Insert or update the given edges, which are visible to any subsequent query like in `upsert_nodes`.
+    pub fn upsert_edges(&mut self, rels: &Vec<Edge>) -> Result<(), CodeGraphError> {
         self.init()?;
 
         log::info!("upsert {} edges", rels.len());
+        self.mark_stale_tables(rels.iter().map(rel_table_name));
 
         // 每次需要连接时创建新的连接，避免生命周期问题
         if let Some(db) = &self.db {
-            let conn = kuzu::Connection::new(db)?;
+            // Edges of the same type (and endpoint types) share the same query, which is prepared only once.
+            let mut cache = StatementCache::new(db)?;
 
             for rel in rels {
-                let table_name = rel.r#type.to_string().to_ascii_uppercase();
+                let table_name = rel_table_name(rel);
                 let _from_to = rel.from_to();
                 let from_to = _from_to.split('_').collect::<Vec<&str>>();
                 let from_node_table_name = to_title_case(from_to[0]);
@@ -600,36 +901,199 @@ ON MATCH SET {}
                     .filter(|(k, _)| *k != "from" && *k != "to")
                     .map(|(k, v)| (k.clone(), v.clone()))
                     .collect();
-                let set_data = Self::to_set_data(&"e", &"", &rel_dict)?;
+                let (set_data, mut params) = Self::to_set_params("e", "", &rel_dict)?;
                 let query = format!(
                     r#"
 MATCH (a:{}), (b:{})
-WHERE a.name = '{}' AND b.name = '{}'
+WHERE a.name = $from AND b.name = $to
 MERGE (a)-[e:{}]->(b)
 ON CREATE SET {}
 ON MATCH SET {}
                 "#,
-                    from_node_table_name,
-                    to_node_table_name,
-                    rel.from.name,
-                    rel.to.name,
-                    table_name,
-                    set_data,
-                    set_data,
+                    from_node_table_name, to_node_table_name, table_name, set_data, set_data,
                 );
-                log::debug!("upsert_edges query: {}", query);
-                conn.query(&query)?;
+                log::debug!("upsert_edges edge: {} -> {}", rel.from.name, rel.to.name);
+                params.push((
+                    "from".to_string(),
+                    kuzu::Value::String(self.node_key(&rel.from.name)),
+                ));
+                params.push((
+                    "to".to_string(),
+                    kuzu::Value::String(self.node_key(&rel.to.name)),
+                ));
+                cache.execute(query, params)?;
             }
+            self.prepare_count += cache.len();
         }
 
         Ok(())
     }
 
-    pub fn query(
+    /// Set the names of the imported files on the given file nodes.
+    pub fn set_imported_files(
         &mut self,
-        stmt: &str,
-    ) -> Result<Option<kuzu::QueryResult>, Box<dyn std::error::Error>> {
+        imported_files: &BTreeMap<String, Vec<String>>,
+    ) -> Result<(), CodeGraphError> {
+        self.set_file_string_lists("imported_files", imported_files)
+    }
+
+    /// Set the (encoded) unresolved imports on the given file nodes.
+    pub fn set_unresolved_imports(
+        &mut self,
+        unresolved_imports: &BTreeMap<String, Vec<String>>,
+    ) -> Result<(), CodeGraphError> {
+        self.set_file_string_lists("unresolved_imports", unresolved_imports)
+    }
+
+    fn set_file_string_lists(
+        &mut self,
+        property: &str,
+        values: &BTreeMap<String, Vec<String>>,
+    ) -> Result<(), CodeGraphError> {
+        self.init()?;
+
+        if let Some(db) = &self.db {
+            let conn = kuzu::Connection::new(db)?;
+
+            for (file_name, strings) in values {
+                let strings_array = format!(
+                    "[{}]",
+                    strings
+                        .iter()
+                        .map(|s| string_repr(s))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                let query = format!(
+                    r#"
+MATCH (f:File)
+WHERE f.name = {}
+SET f.{} = CAST({} AS STRING[])
+"#,
+                    string_repr(&self.node_key(file_name)),
+                    property,
+                    strings_array,
+                );
+                log::debug!("set_{} query: {}", property, query);
+                conn.query(query.as_str())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the metadata of the node with the given name, or None if there is no such node.
+    ///
+    /// Directory nodes have no metadata.
+    pub fn node_metadata(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<BTreeMap<String, String>>, CodeGraphError> {
+        let stmt = format!(
+            "MATCH (n:{}) WHERE n.name = {} RETURN n;",
+            METADATA_NODE_LABELS,
+            string_repr(&self.node_key(name)),
+        );
+        let nodes = self.query_nodes(&stmt)?;
+        Ok(nodes.into_iter().next().map(|node| node.metadata))
+    }
+
+    /// Get the (outgoing, incoming) edges of the node with the given name, each ordered by the other node.
+    pub fn incident_edges(&mut self, name: &str) -> Result<(Vec<Edge>, Vec<Edge>), CodeGraphError> {
+        let key = string_repr(&self.node_key(name));
+
+        let stmt = format!(
+            "MATCH (a)-[e]->(b) WHERE a.name = {} RETURN a.full_name, b.full_name, e;",
+            key
+        );
+        let mut outgoing = self.query_edges(&stmt)?;
+        outgoing.sort_by_key(|edge| (edge.to.name.clone(), edge.r#type.to_string()));
+
+        let stmt = format!(
+            "MATCH (a)-[e]->(b) WHERE b.name = {} RETURN a.full_name, b.full_name, e;",
+            key
+        );
+        let mut incoming = self.query_edges(&stmt)?;
+        incoming.sort_by_key(|edge| (edge.from.name.clone(), edge.r#type.to_string()));
+
+        Ok((outgoing, incoming))
+    }
+
+    /// Replace the metadata of the node with the given name.
+    pub fn set_node_metadata(
+        &mut self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<(), CodeGraphError> {
+        self.init()?;
+
+        if let Some(db) = &self.db {
+            let conn = kuzu::Connection::new(db)?;
+            let query = format!(
+                "MATCH (n:{}) WHERE n.name = {} SET n.metadata = {}",
+                METADATA_NODE_LABELS,
+                string_repr(&self.node_key(name)),
+                string_repr(&serde_json::to_string(metadata)?),
+            );
+            log::debug!("set_node_metadata query: {}", query);
+            conn.query(query.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the definition nodes whose doc comments contain the given text.
+    pub fn search_docs(&mut self, text: &str) -> Result<Vec<Node>, CodeGraphError> {
+        let stmt = format!(
+            "MATCH (n:Interface:Class:Function:OtherType:Namespace) WHERE n.doc_comment CONTAINS {} RETURN n;",
+            string_repr(text),
+        );
+        let mut nodes = self.query_nodes(&stmt)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Update the fields derived from the name and the code of the node with the given name.
+    ///
+    /// The skeleton code is left unchanged if it is None, or if the node has no such field (e.g. directories).
+    pub fn set_derived_fields(
+        &mut self,
+        name: &str,
+        short_name: &str,
+        skeleton_code: Option<&str>,
+    ) -> Result<(), CodeGraphError> {
+        self.init()?;
+
+        if let Some(db) = &self.db {
+            let conn = kuzu::Connection::new(db)?;
+            let key = string_repr(&self.node_key(name));
+            let mut queries = vec![format!(
+                "MATCH (n) WHERE n.name = {} SET n.short_name = {}",
+                key,
+                string_repr(short_name),
+            )];
+            if let Some(skeleton_code) = skeleton_code {
+                queries.push(format!(
+                    "MATCH (n:{}) WHERE n.name = {} SET n.skeleton_code = {}",
+                    METADATA_NODE_LABELS,
+                    key,
+                    string_repr(skeleton_code),
+                ));
+            }
+            for query in queries {
+                log::debug!("set_derived_fields query: {}", query);
+                conn.query(query.as_str())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn query(&mut self, stmt: &str) -> Result<Option<kuzu::QueryResult>, CodeGraphError> {
         self.init()?;
+        if is_write_statement(stmt) {
+            self.mark_all_tables_stale();
+        }
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
@@ -640,19 +1104,75 @@ ON MATCH SET {}
         Ok(None)
     }
 
-    pub fn query_nodes(&mut self, stmt: &str) -> Result<Vec<Node>, Box<dyn std::error::Error>> {
+    /// Execute the given parameterized statement, where the parameters (e.g. `$name`) are bound to
+    /// the given values, instead of being formatted into the statement.
+    pub fn query_params(
+        &mut self,
+        stmt: &str,
+        params: HashMap<String, kuzu::Value>,
+    ) -> Result<Option<kuzu::QueryResult>, CodeGraphError> {
         self.init()?;
-
-        let mut nodes: Vec<Node> = vec![];
+        if is_write_statement(stmt) {
+            self.mark_all_tables_stale();
+        }
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
-            let result = conn.query(stmt)?;
+            let mut prepared = conn.prepare(stmt)?;
+            let params = params
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.clone()))
+                .collect();
+            let result = conn.execute(&mut prepared, params)?;
+            return Ok(Some(result));
+        }
+
+        Ok(None)
+    }
+
+    pub fn query_nodes(&mut self, stmt: &str) -> Result<Vec<Node>, CodeGraphError> {
+        self.query_nodes_params(stmt, HashMap::new())
+    }
+
+    /// Query the nodes with the given parameterized statement (see `query_params`).
+    pub fn query_nodes_params(
+        &mut self,
+        stmt: &str,
+        params: HashMap<String, kuzu::Value>,
+    ) -> Result<Vec<Node>, CodeGraphError> {
+        let mut nodes: Vec<Node> = vec![];
+        self.for_each_node_params(stmt, params, |node| {
+            nodes.push(node);
+            Ok(())
+        })?;
+        Ok(nodes)
+    }
+
+    /// Query the nodes like `query_nodes`, but pass each node to `f` as soon as its row is read, instead of
+    /// collecting all of them, e.g. to stream a large result.
+    pub fn for_each_node<F>(&mut self, stmt: &str, f: F) -> Result<(), CodeGraphError>
+    where
+        F: FnMut(Node) -> Result<(), CodeGraphError>,
+    {
+        self.for_each_node_params(stmt, HashMap::new(), f)
+    }
+
+    fn for_each_node_params<F>(
+        &mut self,
+        stmt: &str,
+        params: HashMap<String, kuzu::Value>,
+        mut f: F,
+    ) -> Result<(), CodeGraphError>
+    where
+        F: FnMut(Node) -> Result<(), CodeGraphError>,
+    {
+        if let Some(result) = self.query_params(stmt, params)? {
             for row in result {
                 match &row[0] {
                     kuzu::Value::Node(node) => {
                         let props = node.get_properties();
                         let mut node = Node::from_type_and_name(NodeType::Unparsed, "".to_string());
+                        let mut full_name: Option<String> = None;
                         for (prop_name, prop_value) in props {
                             match prop_name.as_str() {
                                 "name" => {
@@ -671,15 +1191,88 @@ ON MATCH SET {}
                                 "code" => {
                                     node.code = prop_value.to_string();
                                 }
+                                "skeleton_code" => {
+                                    node.skeleton_code = prop_value.to_string();
+                                }
+                                "doc_comment" => {
+                                    node.doc_comment = prop_value.to_string();
+                                }
+                                "accessor_kind" => {
+                                    node.accessor_kind =
+                                        prop_value.to_string().parse().unwrap_or_default();
+                                }
+                                "exported" => {
+                                    if let kuzu::Value::Bool(exported) = prop_value {
+                                        node.exported = *exported;
+                                    }
+                                }
+                                "is_static" => {
+                                    if let kuzu::Value::Bool(is_static) = prop_value {
+                                        node.is_static = *is_static;
+                                    }
+                                }
+                                "receiver" => {
+                                    node.receiver = prop_value.to_string();
+                                }
+                                "grammar_version" => {
+                                    node.grammar_version = prop_value.to_string();
+                                }
+                                "content_hash" => {
+                                    node.content_hash = prop_value.to_string();
+                                }
+                                "full_name" => {
+                                    if let kuzu::Value::String(name) = prop_value {
+                                        full_name = Some(name.clone());
+                                    }
+                                }
+                                "imported_files" => {
+                                    if let kuzu::Value::List(_, files) = prop_value {
+                                        node.imported_files =
+                                            files.iter().map(|f| f.to_string()).collect();
+                                    }
+                                }
+                                "generated" => {
+                                    if let kuzu::Value::Bool(generated) = prop_value {
+                                        node.generated = *generated;
+                                    }
+                                }
+                                "metadata" => {
+                                    if let kuzu::Value::String(metadata) = prop_value {
+                                        node.metadata =
+                                            serde_json::from_str(metadata).unwrap_or_default();
+                                    }
+                                }
+                                "unresolved_imports" => {
+                                    if let kuzu::Value::List(_, imports) = prop_value {
+                                        node.unresolved_imports =
+                                            imports.iter().map(|i| i.to_string()).collect();
+                                    }
+                                }
                                 "start_line" => {
                                     node.start_line = prop_value.to_string().parse().unwrap_or(0);
                                 }
+                                "function_count" => {
+                                    node.summary.function_count =
+                                        prop_value.to_string().parse().unwrap_or(0);
+                                }
+                                "class_count" => {
+                                    node.summary.class_count =
+                                        prop_value.to_string().parse().unwrap_or(0);
+                                }
+                                "import_count" => {
+                                    node.summary.import_count =
+                                        prop_value.to_string().parse().unwrap_or(0);
+                                }
                                 "end_line" => {
                                     node.end_line = prop_value.to_string().parse().unwrap_or(0);
                                 }
                                 _ => {}
                             }
                         }
+                        // Prefer the human-readable name, in case the primary key is hashed.
+                        if let Some(full_name) = full_name {
+                            node.name = full_name;
+                        }
                         /*
                         if let kuzu::Value::String(name) = &props[0].1 {
                             node.name = name.to_string();
@@ -700,19 +1293,31 @@ ON MATCH SET {}
                             node.end_line = *line as usize;
                         }
                         */
-                        nodes.push(node);
+                        f(node)?;
                     }
                     _ => println!("Unrecoginized node type"),
                 }
             }
         }
-        Ok(nodes)
+        Ok(())
     }
 
-    pub fn query_edges(&mut self, stmt: &str) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
-        self.init()?;
-
+    pub fn query_edges(&mut self, stmt: &str) -> Result<Vec<Edge>, CodeGraphError> {
         let mut edges: Vec<Edge> = vec![];
+        self.for_each_edge(stmt, |edge| {
+            edges.push(edge);
+            Ok(())
+        })?;
+        Ok(edges)
+    }
+
+    /// Query the edges like `query_edges`, but pass each edge to `f` as soon as its row is read, instead of
+    /// collecting all of them, e.g. to stream a large result.
+    pub fn for_each_edge<F>(&mut self, stmt: &str, mut f: F) -> Result<(), CodeGraphError>
+    where
+        F: FnMut(Edge) -> Result<(), CodeGraphError>,
+    {
+        self.init()?;
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
@@ -733,6 +1338,7 @@ ON MATCH SET {}
                         let mut typ: String = "".to_string();
                         let mut import: Option<String> = None;
                         let mut alias: Option<String> = None;
+                        let mut properties = IndexMap::new();
                         for (prop_name, prop_value) in props {
                             match prop_name.as_str() {
                                 "type" => {
@@ -744,6 +1350,12 @@ ON MATCH SET {}
                                 "alias" => {
                                     alias = Some(prop_value.to_string());
                                 }
+                                "properties" => {
+                                    if let kuzu::Value::String(properties_json) = prop_value {
+                                        properties = serde_json::from_str(properties_json)
+                                            .unwrap_or_default();
+                                    }
+                                }
                                 _ => {}
                             }
                         }
@@ -786,49 +1398,76 @@ ON MATCH SET {}
                             to: Node::from_type_and_name(to_node_type, to_node_name),
                             import: import,
                             alias: alias,
+                            properties: properties,
                         };
 
-                        edges.push(edge);
+                        f(edge)?;
                     }
                     _ => println!("无法识别的关系类型"),
                 }
             }
         }
-        Ok(edges)
+        Ok(())
     }
 
-    pub fn delete_nodes(&mut self, names: &Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn delete_nodes(&mut self, names: &Vec<String>) -> Result<(), CodeGraphError> {
         if names.is_empty() {
             return Ok(());
         }
 
         self.init()?;
+        self.mark_all_tables_stale();
 
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
 
             // Delete nodes and all of their edges
             // see https://docs.kuzudb.com/cypher/data-manipulation-clauses/delete/#detach-delete.
-            let query = format!("MATCH (n) WHERE n.name IN {:?} DETACH DELETE n", &names,);
+            let keys: Vec<String> = names.iter().map(|name| self.node_key(name)).collect();
+            let query = format!("MATCH (n) WHERE n.name IN {:?} DETACH DELETE n", &keys,);
             conn.query(&query)?;
         }
 
         Ok(())
     }
 
-    pub fn clean(&mut self, delete: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// Delete all the out-going edges of the nodes with the given names.
+    pub fn delete_outgoing_edges(&mut self, names: &[String]) -> Result<(), CodeGraphError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let keys: Vec<String> = names
+            .iter()
+            .map(|name| util::cypher_string_literal(&self.node_key(name)))
+            .collect();
+        let stmt = format!(
+            "MATCH (a)-[e]->() WHERE a.name IN [{}] DELETE e;",
+            keys.join(", ")
+        );
+        log::debug!("delete out-going edges: {}", stmt);
+        self.query(&stmt)?;
+
+        Ok(())
+    }
+
+    pub fn clean(&mut self, delete: bool) -> Result<(), CodeGraphError> {
         if delete {
-            // Delete the entire database directory.
+            // Close the database before deleting the entire database directory.
+            self.db = None;
             if self.db_path.exists() {
                 std::fs::remove_dir_all(&self.db_path)?;
             }
 
             // Need to reinitialize the database later.
             self.initialized = false;
+            self.stats = None;
+            self.stale_tables.clear();
             return Ok(());
         }
 
         // Remove all records in the database.
+        self.mark_all_tables_stale();
         if let Some(db) = &self.db {
             let conn = kuzu::Connection::new(db)?;
             let _ = conn.query("MATCH (n) DETACH DELETE n")?;
@@ -873,6 +1512,36 @@ fn string_repr(s: &str) -> String {
     result
 }
 
+/// Convert a value of `Node::to_dict` or `Edge::to_dict` into a query parameter of the matching column type.
+fn to_kuzu_value(value: &serde_json::Value) -> Result<kuzu::Value, CodeGraphError> {
+    Ok(match value {
+        serde_json::Value::String(s) => kuzu::Value::String(s.clone()),
+        // All the numeric columns (i.e. line numbers and counts) are UINT32.
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .map(kuzu::Value::UInt32)
+            .ok_or_else(|| format!("Unsupported number value: {}", n))?,
+        serde_json::Value::Bool(b) => kuzu::Value::Bool(*b),
+        serde_json::Value::Array(items) => kuzu::Value::List(
+            kuzu::LogicalType::String,
+            items.iter().map(to_kuzu_value).collect::<Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(_) => kuzu::Value::String(serde_json::to_string(value)?),
+        serde_json::Value::Null => kuzu::Value::Null(kuzu::LogicalType::String),
+    })
+}
+
+/// Get the name of the node table of the given node, e.g. `Function`.
+fn node_table_name(node: &Node) -> String {
+    to_title_case(node.r#type.to_string().as_str())
+}
+
+/// Get the name of the relationship table of the given edge, e.g. `CALLS`.
+fn rel_table_name(edge: &Edge) -> String {
+    edge.r#type.to_string().to_ascii_uppercase()
+}
+
 fn to_title_case(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut capitalize_next = true;
@@ -897,6 +1566,16 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_is_write_statement() {
+        assert!(!is_write_statement("MATCH (n:File) RETURN count(n);"));
+        assert!(!is_write_statement("CALL show_tables() RETURN name, type;"));
+        assert!(is_write_statement("MATCH (a)-[e]->() DELETE e;"));
+        assert!(is_write_statement("match (n) detach delete n"));
+        assert!(is_write_statement("MATCH (n:File) SET n.generated = true"));
+        assert!(is_write_statement("COPY File FROM 'nodes.csv'"));
+    }
+
     #[test]
     fn test_query() {}
 
@@ -912,6 +1591,7 @@ mod tests {
             to: Node::from_type_and_name(NodeType::Function, "func1".to_string()),
             import: None,
             alias: None,
+            properties: IndexMap::new(),
         }];
         let mut db = Database::new(PathBuf::from("db"));
         db.upsert_nodes(&nodes).unwrap();
@@ -929,6 +1609,91 @@ mod tests {
         db.clean(false).unwrap();
     }
 
+    #[test]
+    fn test_query_edges_properties() {
+        let nodes = vec![
+            Node::from_type_and_name(NodeType::Function, "caller".to_string()),
+            Node::from_type_and_name(NodeType::Function, "callee".to_string()),
+        ];
+        let mut properties = IndexMap::new();
+        properties.insert("line".to_string(), serde_json::json!(42));
+        properties.insert("type_only".to_string(), serde_json::json!(false));
+        let rels = vec![Edge {
+            r#type: EdgeType::Calls,
+            from: nodes[0].clone(),
+            to: nodes[1].clone(),
+            import: None,
+            alias: None,
+            properties: properties.clone(),
+        }];
+        let mut db = Database::new(PathBuf::from("db_edge_properties"));
+        db.upsert_nodes(&nodes).unwrap();
+        db.upsert_edges(&rels).unwrap();
+
+        let existing_rels = db
+            .query_edges("MATCH (a)-[e:CALLS]->(b) RETURN a.name, b.name, e")
+            .unwrap();
+        assert_eq!(existing_rels.len(), 1);
+        assert_eq!(existing_rels[0].properties, properties);
+
+        db.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_upsert_nodes_reuses_prepared_statements() {
+        let mut nodes: Vec<Node> = (0..3)
+            .map(|i| Node::from_type_and_name(NodeType::Function, format!("func{}", i)))
+            .collect();
+        nodes.push(Node::from_type_and_name(
+            NodeType::File,
+            "file1".to_string(),
+        ));
+        let rels: Vec<Edge> = (0..3)
+            .map(|i| Edge {
+                r#type: EdgeType::Contains,
+                from: nodes[3].clone(),
+                to: nodes[i].clone(),
+                import: None,
+                alias: None,
+                properties: IndexMap::new(),
+            })
+            .collect();
+        let mut db = Database::new(PathBuf::from("db_prepared_statements"));
+        db.clean(true).unwrap();
+
+        // One statement for functions and one for files.
+        db.upsert_nodes(&nodes).unwrap();
+        assert_eq!(db.prepare_count(), 2);
+        // One statement for the file-to-function edges.
+        db.upsert_edges(&rels).unwrap();
+        assert_eq!(db.prepare_count(), 3);
+
+        // Upserting again updates the existing nodes instead of duplicating them.
+        nodes[0].code = "func func0() {}".to_string();
+        db.upsert_nodes(&nodes).unwrap();
+
+        let mut node_names: Vec<_> = db
+            .query_nodes("MATCH (n) RETURN n")
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        node_names.sort();
+        assert_eq!(node_names, ["file1", "func0", "func1", "func2"]);
+
+        let func0 = db
+            .query_nodes("MATCH (n:Function) WHERE n.name = 'func0' RETURN n")
+            .unwrap();
+        assert_eq!(func0[0].code, "func func0() {}");
+
+        let existing_rels = db
+            .query_edges("MATCH (a)-[e:CONTAINS]->(b) RETURN a.name, b.name, e")
+            .unwrap();
+        assert_eq!(existing_rels.len(), 3);
+
+        db.clean(true).unwrap();
+    }
+
     #[test]
     fn test_delete_nodes() {
         let nodes = vec![Node {
@@ -939,6 +1704,7 @@ mod tests {
             skeleton_code: "func Node1() {}".to_string(),
             start_line: 1,
             end_line: 1,
+            ..Default::default()
         }];
         let mut db = Database::new(PathBuf::from("test.db"));
 
@@ -953,6 +1719,75 @@ mod tests {
         db.clean(false).unwrap();
     }
 
+    #[test]
+    fn test_bulk_insert_nodes_via_csv_in_batches() {
+        let nodes: Vec<Node> = (0..5)
+            .map(|i| Node::from_type_and_name(NodeType::File, format!("file{}.go", i)))
+            .chain(
+                (0..2).map(|i| Node::from_type_and_name(NodeType::Directory, format!("dir{}", i))),
+            )
+            .collect();
+        let mut db = Database::new(PathBuf::from("test_batches.db"));
+        db.clean(true).unwrap();
+        db.set_bulk_insert_batch_size(2);
+
+        // 3 batches for files (2 + 2 + 1) and 1 batch for directories.
+        let copy_count = db.bulk_insert_nodes_via_csv(&nodes).unwrap();
+        assert_eq!(copy_count, 4);
+
+        let mut node_names: Vec<_> = db
+            .query_nodes("MATCH (n) RETURN n")
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        node_names.sort();
+        assert_eq!(
+            node_names,
+            ["dir0", "dir1", "file0.go", "file1.go", "file2.go", "file3.go", "file4.go"],
+        );
+
+        db.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_bulk_insert_edges_via_csv_reports_failures() {
+        let nodes = vec![
+            Node::from_type_and_name(NodeType::File, "file1.go".to_string()),
+            Node::from_type_and_name(NodeType::Function, "file1.go:func1".to_string()),
+        ];
+        let edge = |to_type: NodeType, to_name: &str| Edge {
+            r#type: EdgeType::Contains,
+            from: Node::from_type_and_name(NodeType::File, "file1.go".to_string()),
+            to: Node::from_type_and_name(to_type, to_name.to_string()),
+            import: None,
+            alias: None,
+            properties: IndexMap::new(),
+        };
+        let mut db = Database::new(PathBuf::from("test_edge_failures.db"));
+        db.clean(true).unwrap();
+        db.bulk_insert_nodes_via_csv(&nodes).unwrap();
+
+        // The class node of the second edge does not exist.
+        let edges = vec![
+            edge(NodeType::Function, "file1.go:func1"),
+            edge(NodeType::Class, "file1.go:Missing"),
+        ];
+        let err = db.bulk_insert_edges_via_csv(&edges).unwrap_err();
+        assert!(err.to_string().contains("contains_File_Class.csv"));
+
+        // The other edges are still copied.
+        let edge_strings: Vec<_> = db
+            .query_edges("MATCH (a)-[e]->(b) RETURN a.name, b.name, e")
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        assert_eq!(edge_strings, ["file1.go-[contains]->file1.go:func1"]);
+
+        db.clean(true).unwrap();
+    }
+
     #[test]
     fn test_write_nodes_to_csv() {
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
@@ -969,6 +1804,7 @@ mod tests {
             skeleton_code: "func Node1() {}".to_string(),
             start_line: 1,
             end_line: 1,
+            ..Default::default()
         }];
         let db = Database::new(PathBuf::from("test.db"));
         match db.write_nodes_to_csv(&nodes, &temp_out_dir) {
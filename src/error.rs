@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::IndexCancelled;
+
+/// The error returned by the operations of `CodeGraph` and `Database`.
+#[derive(Debug)]
+pub enum CodeGraphError {
+    /// Parsing the source files failed
+    Parse(Box<dyn Error + Send + Sync>),
+    /// The Kuzu database failed, e.g. due to an invalid query
+    Database(kuzu::Error),
+    /// Reading or writing a file failed
+    Io(std::io::Error),
+    /// The path does not exist, or is not supported by the operation (e.g. a subdirectory for a store
+    /// other than Kuzu)
+    UnsupportedPath(PathBuf),
+    /// The language of the file is not supported by the operation
+    LanguageUnsupported(String),
+    /// The node with the given name does not exist
+    NotFound(String),
+    /// The indexing is cancelled via the `cancel_flag` of the config
+    Cancelled,
+    /// Any other error, e.g. of a store other than Kuzu, or of the (de)serialization of exports
+    Other(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for CodeGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeGraphError::Parse(err) => write!(f, "Parse error: {}", err),
+            CodeGraphError::Database(err) => write!(f, "Database error: {}", err),
+            CodeGraphError::Io(err) => write!(f, "I/O error: {}", err),
+            CodeGraphError::UnsupportedPath(path) => {
+                write!(f, "Unsupported path: {}", path.display())
+            }
+            CodeGraphError::LanguageUnsupported(language) => {
+                write!(f, "Unsupported language: {}", language)
+            }
+            CodeGraphError::NotFound(name) => write!(f, "Node not found: {}", name),
+            CodeGraphError::Cancelled => write!(f, "{}", IndexCancelled),
+            CodeGraphError::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for CodeGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodeGraphError::Parse(err) => Some(err.as_ref()),
+            CodeGraphError::Database(err) => Some(err),
+            CodeGraphError::Io(err) => Some(err),
+            CodeGraphError::Other(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for CodeGraphError {
+    /// Converts the errors from the parser, where the known ones (e.g. of the database) are unwrapped,
+    /// and the others are regarded as parse errors.
+    fn from(err: Box<dyn Error + Send + Sync>) -> Self {
+        let err = match err.downcast::<CodeGraphError>() {
+            Ok(err) => return *err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<IndexCancelled>() {
+            Ok(_) => return CodeGraphError::Cancelled,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<kuzu::Error>() {
+            Ok(err) => return CodeGraphError::Database(*err),
+            Err(err) => err,
+        };
+        match err.downcast::<std::io::Error>() {
+            Ok(err) => CodeGraphError::Io(*err),
+            Err(err) => CodeGraphError::Parse(err),
+        }
+    }
+}
+
+impl From<kuzu::Error> for CodeGraphError {
+    fn from(err: kuzu::Error) -> Self {
+        CodeGraphError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for CodeGraphError {
+    fn from(err: std::io::Error) -> Self {
+        CodeGraphError::Io(err)
+    }
+}
+
+impl From<IndexCancelled> for CodeGraphError {
+    fn from(_: IndexCancelled) -> Self {
+        CodeGraphError::Cancelled
+    }
+}
+
+impl From<String> for CodeGraphError {
+    fn from(message: String) -> Self {
+        CodeGraphError::Other(message.into())
+    }
+}
+
+impl From<&str> for CodeGraphError {
+    fn from(message: &str) -> Self {
+        CodeGraphError::Other(message.into())
+    }
+}
+
+impl From<serde_json::Error> for CodeGraphError {
+    fn from(err: serde_json::Error) -> Self {
+        CodeGraphError::Other(Box::new(err))
+    }
+}
+
+impl From<csv::Error> for CodeGraphError {
+    fn from(err: csv::Error) -> Self {
+        CodeGraphError::Other(Box::new(err))
+    }
+}
+
+impl From<bincode::Error> for CodeGraphError {
+    fn from(err: bincode::Error) -> Self {
+        CodeGraphError::Other(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for CodeGraphError {
+    fn from(err: rusqlite::Error) -> Self {
+        CodeGraphError::Other(Box::new(err))
+    }
+}
+
+#[cfg(feature = "watch")]
+impl From<notify::Error> for CodeGraphError {
+    fn from(err: notify::Error) -> Self {
+        CodeGraphError::Other(Box::new(err))
+    }
+}
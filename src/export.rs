@@ -0,0 +1,540 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+
+use crate::{CodeGraphError, Edge, EdgeType, Node, NodeType};
+
+/// The formats that the graph is exported to, each of which escapes the strings (e.g. node names) differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Cypher,
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl ExportFormat {
+    /// Returns the given string escaped for the format, so that any node name (e.g. `types.ts:Result<User>`
+    /// or one with quotes and backslashes) is written as is:
+    ///
+    /// - Cypher: a single-quoted string literal.
+    /// - DOT: a double-quoted identifier, which may contain any characters (e.g. `:`, `.` and `<`).
+    /// - GraphML: the XML text (without quotes), which is safe for both the text content and the attribute
+    ///   values. The control characters other than tabs and newlines are dropped, since they are not allowed
+    ///   in XML 1.0.
+    /// - JSON: a double-quoted string.
+    pub fn escape(&self, s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len() + 2);
+        match self {
+            ExportFormat::Cypher => {
+                escaped.push('\'');
+                for c in s.chars() {
+                    match c {
+                        '\\' => escaped.push_str("\\\\"),
+                        '\'' => escaped.push_str("\\'"),
+                        '\n' => escaped.push_str("\\n"),
+                        '\r' => escaped.push_str("\\r"),
+                        '\t' => escaped.push_str("\\t"),
+                        c => escaped.push(c),
+                    }
+                }
+                escaped.push('\'');
+            }
+            ExportFormat::Dot => {
+                escaped.push('"');
+                for c in s.chars() {
+                    match c {
+                        '\\' => escaped.push_str("\\\\"),
+                        '"' => escaped.push_str("\\\""),
+                        '\n' => escaped.push_str("\\n"),
+                        '\r' => {}
+                        c => escaped.push(c),
+                    }
+                }
+                escaped.push('"');
+            }
+            ExportFormat::GraphMl => {
+                for c in s.chars() {
+                    match c {
+                        '&' => escaped.push_str("&amp;"),
+                        '<' => escaped.push_str("&lt;"),
+                        '>' => escaped.push_str("&gt;"),
+                        '"' => escaped.push_str("&quot;"),
+                        '\'' => escaped.push_str("&apos;"),
+                        '\t' | '\n' | '\r' => escaped.push(c),
+                        c if c.is_control() => {}
+                        c => escaped.push(c),
+                    }
+                }
+            }
+            ExportFormat::Json => {
+                escaped = serde_json::Value::String(s.to_string()).to_string();
+            }
+        }
+        escaped
+    }
+}
+
+/// Returns the Cypher literal of the given property value, or `None` for null (i.e. missing) values.
+///
+/// Objects are written as JSON strings, since Neo4j does not support nested maps as property values.
+fn cypher_value(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::String(s) => Some(ExportFormat::Cypher.escape(s)),
+        serde_json::Value::Array(values) => Some(format!(
+            "[{}]",
+            values
+                .iter()
+                .filter_map(cypher_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        serde_json::Value::Object(_) => Some(ExportFormat::Cypher.escape(&value.to_string())),
+    }
+}
+
+/// Returns the Cypher map literal of the given properties, skipping the null ones.
+fn cypher_map(properties: &IndexMap<String, serde_json::Value>) -> String {
+    let entries: Vec<String> = properties
+        .iter()
+        .filter_map(|(key, value)| cypher_value(value).map(|v| format!("{}: {}", key, v)))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Returns a Cypher script of `CREATE` statements, which reconstructs the nodes and edges in Neo4j.
+///
+/// Nodes are labeled by their types (e.g. `Function`), and relationships are typed by the uppercase edge
+/// types (e.g. `CONTAINS`). Since each statement runs on its own, a relationship matches its nodes by name.
+pub fn write_cypher(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut script = String::new();
+
+    script.push_str("// Nodes\n");
+    for node in nodes {
+        let mut properties = node.to_dict();
+        // The type is already the label.
+        properties.shift_remove("type");
+        script.push_str(&format!(
+            "CREATE (:{} {});\n",
+            node.r#type,
+            cypher_map(&properties)
+        ));
+    }
+
+    script.push_str("// Relationships\n");
+    for edge in edges {
+        let mut properties = edge.to_dict();
+        // The endpoints are matched by name, and the type is already the relationship type.
+        for key in ["from", "to", "type"] {
+            properties.shift_remove(key);
+        }
+        script.push_str(&format!(
+            "MATCH (a:{} {{name: {}}}), (b:{} {{name: {}}}) CREATE (a)-[:{} {}]->(b);\n",
+            edge.from.r#type,
+            ExportFormat::Cypher.escape(&edge.from.name),
+            edge.to.r#type,
+            ExportFormat::Cypher.escape(&edge.to.name),
+            edge.r#type.to_string().to_uppercase(),
+            cypher_map(&properties)
+        ));
+    }
+
+    script
+}
+
+/// Write the nodes and edges into a SQLite database at `path`, which is overwritten if it already exists.
+///
+/// The database has two tables:
+/// - `nodes(name, type, language, start_line, end_line, code, skeleton_code)`
+/// - `edges("from", "to", type, import, alias)`
+#[cfg(feature = "sqlite")]
+pub fn write_sqlite(path: &Path, nodes: &[Node], edges: &[Edge]) -> Result<(), CodeGraphError> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+
+    let mut conn = rusqlite::Connection::open(path)?;
+    conn.execute_batch(
+        r#"
+CREATE TABLE nodes (
+    name TEXT PRIMARY KEY,
+    type TEXT NOT NULL,
+    language TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    skeleton_code TEXT NOT NULL
+);
+CREATE TABLE edges (
+    "from" TEXT NOT NULL,
+    "to" TEXT NOT NULL,
+    type TEXT NOT NULL,
+    import TEXT,
+    alias TEXT
+);
+CREATE INDEX edges_from ON edges ("from");
+CREATE INDEX edges_to ON edges ("to");
+"#,
+    )?;
+
+    // Insert all the rows in one transaction, which is much faster than autocommit.
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO nodes (name, type, language, start_line, end_line, code, skeleton_code) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for node in nodes {
+            stmt.execute(rusqlite::params![
+                node.name,
+                node.r#type.to_string(),
+                node.language.to_string(),
+                node.start_line as i64,
+                node.end_line as i64,
+                node.code,
+                node.skeleton_code,
+            ])?;
+        }
+
+        let mut stmt = tx.prepare(
+            r#"INSERT INTO edges ("from", "to", type, import, alias) VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )?;
+        for edge in edges {
+            stmt.execute(rusqlite::params![
+                edge.from.name,
+                edge.to.name,
+                edge.r#type.to_string(),
+                edge.import,
+                edge.alias,
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Returns the DOT attributes of the nodes with the given type.
+fn dot_node_style(r#type: &NodeType) -> &'static str {
+    match r#type {
+        NodeType::Directory => "shape=folder, style=filled, fillcolor=lightgray",
+        NodeType::File => "shape=note, style=filled, fillcolor=lightblue",
+        NodeType::Interface | NodeType::Class => "shape=box, style=filled, fillcolor=lightyellow",
+        NodeType::Function => "shape=ellipse, style=filled, fillcolor=palegreen",
+        _ => "shape=box, style=rounded",
+    }
+}
+
+/// Returns the DOT attributes of the edges with the given type.
+fn dot_edge_style(r#type: &EdgeType) -> &'static str {
+    match r#type {
+        EdgeType::Contains => "style=dashed, color=gray50",
+        EdgeType::Imports => "color=blue",
+        EdgeType::Inherits => "arrowhead=empty",
+        EdgeType::References => "style=dotted",
+        EdgeType::Calls => "color=black",
+    }
+}
+
+/// Returns a DOT digraph of the nodes and edges, which can be rendered by Graphviz, e.g. `dot -Tsvg`.
+///
+/// Nodes are identified by their full names, labeled by their short names and colored by their types, while
+/// edges are labeled and styled by their types.
+pub fn write_dot(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut dot = String::new();
+
+    dot.push_str("digraph codegraph {\n");
+    dot.push_str("  rankdir=LR;\n");
+    for node in nodes {
+        dot.push_str(&format!(
+            "  {} [label={}, {}];\n",
+            ExportFormat::Dot.escape(&node.name),
+            ExportFormat::Dot.escape(&node.short_name()),
+            dot_node_style(&node.r#type)
+        ));
+    }
+    for edge in edges {
+        dot.push_str(&format!(
+            "  {} -> {} [label={}, {}];\n",
+            ExportFormat::Dot.escape(&edge.from.name),
+            ExportFormat::Dot.escape(&edge.to.name),
+            ExportFormat::Dot.escape(&edge.r#type.to_string()),
+            dot_edge_style(&edge.r#type)
+        ));
+    }
+    dot.push_str("}\n");
+
+    dot
+}
+
+/// Writes the nodes and edges as a directed GraphML graph, e.g. for visualization in Gephi or yEd.
+///
+/// Nodes are identified by their full names, with the data keys `name`, `type`, `language`, `start_line`,
+/// `end_line` and `code`. Edges carry the data key `edge_type`, plus `import` and `alias` if any.
+pub fn write_graphml<W: Write>(
+    writer: &mut W,
+    nodes: &[Node],
+    edges: &[Edge],
+) -> Result<(), CodeGraphError> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (id, domain, name, r#type) in [
+        ("name", "node", "name", "string"),
+        ("type", "node", "type", "string"),
+        ("language", "node", "language", "string"),
+        ("start_line", "node", "start_line", "int"),
+        ("end_line", "node", "end_line", "int"),
+        ("code", "node", "code", "string"),
+        // Key IDs are shared by nodes and edges, so the type of edges needs a distinct one.
+        ("edge_type", "edge", "type", "string"),
+        ("import", "edge", "import", "string"),
+        ("alias", "edge", "alias", "string"),
+    ] {
+        writeln!(
+            writer,
+            r#"  <key id="{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+            id, domain, name, r#type
+        )?;
+    }
+    writeln!(writer, r#"  <graph id="codegraph" edgedefault="directed">"#)?;
+
+    for node in nodes {
+        writeln!(
+            writer,
+            r#"    <node id="{}">"#,
+            ExportFormat::GraphMl.escape(&node.name)
+        )?;
+        for (key, value) in [
+            ("name", node.name.clone()),
+            ("type", node.r#type.to_string()),
+            ("language", node.language.to_string()),
+            ("start_line", node.start_line.to_string()),
+            ("end_line", node.end_line.to_string()),
+            ("code", node.code.clone()),
+        ] {
+            writeln!(
+                writer,
+                r#"      <data key="{}">{}</data>"#,
+                key,
+                ExportFormat::GraphMl.escape(&value)
+            )?;
+        }
+        writeln!(writer, "    </node>")?;
+    }
+
+    for (i, edge) in edges.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"    <edge id="e{}" source="{}" target="{}">"#,
+            i,
+            ExportFormat::GraphMl.escape(&edge.from.name),
+            ExportFormat::GraphMl.escape(&edge.to.name)
+        )?;
+        writeln!(
+            writer,
+            r#"      <data key="edge_type">{}</data>"#,
+            ExportFormat::GraphMl.escape(&edge.r#type.to_string())
+        )?;
+        for (key, value) in [("import", &edge.import), ("alias", &edge.alias)] {
+            if let Some(value) = value {
+                writeln!(
+                    writer,
+                    r#"      <data key="{}">{}</data>"#,
+                    key,
+                    ExportFormat::GraphMl.escape(value)
+                )?;
+            }
+        }
+        writeln!(writer, "    </edge>")?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// A streaming writer of the JSON export, i.e. `{"nodes": [...], "edges": [...]}`, where the nodes are written
+/// (in batches) before the edges, and the export is completed by `finish`.
+///
+/// The nodes are in the shape of `Node::to_dict`, and the edges are in the shape of `Edge::to_dict` plus the
+/// edge type as `_label` (as expected by `Edge::from_dict`), where `import` and `alias` are always present.
+pub struct JsonWriter<W: Write> {
+    writer: W,
+    writing_edges: bool,
+    // The number of items written in the current array.
+    count: usize,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self, CodeGraphError> {
+        writer.write_all(b"{\"nodes\": [")?;
+        Ok(Self {
+            writer,
+            writing_edges: false,
+            count: 0,
+        })
+    }
+
+    pub fn write_nodes(&mut self, nodes: &[Node]) -> Result<(), CodeGraphError> {
+        if self.writing_edges {
+            return Err("the nodes must be written before the edges".into());
+        }
+        for node in nodes {
+            self.write_item(&node.to_dict())?;
+        }
+        Ok(())
+    }
+
+    pub fn write_edges(&mut self, edges: &[Edge]) -> Result<(), CodeGraphError> {
+        if !self.writing_edges {
+            self.writer.write_all(b"\n], \"edges\": [")?;
+            self.writing_edges = true;
+            self.count = 0;
+        }
+        for edge in edges {
+            let mut dict = edge.to_dict();
+            for key in ["import", "alias"] {
+                dict.entry(key.to_string())
+                    .or_insert(serde_json::Value::Null);
+            }
+            dict.insert(
+                "_label".to_string(),
+                serde_json::Value::String(edge.r#type.to_string()),
+            );
+            self.write_item(&dict)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), CodeGraphError> {
+        // The edges array is still written if there are no edges.
+        self.write_edges(&[])?;
+        self.writer.write_all(b"\n]}\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the given item on its own line.
+    fn write_item(
+        &mut self,
+        item: &IndexMap<String, serde_json::Value>,
+    ) -> Result<(), CodeGraphError> {
+        if self.count > 0 {
+            self.writer.write_all(b",")?;
+        }
+        self.writer.write_all(b"\n")?;
+        serde_json::to_writer(&mut self.writer, item)?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// The JSON export, whose nodes and edges are in the shapes of `Node::to_dict` and `Edge::to_dict`.
+#[derive(Deserialize)]
+struct JsonGraph {
+    nodes: Vec<HashMap<String, serde_json::Value>>,
+    edges: Vec<HashMap<String, serde_json::Value>>,
+}
+
+/// Reads the nodes and edges from the JSON written by `JsonWriter`.
+///
+/// Returns a `NotFound` error if the endpoint of any edge is missing from the nodes.
+pub fn read_json<R: Read>(reader: R) -> Result<(Vec<Node>, Vec<Edge>), CodeGraphError> {
+    let graph: JsonGraph = serde_json::from_reader(std::io::BufReader::new(reader))?;
+
+    let mut nodes: IndexMap<String, Node> = IndexMap::new();
+    for (i, data) in graph.nodes.iter().enumerate() {
+        let node = Node::from_dict(data)
+            .map_err(|e| CodeGraphError::Other(format!("invalid node #{}: {}", i, e).into()))?;
+        nodes.insert(node.name.clone(), node);
+    }
+
+    let mut edges: Vec<Edge> = Vec::with_capacity(graph.edges.len());
+    for (i, data) in graph.edges.iter().enumerate() {
+        let mut edge = Edge::from_dict(data)
+            .map_err(|e| CodeGraphError::Other(format!("invalid edge #{}: {}", i, e).into()))?;
+        // The endpoints are only identified by name in the export.
+        for (endpoint, key) in [(&mut edge.from, "from"), (&mut edge.to, "to")] {
+            let name = data.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+            *endpoint = nodes
+                .get(name)
+                .ok_or_else(|| CodeGraphError::NotFound(name.to_string()))?
+                .clone();
+        }
+        edges.push(edge);
+    }
+
+    Ok((nodes.into_values().collect(), edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAME: &str = r#"types.ts:Result<"a\b">"#;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            ExportFormat::Cypher.escape(NAME),
+            r#"'types.ts:Result<"a\\b">'"#
+        );
+        assert_eq!(
+            ExportFormat::Dot.escape(NAME),
+            r#""types.ts:Result<\"a\\b\">""#
+        );
+        assert_eq!(
+            ExportFormat::GraphMl.escape(NAME),
+            r#"types.ts:Result&lt;&quot;a\b&quot;&gt;"#
+        );
+        assert_eq!(
+            ExportFormat::Json.escape(NAME),
+            r#""types.ts:Result<\"a\\b\">""#
+        );
+    }
+
+    #[test]
+    fn test_export_special_names() {
+        let file_node = Node::from_type_and_name(NodeType::File, "types.ts".to_string());
+        let class_node = Node::from_type_and_name(NodeType::Class, NAME.to_string());
+        let nodes = vec![file_node.clone(), class_node.clone()];
+        let edges = vec![Edge {
+            r#type: EdgeType::Contains,
+            from: file_node,
+            to: class_node,
+            import: None,
+            alias: None,
+            properties: IndexMap::new(),
+        }];
+
+        let cypher = write_cypher(&nodes, &edges);
+        assert!(cypher.contains(r#"(b:Class {name: 'types.ts:Result<"a\\b">'})"#));
+
+        let dot = write_dot(&nodes, &edges);
+        assert!(dot.contains(r#"  "types.ts" -> "types.ts:Result<\"a\\b\">" [label="contains""#));
+
+        let mut graphml = Vec::new();
+        write_graphml(&mut graphml, &nodes, &edges).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(r#"<node id="types.ts:Result&lt;&quot;a\b&quot;&gt;">"#));
+
+        let mut json = Vec::new();
+        let mut writer = JsonWriter::new(&mut json).unwrap();
+        writer.write_nodes(&nodes).unwrap();
+        writer.write_edges(&edges).unwrap();
+        writer.finish().unwrap();
+        let (read_nodes, read_edges) = read_json(json.as_slice()).unwrap();
+        assert_eq!(read_nodes[1].name, NAME);
+        assert_eq!(read_edges[0].to.name, NAME);
+    }
+}
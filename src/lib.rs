@@ -1,15 +1,31 @@
 use log;
 use pathdiff;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, Weak};
 
 mod db;
+mod error;
+mod export;
 mod parser;
+mod snapshot;
+mod store;
+mod synthetic;
 mod types;
 mod util;
 
 pub use db::Database;
-pub use parser::{File, FuncParamType, Parser, ParserConfig};
-pub use types::{Edge, EdgeType, Language, Node, NodeType};
+pub use error::CodeGraphError;
+pub use export::ExportFormat;
+pub use parser::{File, FuncParamType, Parser, ParserConfig, ReferenceScope, UnresolvedImport};
+pub use store::{GraphStore, MemoryStore};
+// Only for the benchmarks, not a part of the public API.
+#[doc(hidden)]
+pub use synthetic::generate_synthetic_repo;
+pub use types::{
+    AccessorKind, CallTree, DirMetric, Edge, EdgeType, FileSummary, GraphStats, IndexCancelled,
+    IndexProgress, Language, Node, NodeType, SchemaInfo, TableInfo, WatchEvent,
+};
 
 pub type Config = ParserConfig;
 
@@ -21,45 +37,398 @@ pub struct Snippet {
     pub content: String,
 }
 
-pub struct CodeGraph {
-    db: Database,
+/// A text chunk of a definition node, e.g. for building a vector index over the code.
+#[derive(Debug)]
+pub struct Chunk {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// Returns the first `max_methods` (if any) method skeletons in source order, as well as the number of
+/// the omitted ones.
+///
+/// Each method is a `{start_line, skeleton_code}` struct, since the methods are collected in no particular order.
+fn capped_methods(methods: &[kuzu::Value], max_methods: Option<usize>) -> (Vec<&str>, usize) {
+    let mut methods: Vec<(u32, &str)> = methods
+        .iter()
+        .filter_map(|meth| match meth {
+            kuzu::Value::Struct(fields) => match fields.as_slice() {
+                // The nested classes (e.g. in Python) have no skeletons.
+                [(_, kuzu::Value::UInt32(start_line)), (_, kuzu::Value::String(meth_skeleton_code))]
+                    if !meth_skeleton_code.is_empty() =>
+                {
+                    Some((*start_line, meth_skeleton_code.as_str()))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    methods.sort();
+    let kept = max_methods.map_or(methods.len(), |max| max.min(methods.len()));
+    let more = methods.len() - kept;
+    (
+        methods
+            .into_iter()
+            .take(kept)
+            .map(|(_, meth)| meth)
+            .collect(),
+        more,
+    )
+}
+
+/// Returns the snippet content of a type referenced by function parameters, which is assembled uniformly across
+/// languages:
+///
+/// - A type without methods (e.g. an interface, whose methods are part of its code, or a type alias) is its code.
+/// - A class (or struct) with methods is its skeleton followed by the method skeletons, which are placed in the
+///   class body for TypeScript and Python, and after the struct for Go (whose methods are declared outside of it).
+///
+/// `more` is the number of the omitted methods, which is marked by a `... N more` comment.
+fn type_snippet_content(
+    language: &Language,
+    type_type: &NodeType,
+    code: &str,
+    skeleton_code: &str,
+    methods: &[&str],
+    more: usize,
+) -> String {
+    // The Go type specs are captured without the `type` keyword, e.g. `Status int`.
+    let code = match language {
+        Language::Go if !code.starts_with("type ") => format!("type {}", code),
+        _ => code.to_string(),
+    };
+    if *type_type != NodeType::Class || (methods.is_empty() && more == 0) {
+        return code;
+    }
+
+    // (skeleton, separator and indent of the methods, comment marker, closing)
+    let (skeleton, separator, indent, comment, closing) = match language {
+        // Structs have no skeletons, since their bodies only contain the fields.
+        Language::Go => (code, "\n\n", "", "//", ""),
+        Language::TypeScript => (
+            format!(
+                "{}{{",
+                skeleton_code
+                    .strip_suffix("{ ... }")
+                    .unwrap_or(skeleton_code)
+            ),
+            "\n",
+            "  ",
+            "//",
+            "\n}",
+        ),
+        Language::Python => (
+            code.lines().next().unwrap_or_default().to_string(),
+            "\n",
+            "    ",
+            "#",
+            "",
+        ),
+        _ => return code,
+    };
+
+    let mut content = skeleton;
+    let more_marker = format!("{} ... {} more", comment, more);
+    let more_marker = (more > 0).then_some(more_marker.as_str());
+    for method in methods.iter().copied().chain(more_marker) {
+        content.push_str(separator);
+        let lines: Vec<String> = method
+            .lines()
+            .map(|line| format!("{}{}", indent, line))
+            .collect();
+        content.push_str(&lines.join("\n"));
+    }
+    content.push_str(closing);
+    content
+}
+
+/// Split the code of the node into chunks of at most `max_chars` characters, each of which starts with
+/// the node name on its own line. The doc comment of the node (if any) precedes the code, and is attributed
+/// to the start line of the node.
+///
+/// The code is split at line boundaries, unless a single line is too long to fit into a chunk.
+fn node_chunks(node: &Node, max_chars: usize) -> Vec<Chunk> {
+    let header = format!("{}\n", node.name);
+    // Leave room for at least one character of code, even if the name is too long.
+    let budget = max_chars.saturating_sub(header.chars().count()).max(1);
+
+    // (line number, text) of the pieces of code, each of which fits into a chunk.
+    let mut pieces: Vec<(usize, String)> = Vec::new();
+    let doc_lines = node.doc_comment.lines().map(|line| (node.start_line, line));
+    let code_lines = (node.start_line..).zip(node.code.lines());
+    for (line_number, line) in doc_lines.chain(code_lines) {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            pieces.push((line_number, String::new()));
+        }
+        for part in chars.chunks(budget) {
+            pieces.push((line_number, part.iter().collect()));
+        }
+    }
+
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut current: Option<(usize, usize, String)> = None;
+    for (line, piece) in pieces {
+        if let Some((_, end_line, text)) = &mut current {
+            // One more character for the line break.
+            if text.chars().count() + 1 + piece.chars().count() <= budget {
+                text.push('\n');
+                text.push_str(&piece);
+                *end_line = line;
+                continue;
+            }
+        }
+        if let Some((start_line, end_line, text)) = current.take() {
+            chunks.push(Chunk {
+                name: node.name.clone(),
+                start_line,
+                end_line,
+                text: header.clone() + &text,
+            });
+        }
+        current = Some((line, line, piece));
+    }
+    // A node without code still has a chunk with its name.
+    let (start_line, end_line, text) =
+        current.unwrap_or((node.start_line, node.end_line, String::new()));
+    chunks.push(Chunk {
+        name: node.name.clone(),
+        start_line,
+        end_line,
+        text: header + &text,
+    });
+    chunks
+}
+
+/// Get the database shared by all the `CodeGraph` instances (in the current process) with the same database path.
+///
+/// Kuzu only allows one read-write database instance per path, and indexing interleaves deletes and inserts,
+/// so all the operations on the same database are serialized by the returned mutex.
+///
+/// Since the primary keys of the shared database depend on `hash_names`, it's an error to share the database
+/// with a different `hash_names` setting.
+fn shared_database(
+    db_path: PathBuf,
+    hash_names: bool,
+) -> Result<Arc<Mutex<Database>>, CodeGraphError> {
+    static DATABASES: OnceLock<Mutex<HashMap<PathBuf, Weak<Mutex<Database>>>>> = OnceLock::new();
+
+    let key = std::path::absolute(&db_path).unwrap_or(db_path.clone());
+    let mut databases = DATABASES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(db) = databases.get(&key).and_then(|db| db.upgrade()) {
+        let shared_hash_names = db
+            .lock()
+            .map_err(|_| "the database lock is poisoned by a panicked operation")?
+            .hash_names();
+        if shared_hash_names != hash_names {
+            return Err(format!(
+                "The database at {} is already opened with hash_node_names = {}",
+                key.display(),
+                shared_hash_names
+            )
+            .into());
+        }
+        return Ok(db);
+    }
+
+    // Drop the entries of databases that are no longer used.
+    databases.retain(|_, db| db.strong_count() > 0);
+
+    let mut db = Database::new(db_path);
+    db.set_hash_names(hash_names);
+    let db = Arc::new(Mutex::new(db));
+    databases.insert(key, Arc::downgrade(&db));
+    Ok(db)
+}
+
+/// Group the names of the imported files (or directories, e.g. Go packages) by the importing file.
+fn imported_files_by_file<'a>(
+    edges: impl Iterator<Item = &'a Edge>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut imported_files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for edge in edges {
+        if let EdgeType::Imports = edge.r#type {
+            // "src/a.ts:A" => "src/a.ts"
+            let file_name = edge.to.name.split(':').next().unwrap_or(&edge.to.name);
+            let files = imported_files.entry(edge.from.name.clone()).or_default();
+            if !files.iter().any(|f| f == file_name) {
+                files.push(file_name.to_string());
+            }
+        }
+    }
+    for files in imported_files.values_mut() {
+        files.sort();
+    }
+    imported_files
+}
+
+/// Keep the nodes whose types are allowed by `node_types` (if any), where directories and files are always kept.
+fn retain_node_types(node_types: &Option<HashSet<NodeType>>, nodes: Vec<Node>) -> Vec<Node> {
+    nodes
+        .into_iter()
+        .filter(|node| is_node_type_kept(node_types, &node.r#type))
+        .collect()
+}
+
+/// Keep the edges between the nodes whose types are allowed by `node_types` (if any).
+fn retain_edge_node_types(node_types: &Option<HashSet<NodeType>>, edges: &[Edge]) -> Vec<Edge> {
+    edges
+        .iter()
+        .filter(|edge| {
+            is_node_type_kept(node_types, &edge.from.r#type)
+                && is_node_type_kept(node_types, &edge.to.r#type)
+        })
+        .cloned()
+        .collect()
+}
+
+fn is_node_type_kept(node_types: &Option<HashSet<NodeType>>, r#type: &NodeType) -> bool {
+    match node_types {
+        Some(node_types) => {
+            matches!(r#type, NodeType::Directory | NodeType::File) || node_types.contains(r#type)
+        }
+        None => true,
+    }
+}
+
+pub struct CodeGraph<S: GraphStore = Database> {
+    db: Arc<Mutex<S>>,
     repo_path: PathBuf,
     config: Config,
 }
 
 impl CodeGraph {
+    /// Create a code graph whose database at `db_path` is shared with the other code graphs of the same path
+    /// (see `try_new`).
+    ///
+    /// Panics if the database is shared with a different `hash_node_names` setting.
     pub fn new(db_path: PathBuf, repo_path: PathBuf, config: Config) -> Self {
+        Self::try_new(db_path, repo_path, config).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a code graph like `new`, but return an error if the database at `db_path` is already opened (by
+    /// another code graph in the current process) with a different `hash_node_names` setting.
+    pub fn try_new(
+        db_path: PathBuf,
+        repo_path: PathBuf,
+        config: Config,
+    ) -> Result<Self, CodeGraphError> {
+        let db = shared_database(db_path, config.hash_node_names)?;
+
+        Ok(Self {
+            db: db,
+            repo_path: repo_path,
+            config: config,
+        })
+    }
+
+    /// Create a code graph whose database at `db_path` is rebuilt from the given snapshot file (see
+    /// `save_snapshot`), e.g. to restore an index cached by CI without re-parsing the repository.
+    pub fn load_snapshot(
+        path: PathBuf,
+        db_path: PathBuf,
+        repo_path: PathBuf,
+        config: Config,
+    ) -> Result<Self, CodeGraphError> {
+        let mut graph = Self::try_new(db_path, repo_path, config)?;
+        graph.restore_snapshot(path)?;
+        Ok(graph)
+    }
+}
+
+impl<S: GraphStore> CodeGraph<S> {
+    /// Create a code graph backed by the given store, e.g. a `MemoryStore` for tests.
+    ///
+    /// Note that only the Kuzu store supports all the operations, while the other stores only support
+    /// indexing the whole repository and querying the nodes and edges.
+    pub fn with_store(store: S, repo_path: PathBuf, config: Config) -> Self {
         Self {
-            db: Database::new(db_path),
+            db: Arc::new(Mutex::new(store)),
             repo_path: repo_path,
             config: config,
         }
     }
 
+    /// Lock the database for the duration of an operation.
+    ///
+    /// Overlapping operations (e.g. two threads indexing the same repository) are executed one after another.
+    fn db(&self) -> Result<MutexGuard<'_, S>, CodeGraphError> {
+        self.db
+            .lock()
+            .map_err(|_| "the database lock is poisoned by a panicked operation".into())
+    }
+
     /// Index the given path into the database.
     ///
-    /// If `force` is true, the existing files will be re-indexed.
-    pub fn index(&mut self, path: PathBuf, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// If `force` is true, the existing files will be re-indexed. Otherwise, a single file is skipped if its
+    /// content is unchanged since it was last indexed.
+    pub fn index(&mut self, path: PathBuf, force: bool) -> Result<(), CodeGraphError> {
+        let parser = Parser::new(self.repo_path.clone(), self.config.clone());
+        self.index_with_parser(parser, path, force)
+    }
+
+    /// Index the given path into the database like `index`, but only the files with the given extensions
+    /// (e.g. `["go"]`) for this call.
+    pub fn index_with_extensions(
+        &mut self,
+        path: PathBuf,
+        force: bool,
+        extensions: Vec<String>,
+    ) -> Result<(), CodeGraphError> {
         let mut parser = Parser::new(self.repo_path.clone(), self.config.clone());
+        parser.restrict_extensions(extensions);
+        self.index_with_parser(parser, path, force)
+    }
+
+    fn index_with_parser(
+        &mut self,
+        mut parser: Parser,
+        path: PathBuf,
+        force: bool,
+    ) -> Result<(), CodeGraphError> {
+        let mut store = self.db()?;
+        let Some(db) = store.as_kuzu() else {
+            return self.index_into_store(&mut *store, parser, path, force);
+        };
 
         if path == self.repo_path {
             // Try to index the root directory of the repository.
             // We assume that there are many files in the repository, so we need to
             // use the Kuzu's `COPY FROM` command (i.e. batch insert) for better performance.
 
+            // Parse before cleaning, so that the database is left as it was if the parsing fails (or is cancelled).
+            let (nodes, edges) = parser.parse(&path, None)?;
+
             if force {
                 // Since the `COPY FROM` command does not support deleting existing nodes,
                 // we need to delete the existing nodes manually.
-                self.db.clean(true)?;
+                db.clean(true)?;
             }
+            db.set_bulk_insert_batch_size(self.config.bulk_insert_batch_size);
 
-            let (nodes, edges) = parser.parse(&path, None)?;
+            let node_types = &self.config.node_types;
             let vec_nodes: Vec<Node> = nodes.values().cloned().collect();
-            self.db.bulk_insert_nodes_via_csv(&vec_nodes)?;
-            self.db.bulk_insert_edges_via_csv(&edges)?;
+            db.bulk_insert_nodes_via_csv(&retain_node_types(node_types, vec_nodes))?;
+            db.bulk_insert_edges_via_csv(&retain_edge_node_types(node_types, &edges))?;
+
+            let resolved_edges = parser.resolve_pending_edges(Some(&mut *db))?;
+            db.bulk_insert_edges_via_csv(&retain_edge_node_types(node_types, &resolved_edges))?;
 
-            let resolved_edges = parser.resolve_pending_edges(Some(&mut self.db))?;
-            self.db.bulk_insert_edges_via_csv(&resolved_edges)?;
+            // Persist the unresolved imports, which may be resolved once their targets are indexed.
+            db.set_unresolved_imports(&parser.unresolved_pending_imports(&resolved_edges))?;
+            parser.write_diagnostics(&resolved_edges)?;
+
+            if self.config.store_import_summary {
+                let imported_files =
+                    imported_files_by_file(edges.iter().chain(resolved_edges.iter()));
+                db.set_imported_files(&imported_files)?;
+            }
 
             return Ok(());
         }
@@ -67,17 +436,51 @@ impl CodeGraph {
         // Otherwise, we assume that the given path is a single file or a small directory.
         // We use the Kuzu's `MERGE` command to upsert (i.e. insert or update) the nodes.
         if path.is_file() {
-            self.index_file(&mut parser, path, None)?;
+            if !parser.has_allowed_extension(&path) {
+                return Ok(());
+            }
+            self.index_files(db, &mut parser, vec![(path, None)], force)?;
         } else if path.is_dir() {
-            return Err("Not supported yet".into());
+            self.index_directory(db, &mut parser, path)?;
         } else {
-            return Err(format!(
-                "{:?} does not exist or is neither a file nor directory",
-                path
-            )
-            .into());
+            // The path does not exist or is neither a file nor directory.
+            return Err(CodeGraphError::UnsupportedPath(path));
+        }
+
+        Ok(())
+    }
+
+    /// Index the whole repository into a store other than Kuzu.
+    ///
+    /// Since such stores can not be queried while resolving the edges, the edges that need the stored
+    /// nodes (e.g. the references to function parameter types) are not indexed.
+    fn index_into_store(
+        &self,
+        store: &mut S,
+        mut parser: Parser,
+        path: PathBuf,
+        force: bool,
+    ) -> Result<(), CodeGraphError> {
+        if path != self.repo_path {
+            // Only the whole repository can be indexed into this store.
+            return Err(CodeGraphError::UnsupportedPath(path));
+        }
+        let (nodes, edges) = parser.parse(&path, None)?;
+        if force {
+            store.clean(true)?;
         }
 
+        let node_types = &self.config.node_types;
+        store.upsert_nodes(&retain_node_types(
+            node_types,
+            nodes.values().cloned().collect(),
+        ))?;
+        store.upsert_edges(&retain_edge_node_types(node_types, &edges))?;
+
+        let resolved_edges = parser.resolve_pending_edges(None)?;
+        store.upsert_edges(&retain_edge_node_types(node_types, &resolved_edges))?;
+        parser.write_diagnostics(&resolved_edges)?;
+
         Ok(())
     }
 
@@ -89,77 +492,245 @@ impl CodeGraph {
         &mut self,
         path: PathBuf,
         content: &[u8],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), CodeGraphError> {
         let mut parser = Parser::new(self.repo_path.clone(), self.config.clone());
-        return self.index_file(&mut parser, path, Some(content));
+        if !parser.is_supported_file(&path) {
+            let extension = path.extension().unwrap_or_default();
+            return Err(CodeGraphError::LanguageUnsupported(
+                extension.to_string_lossy().to_string(),
+            ));
+        }
+        let mut store = self.db()?;
+        let db = store
+            .as_kuzu()
+            .ok_or("Indexing a single file is only supported by the Kuzu store")?;
+        return self.index_files(db, &mut parser, vec![(path, Some(content))], false);
     }
 
-    fn index_file(
+    /// Index the dirty files with the given contents into the database, like `index_dirty_file`.
+    ///
+    /// All the files are parsed by a single parser and upserted in one pass, so that the references
+    /// between them are resolved without querying the database.
+    pub fn index_dirty_files(
         &mut self,
+        files: Vec<(PathBuf, Vec<u8>)>,
+    ) -> Result<(), CodeGraphError> {
+        let mut parser = Parser::new(self.repo_path.clone(), self.config.clone());
+        for (path, _) in &files {
+            if !parser.is_supported_file(path) {
+                let extension = path.extension().unwrap_or_default();
+                return Err(CodeGraphError::LanguageUnsupported(
+                    extension.to_string_lossy().to_string(),
+                ));
+            }
+        }
+        let mut store = self.db()?;
+        let db = store
+            .as_kuzu()
+            .ok_or("Indexing dirty files is only supported by the Kuzu store")?;
+        let files = files
+            .iter()
+            .map(|(path, content)| (path.clone(), Some(content.as_slice())))
+            .collect();
+        self.index_files(db, &mut parser, files, false)
+    }
+
+    /// Index the subtree of the given subdirectory into the database.
+    ///
+    /// The nodes of the files that no longer exist under the subdirectory are deleted, while the nodes
+    /// outside of it are left untouched.
+    fn index_directory(
+        &self,
+        db: &mut Database,
         parser: &mut Parser,
         path: PathBuf,
-        content: Option<&[u8]>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let rel_file_path = path
+    ) -> Result<(), CodeGraphError> {
+        let rel_dir_path = path
             .strip_prefix(self.repo_path.clone())
             .unwrap_or(&path)
             .to_string_lossy()
             .to_string();
 
-        // find all existing nodes related to the file.
+        // find all existing nodes under the directory.
+        let stmt = format!(
+            r#"
+MATCH (dir:Directory)-[:CONTAINS*]->(n)
+WHERE dir.name = {}
+RETURN DISTINCT n;
+"#,
+            util::cypher_string_literal(&db.node_key(&rel_dir_path)),
+        );
+        let old_nodes = db.query_nodes(stmt.as_str())?;
+
+        let (nodes, edges) = parser.parse(&path, None)?;
+
+        // Delete the nodes that no longer exist, e.g. those of the removed files.
+        let node_names_to_delete: Vec<String> = old_nodes
+            .iter()
+            .filter(|old_node| !nodes.contains_key(&old_node.name))
+            .map(|old_node| old_node.name.clone())
+            .collect();
+        db.delete_nodes(&node_names_to_delete)?;
+
+        // Delete all out-going edges from the directory and the remaining old nodes.
+        let mut node_names_for_rel_deletion = vec![rel_dir_path.clone()];
+        node_names_for_rel_deletion.extend(
+            old_nodes
+                .iter()
+                .filter(|old_node| nodes.contains_key(&old_node.name))
+                .map(|old_node| old_node.name.clone()),
+        );
+        db.delete_outgoing_edges(&node_names_for_rel_deletion)?;
+
+        // Upsert the nodes and edges.
+        //
+        // The root directory, which is only parsed to chain the directory back to it, is stored with
+        // the placeholder name "." (see `write_edges_to_csv`).
+        let node_types = &self.config.node_types;
+        let vec_nodes: Vec<Node> = nodes
+            .values()
+            .filter(|node| !node.name.is_empty())
+            .cloned()
+            .collect();
+        let edges: Vec<Edge> = edges
+            .into_iter()
+            .map(|mut edge| {
+                if edge.from.name.is_empty() {
+                    edge.from.name = ".".to_string();
+                }
+                edge
+            })
+            .collect();
+        db.upsert_nodes(&retain_node_types(node_types, vec_nodes))?;
+        db.upsert_edges(&retain_edge_node_types(node_types, &edges))?;
+
+        let resolved_edges = parser.resolve_pending_edges(Some(&mut *db))?;
+        db.upsert_edges(&retain_edge_node_types(node_types, &resolved_edges))?;
+
+        db.set_unresolved_imports(&parser.unresolved_pending_imports(&resolved_edges))?;
+        parser.write_diagnostics(&resolved_edges)?;
+        self.repair_imports(db, parser, &[rel_dir_path.clone()])?;
+
+        if self.config.store_import_summary {
+            // Always update the files under the directory, in case they no longer import anything.
+            let mut imported_files =
+                imported_files_by_file(edges.iter().chain(resolved_edges.iter()));
+            for node in nodes.values() {
+                if node.r#type == NodeType::File {
+                    imported_files.entry(node.name.clone()).or_default();
+                }
+            }
+            db.set_imported_files(&imported_files)?;
+        }
+
+        Ok(())
+    }
+
+    /// Index the given files (with their contents, if dirty) into the database in one pass.
+    ///
+    /// The references between the given files are resolved among them before querying the database.
+    fn index_files(
+        &self,
+        db: &mut Database,
+        parser: &mut Parser,
+        files: Vec<(PathBuf, Option<&[u8]>)>,
+        force: bool,
+    ) -> Result<(), CodeGraphError> {
+        let mut files_to_index: Vec<(PathBuf, Option<&[u8]>, String)> = Vec::new();
+        for (path, content) in files {
+            let rel_file_path = path
+                .strip_prefix(self.repo_path.clone())
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+
+            // Skip parsing (and writing) the file if its content hash is the same as the stored one, i.e. neither
+            // the content nor the grammar or the parsing options have changed (see `Parser::content_hash`).
+            if !force {
+                let content_hash = match content {
+                    Some(content) => parser.content_hash(&path, content),
+                    None => parser.content_hash(&path, &std::fs::read(&path)?),
+                };
+                let stmt = r#"MATCH (file:File) WHERE file.name = $name RETURN file;"#;
+                let params = HashMap::from([(
+                    "name".to_string(),
+                    kuzu::Value::String(db.node_key(&rel_file_path)),
+                )]);
+                let file_nodes = db.query_nodes_params(stmt, params)?;
+                if file_nodes
+                    .first()
+                    .is_some_and(|file_node| file_node.content_hash == content_hash)
+                {
+                    log::debug!("Skipping unchanged file: {}", rel_file_path);
+                    continue;
+                }
+            }
+            files_to_index.push((path, content, rel_file_path));
+        }
+        if files_to_index.is_empty() {
+            return Ok(());
+        }
+
+        // Convert node names to a string array for the query. e.g. ["file1", "node1", "node2"]
+        let to_names_array = |db: &Database, names: &[String]| {
+            format!(
+                "[{}]",
+                names
+                    .iter()
+                    .map(|name| util::cypher_string_literal(&db.node_key(name)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let rel_file_paths: Vec<String> = files_to_index
+            .iter()
+            .map(|(_, _, rel_file_path)| rel_file_path.clone())
+            .collect();
+
+        // find all existing nodes related to the files.
         let stmt = format!(
             r#"
 MATCH (file)-[:CONTAINS*1..2]->(def)
-WHERE file.name = "{}"
+WHERE file.name IN {}
 RETURN def;
 "#,
-            &rel_file_path,
+            to_names_array(db, &rel_file_paths),
         );
-        let old_nodes = self.db.query_nodes(stmt.as_str())?;
+        let old_nodes = db.query_nodes(stmt.as_str())?;
 
-        let (nodes, edges) = parser.parse(&path, content)?;
+        // The parser accumulates the nodes and edges of all the files parsed so far.
+        let mut nodes: indexmap::IndexMap<String, Node> = indexmap::IndexMap::new();
+        let mut edges: Vec<Edge> = Vec::new();
+        for (path, content, _) in &files_to_index {
+            (nodes, edges) = parser.parse(path, *content)?;
+        }
 
         // Delete outdated nodes.
-        // Find nodes that exist in old_nodes but not in nodes (outdated nodes to be deleted)
+        // Find nodes that exist in old_nodes but not in nodes (outdated nodes to be deleted), along with
+        // the file nodes of the empty files that are skipped (see `skip_empty_files`).
         let node_names_to_delete: Vec<String> = old_nodes
             .clone()
             .into_iter()
-            .filter(|old_node| !nodes.contains_key(&old_node.name))
             .map(|old_node| old_node.name)
+            .chain(rel_file_paths.iter().cloned())
+            .filter(|name| !nodes.contains_key(name))
             .collect();
-        self.db.delete_nodes(&node_names_to_delete)?;
+        db.delete_nodes(&node_names_to_delete)?;
 
-        // Delete all out-going edges from the current file node and old nodes.
-        let mut node_names_for_rel_deletion = vec![rel_file_path.clone()];
+        // Delete all out-going edges from the current file nodes and old nodes.
+        let mut node_names_for_rel_deletion = rel_file_paths.clone();
         node_names_for_rel_deletion
             .extend(old_nodes.clone().into_iter().map(|node| node.name.clone()));
-        // Convert node names to a string array for the query. e.g. ["file1", "node1", "node2"]
-        let node_names_array = format!(
-            "[{}]",
-            node_names_for_rel_deletion
-                .iter()
-                .map(|name| format!("{:?}", name))
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
 
-        let stmt = format!(
-            r#"
-MATCH (a)-[e]->()
-WHERE a.name IN {}
-DELETE e;
-"#,
-            &node_names_array,
-        );
-        log::debug!("delete out-going edges: {}", stmt);
-        let _ = self.db.query(stmt.as_str())?;
+        db.delete_outgoing_edges(&node_names_for_rel_deletion)?;
 
         // Upsert the nodes and edges.
+        let node_types = &self.config.node_types;
         let vec_nodes: Vec<Node> = nodes.values().cloned().collect();
-        self.db.upsert_nodes(&vec_nodes)?;
-        self.db.upsert_edges(&edges)?;
+        db.upsert_nodes(&retain_node_types(node_types, vec_nodes))?;
+        db.upsert_edges(&retain_edge_node_types(node_types, &edges))?;
 
-        let resolved_edges = parser.resolve_pending_edges(Some(&mut self.db))?;
+        let resolved_edges = parser.resolve_pending_edges(Some(&mut *db))?;
 
         if log::log_enabled!(log::Level::Debug) {
             for r in &resolved_edges {
@@ -167,211 +738,3344 @@ DELETE e;
             }
         }
 
-        self.db.upsert_edges(&resolved_edges)?;
+        db.upsert_edges(&retain_edge_node_types(node_types, &resolved_edges))?;
+
+        db.set_unresolved_imports(&parser.unresolved_pending_imports(&resolved_edges))?;
+        parser.write_diagnostics(&resolved_edges)?;
+        self.repair_imports(db, parser, &rel_file_paths)?;
+
+        if self.config.store_import_summary {
+            // Always update the current files, in case they no longer import anything.
+            let mut imported_files =
+                imported_files_by_file(edges.iter().chain(resolved_edges.iter()));
+            for rel_file_path in rel_file_paths {
+                imported_files.entry(rel_file_path).or_default();
+            }
+            db.set_imported_files(&imported_files)?;
+        }
 
         Ok(())
     }
 
-    pub fn query_nodes(&mut self, stmt: String) -> Result<Vec<Node>, Box<dyn std::error::Error>> {
-        return self.db.query_nodes(stmt.as_str());
-    }
+    /// Resolve the persisted imports of the other files, which were unresolved until
+    /// the files (or directories) `rel_paths` (whose nodes are parsed by `parser`) were indexed.
+    fn repair_imports(
+        &self,
+        db: &mut Database,
+        parser: &Parser,
+        rel_paths: &[String],
+    ) -> Result<(), CodeGraphError> {
+        let stmt = format!(
+            r#"
+MATCH (file:File)
+WHERE size(file.unresolved_imports) > 0 AND NOT file.name IN [{}]
+RETURN file;
+"#,
+            rel_paths
+                .iter()
+                .map(|name| util::cypher_string_literal(&db.node_key(name)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        let file_nodes = db.query_nodes(stmt.as_str())?;
 
-    pub fn query_edges(&mut self, stmt: String) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
-        return self.db.query_edges(stmt.as_str());
-    }
+        let mut repaired_edges: Vec<Edge> = Vec::new();
+        let mut unresolved_imports: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut imported_files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for file_node in file_nodes {
+            let (edges, unresolved) = parser.resolve_persisted_imports(&file_node);
+            if edges.is_empty() {
+                continue;
+            }
 
-    pub fn get_func_param_types(
-        &mut self,
-        file_path: String,
-        line: usize,
-    ) -> Result<Vec<Snippet>, Box<dyn std::error::Error>> {
-        // TODO: Needs improvements for better maintenance.
+            if self.config.store_import_summary {
+                let mut files = file_node.imported_files.clone();
+                files.extend(imported_files_by_file(edges.iter()).into_values().flatten());
+                files.sort();
+                files.dedup();
+                imported_files.insert(file_node.name.clone(), files);
+            }
+            unresolved_imports.insert(file_node.name, unresolved);
+            repaired_edges.extend(edges);
+        }
 
-        let mut snippets: Vec<Snippet> = Vec::new();
+        if repaired_edges.is_empty() {
+            return Ok(());
+        }
+        log::debug!("repaired {} import edges", repaired_edges.len());
 
-        // Make file_path a relative path to the repo_path.
-        let file_path = pathdiff::diff_paths(&file_path, &self.repo_path)
-            .unwrap_or(PathBuf::from(&file_path))
-            .to_string_lossy()
-            .to_string();
+        db.upsert_edges(&repaired_edges)?;
+        db.set_unresolved_imports(&unresolved_imports)?;
+        if self.config.store_import_summary {
+            db.set_imported_files(&imported_files)?;
+        }
 
-        let stmt = format!(
-            r#"
-MATCH (file {{ name: "{}" }})
-MATCH (file)-[:CONTAINS*1..2]->(func)
-MATCH (func)-[:REFERENCES]->(typ)
-WHERE func.start_line < {} AND func.end_line > {}
-OPTIONAL MATCH (typ)-[r:CONTAINS]->(meth)
-RETURN typ.language, typ.type, typ.name, typ.start_line, typ.end_line, typ.code, typ.skeleton_code, COLLECT(meth.skeleton_code) AS methods;
-        "#,
-            file_path, line, line
-        );
-        log::debug!("Query statement: {}", stmt);
-        if let Some(result) = self.db.query(stmt.as_str())? {
-            for row in result {
-                let language = match &row[0] {
-                    kuzu::Value::String(lang) => lang.parse().unwrap_or(Language::Text),
-                    _ => Language::Text,
-                };
-                let type_type = match &row[1] {
-                    kuzu::Value::String(type_str) => type_str.parse().unwrap_or(NodeType::Unparsed),
-                    _ => NodeType::Unparsed,
-                };
-                let path = match &row[2] {
-                    kuzu::Value::String(path) => {
-                        let parts: Vec<&str> = path.split(':').collect();
-                        parts[0].clone().to_string()
-                    }
-                    _ => "".to_string(),
-                };
-                let start_line = match &row[3] {
-                    kuzu::Value::UInt32(line) => *line as usize,
-                    _ => 0,
-                };
-                let end_line = match &row[4] {
-                    kuzu::Value::UInt32(line) => *line as usize,
-                    _ => 0,
-                };
+        Ok(())
+    }
 
-                let mut content = String::new();
-                match language {
-                    Language::Go => {
-                        match &row[5] {
-                            kuzu::Value::String(type_code) => {
-                                content.push_str(type_code.as_str());
-                            }
-                            _ => {}
-                        }
-                        match &row[7] {
-                            kuzu::Value::List(_, methods) => {
-                                for meth in methods {
-                                    match meth {
-                                        kuzu::Value::String(meth_skeleton_code) => {
-                                            content.push_str("\n\n");
-                                            content.push_str(meth_skeleton_code.as_str());
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    Language::TypeScript => {
-                        if type_type == NodeType::Class {
-                            match &row[6] {
-                                kuzu::Value::String(type_skeleton_code) => {
-                                    content.push_str(
-                                        &type_skeleton_code
-                                            [0..type_skeleton_code.len() - "{ ... }".len()],
-                                    );
-                                    content.push_str("{");
-                                }
-                                _ => {}
-                            }
-                            match &row[7] {
-                                kuzu::Value::List(_, methods) => {
-                                    for meth in methods {
-                                        match meth {
-                                            kuzu::Value::String(meth_skeleton_code) => {
-                                                content.push_str("\n  ");
-                                                content.push_str(meth_skeleton_code.as_str());
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                            content.push_str("\n}");
-                        } else {
-                            match &row[5] {
-                                kuzu::Value::String(type_code) => {
-                                    content.push_str(type_code.as_str());
-                                }
-                                _ => {}
-                            }
-                        }
+    pub fn query_nodes(&mut self, stmt: String) -> Result<Vec<Node>, CodeGraphError> {
+        return self.db()?.query_nodes(stmt.as_str());
+    }
+
+    /// Get the language with the most definition nodes, or None if there are no definitions.
+    ///
+    /// Ties are broken by the language name in alphabetical order.
+    pub fn primary_language(&mut self) -> Result<Option<Language>, CodeGraphError> {
+        const LABELS: [&str; 5] = ["Interface", "Class", "Function", "OtherType", "Namespace"];
+        let mut counts: HashMap<Language, i64> = HashMap::new();
+        let mut store = self.db()?;
+        if let Some(db) = store.as_kuzu() {
+            // Aggregate in the database, instead of loading the definitions (along with their code).
+            let stmt = format!(
+                "MATCH (n) WHERE label(n) IN {:?} RETURN n.language, count(*);",
+                LABELS
+            );
+            for row in db.query(&stmt)?.into_iter().flatten() {
+                if let [kuzu::Value::String(language), kuzu::Value::Int64(count)] = row.as_slice() {
+                    if let Ok(language) = language.parse() {
+                        *counts.entry(language).or_default() += count;
                     }
-                    _ => {}
                 }
-                snippets.push(Snippet {
-                    path,
-                    start_line,
-                    end_line,
-                    content,
-                });
             }
-        };
+        } else {
+            for label in LABELS {
+                let stmt = format!("MATCH (n:{}) RETURN n", label);
+                for node in store.query_nodes(&stmt)? {
+                    *counts.entry(node.language).or_default() += 1;
+                }
+            }
+        }
+
+        Ok(counts
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| {
+                a_count
+                    .cmp(b_count)
+                    .then_with(|| b.to_string().cmp(&a.to_string()))
+            })
+            .map(|(language, _)| language))
+    }
+
+    /// Get the groups of functions with identical code, which span at least `min_lines` lines.
+    ///
+    /// The code is compared regardless of whitespace and of the name of the function itself (see
+    /// `parser::mask_definition_name`), so that renamed copies are grouped as well. Both the groups and the
+    /// functions in each group are sorted by name.
+    pub fn duplicate_bodies(&mut self, min_lines: usize) -> Result<Vec<Vec<Node>>, CodeGraphError> {
+        let functions = self.db()?.query_nodes("MATCH (n:Function) RETURN n")?;
+
+        let mut groups: HashMap<String, Vec<Node>> = HashMap::new();
+        for function in functions {
+            if function.end_line + 1 - function.start_line < min_lines {
+                continue;
+            }
+            let code = parser::mask_definition_name(&function);
+            let normalized_code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+            groups.entry(normalized_code).or_default().push(function);
+        }
+
+        let mut duplicates: Vec<Vec<Node>> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort_by(|a, b| a.name.cmp(&b.name));
+                group
+            })
+            .collect();
+        duplicates.sort_by(|a, b| a[0].name.cmp(&b[0].name));
+        Ok(duplicates)
+    }
+
+    pub fn query_edges(&mut self, stmt: String) -> Result<Vec<Edge>, CodeGraphError> {
+        return self.db()?.query_edges(stmt.as_str());
+    }
+
+    /// Clean the database.
+    /// If `delete` is true, the database directory will be deleted. Otherwise, the database will be cleaned up.
+    ///
+    /// See `clean_path` for cleaning a specific file or directory.
+    pub fn clean(&mut self, delete: bool) -> Result<(), CodeGraphError> {
+        return self.db()?.clean(delete);
+    }
+
+    /// Save all the nodes and edges to the given file as a compact binary snapshot, which can be restored
+    /// without re-parsing the repository (see `restore_snapshot`).
+    pub fn save_snapshot(&mut self, path: PathBuf) -> Result<(), CodeGraphError> {
+        let mut store = self.db()?;
+        // Kuzu might store the hashed names, while the original names are always kept as full names.
+        let name = if store.as_kuzu().is_some() {
+            "full_name"
+        } else {
+            "name"
+        };
+        let nodes = store.query_nodes("MATCH (n) RETURN n")?;
+        let edges = store.query_edges(&format!(
+            "MATCH (a)-[e]->(b) RETURN a.{}, b.{}, e",
+            name, name
+        ))?;
+        snapshot::write_snapshot(&path, &nodes, &edges)
+    }
+
+    /// Replace all the nodes and edges with the ones in the given snapshot file (see `save_snapshot`).
+    pub fn restore_snapshot(&mut self, path: PathBuf) -> Result<(), CodeGraphError> {
+        let (nodes, edges) = snapshot::read_snapshot(&path)?;
+        let mut store = self.db()?;
+        let Some(db) = store.as_kuzu() else {
+            store.clean(false)?;
+            store.upsert_nodes(&nodes)?;
+            return store.upsert_edges(&edges);
+        };
+
+        // The database is empty after cleaning, so use `COPY FROM` like indexing the whole repository.
+        db.clean(false)?;
+        db.bulk_insert_nodes_via_csv(&nodes)?;
+        db.bulk_insert_edges_via_csv(&edges)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: GraphStore + Send + 'static> CodeGraph<S> {
+    /// Index the given path like `index`, but on a blocking thread of the current tokio runtime, and stream the
+    /// progress, which ends with either `Done` or `Failed`.
+    ///
+    /// Like the other operations, the indexing holds the database lock, so there is still a single writer
+    /// of the same database at a time.
+    pub fn index_async(
+        &self,
+        path: PathBuf,
+        force: bool,
+    ) -> impl tokio_stream::Stream<Item = IndexProgress> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut graph = Self {
+            db: self.db.clone(),
+            repo_path: self.repo_path.clone(),
+            config: self.config.clone(),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let mut parser = Parser::new(graph.repo_path.clone(), graph.config.clone());
+            let file_tx = tx.clone();
+            let mut parsed_files = 0;
+            parser.on_file_parsed(Box::new(move |file| {
+                parsed_files += 1;
+                let _ = file_tx.send(IndexProgress::FileParsed {
+                    file: file.to_string(),
+                    parsed_files,
+                });
+            }));
+
+            let progress = match graph.index_with_parser(parser, path, force) {
+                Ok(()) => IndexProgress::Done,
+                Err(e) => IndexProgress::Failed(e.to_string()),
+            };
+            let _ = tx.send(progress);
+        });
+
+        tokio_stream::wrappers::UnboundedReceiverStream::new(rx)
+    }
+}
+
+#[cfg(feature = "watch")]
+impl CodeGraph {
+    /// Watch the repository for file changes and re-index the changed files, until the callback returns false.
+    ///
+    /// Created or modified files are upserted like `index`, while removed files have their nodes deleted. The files
+    /// that would not be indexed (i.e. unsupported or excluded by the ignore patterns) are skipped. Each handled
+    /// change is reported to the callback, and errors of handling the changes do not stop the watching.
+    pub fn watch<F>(&mut self, mut cb: F) -> Result<(), CodeGraphError>
+    where
+        F: FnMut(WatchEvent) -> bool,
+    {
+        use notify::Watcher;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&self.repo_path, notify::RecursiveMode::Recursive)?;
+
+        for result in rx {
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    log::warn!("Failed to watch the file changes: {}", e);
+                    continue;
+                }
+            };
+            if event.kind.is_access() {
+                continue;
+            }
+            for path in event.paths {
+                let Some(watch_event) = self.handle_changed_file(path) else {
+                    continue;
+                };
+                if !cb(watch_event) {
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upserts the file at the given path if it exists, otherwise deletes its nodes.
+    ///
+    /// Returns None if the file is skipped.
+    fn handle_changed_file(&mut self, path: PathBuf) -> Option<WatchEvent> {
+        let parser = Parser::new(self.repo_path.clone(), self.config.clone());
+        if !parser.is_supported_file(&path) || parser.is_ignored(&path, false) {
+            return None;
+        }
+
+        let rel_file_path = path
+            .strip_prefix(&self.repo_path)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        let handled = if path.is_file() {
+            self.index(path, false)
+                .map(|_| WatchEvent::Indexed(rel_file_path.clone()))
+        } else {
+            self.delete_file(&rel_file_path)
+                .map(|_| WatchEvent::Deleted(rel_file_path.clone()))
+        };
+        Some(handled.unwrap_or_else(|e| WatchEvent::Failed {
+            file: rel_file_path,
+            error: e.to_string(),
+        }))
+    }
+
+    /// Delete the file node with the given name (relative to the repository), along with its definitions.
+    fn delete_file(&mut self, rel_file_path: &str) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+        let stmt = format!(
+            r#"
+MATCH (file:File)-[:CONTAINS*1..2]->(def)
+WHERE file.name = {}
+RETURN def;
+"#,
+            util::cypher_string_literal(&db.node_key(rel_file_path)),
+        );
+        let mut node_names: Vec<String> = db
+            .query_nodes(stmt.as_str())?
+            .into_iter()
+            .map(|node| node.name)
+            .collect();
+        node_names.push(rel_file_path.to_string());
+        db.delete_nodes(&node_names)
+    }
+}
+
+impl CodeGraph {
+    /// Query the nodes with the given parameterized statement, where the parameters (e.g. `$name`) are bound
+    /// to the given values. Unlike formatting the values into the statement, this is safe for any value,
+    /// e.g. names containing quotes.
+    pub fn query_nodes_params(
+        &mut self,
+        stmt: String,
+        params: HashMap<String, kuzu::Value>,
+    ) -> Result<Vec<Node>, CodeGraphError> {
+        self.db()?.query_nodes_params(stmt.as_str(), params)
+    }
+
+    pub fn get_func_param_types(
+        &mut self,
+        file_path: String,
+        line: usize,
+    ) -> Result<Vec<Snippet>, CodeGraphError> {
+        let mut snippets: Vec<Snippet> = Vec::new();
+        let max_methods = self.config.max_snippet_methods;
+
+        // Make file_path a relative path to the repo_path.
+        let file_path = pathdiff::diff_paths(&file_path, &self.repo_path)
+            .unwrap_or(PathBuf::from(&file_path))
+            .to_string_lossy()
+            .to_string();
+
+        let stmt = format!(
+            r#"
+MATCH (file {{ name: $file_name }})
+MATCH (file)-[:CONTAINS*1..2]->(func)
+MATCH (func)-[:REFERENCES]->(typ)
+WHERE func.start_line < {} AND func.end_line > {}
+OPTIONAL MATCH (typ)-[r:CONTAINS]->(meth)
+RETURN typ.language, typ.type, typ.full_name, typ.start_line, typ.end_line, typ.code, typ.skeleton_code, COLLECT({{start_line: meth.start_line, skeleton_code: meth.skeleton_code}}) AS methods;
+        "#,
+            line, line
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut db = self.db()?;
+        let params = HashMap::from([(
+            "file_name".to_string(),
+            kuzu::Value::String(db.node_key(&file_path)),
+        )]);
+        if let Some(result) = db.query_params(stmt.as_str(), params)? {
+            for row in result {
+                let language = match &row[0] {
+                    kuzu::Value::String(lang) => lang.parse().unwrap_or(Language::Text),
+                    _ => Language::Text,
+                };
+                let type_type = match &row[1] {
+                    kuzu::Value::String(type_str) => type_str.parse().unwrap_or(NodeType::Unparsed),
+                    _ => NodeType::Unparsed,
+                };
+                let path = match &row[2] {
+                    kuzu::Value::String(path) => {
+                        let parts: Vec<&str> = path.split(':').collect();
+                        parts[0].clone().to_string()
+                    }
+                    _ => "".to_string(),
+                };
+                let start_line = match &row[3] {
+                    kuzu::Value::UInt32(line) => *line as usize,
+                    _ => 0,
+                };
+                let end_line = match &row[4] {
+                    kuzu::Value::UInt32(line) => *line as usize,
+                    _ => 0,
+                };
+
+                let code = match &row[5] {
+                    kuzu::Value::String(code) => code.as_str(),
+                    _ => "",
+                };
+                let skeleton_code = match &row[6] {
+                    kuzu::Value::String(skeleton_code) => skeleton_code.as_str(),
+                    _ => "",
+                };
+                let (methods, more) = match &row[7] {
+                    kuzu::Value::List(_, methods) => capped_methods(methods, max_methods),
+                    _ => (vec![], 0),
+                };
+                let content = type_snippet_content(
+                    &language,
+                    &type_type,
+                    code,
+                    skeleton_code,
+                    &methods,
+                    more,
+                );
+                snippets.push(Snippet {
+                    path,
+                    start_line,
+                    end_line,
+                    content,
+                });
+            }
+        };
+
+        Ok(snippets)
+    }
+
+    /// Get the type nodes referenced by the parameters of the function at the given line, sorted by name.
+    ///
+    /// Unlike `get_func_param_types`, the nodes are returned as is, so that callers can decide how to render them.
+    pub fn func_param_type_nodes(
+        &mut self,
+        file_path: String,
+        line: usize,
+    ) -> Result<Vec<Node>, CodeGraphError> {
+        // Make file_path a relative path to the repo_path.
+        let file_path = pathdiff::diff_paths(&file_path, &self.repo_path)
+            .unwrap_or(PathBuf::from(&file_path))
+            .to_string_lossy()
+            .to_string();
+
+        let mut db = self.db()?;
+        let stmt = format!(
+            r#"
+MATCH (file {{ name: {} }})
+MATCH (file)-[:CONTAINS*1..2]->(func)
+MATCH (func)-[:REFERENCES]->(typ)
+WHERE func.start_line < {} AND func.end_line > {}
+RETURN DISTINCT typ;
+        "#,
+            util::cypher_string_literal(&db.node_key(&file_path)),
+            line,
+            line
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut nodes = db.query_nodes(&stmt)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Get the classes (or structs) that inherit (or implement) the interface with the given name, sorted by name.
+    pub fn implementors_of(&mut self, interface: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+        let stmt = format!(
+            r#"MATCH (c)-[:INHERITS]->(i {{ name: {} }}) RETURN DISTINCT c;"#,
+            util::cypher_string_literal(&db.node_key(&interface))
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut nodes = db.query_nodes(&stmt)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Clean the nodes under the given file or directory, which are contained by it transitively,
+    /// along with their edges. The file or directory node itself is kept.
+    ///
+    /// The path can be either absolute or relative to the repository.
+    pub fn clean_path(&mut self, path: PathBuf) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+
+        // Make path a relative path to the repo_path.
+        let rel_path = pathdiff::diff_paths(&path, &self.repo_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let path_name = if rel_path == "." {
+            ""
+        } else {
+            rel_path.as_str()
+        };
+        let mut path_key = db.node_key(path_name);
+        if path_key.is_empty() {
+            // The root directory is stored with a placeholder name.
+            path_key = ".".to_string();
+        }
+
+        let stmt = format!(
+            r#"MATCH (p)-[:CONTAINS*]->(def) WHERE p.name = {} RETURN DISTINCT def;"#,
+            util::cypher_string_literal(&path_key)
+        );
+        log::debug!("Query statement: {}", stmt);
+        let node_names: Vec<String> = db
+            .query_nodes(&stmt)?
+            .into_iter()
+            .map(|node| node.name)
+            .collect();
+        db.delete_nodes(&node_names)
+    }
+
+    /// Get the nodes that reference the node with the given name, e.g. the functions using a type
+    /// as a parameter, sorted by name.
+    pub fn find_references(&mut self, node_name: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+        let stmt = r#"MATCH (a)-[:REFERENCES]->(t) WHERE t.name = $name RETURN DISTINCT a;"#;
+        log::debug!("Query statement: {}", stmt);
+        let params = HashMap::from([(
+            "name".to_string(),
+            kuzu::Value::String(db.node_key(&node_name)),
+        )]);
+        let mut nodes = db.query_nodes_params(stmt, params)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Get the functions called by the function with the given name, sorted by name.
+    pub fn get_callees(&mut self, node_name: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+        let stmt = r#"MATCH (caller)-[:CALLS]->(callee) WHERE caller.name = $name RETURN DISTINCT callee;"#;
+        log::debug!("Query statement: {}", stmt);
+        let params = HashMap::from([(
+            "name".to_string(),
+            kuzu::Value::String(db.node_key(&node_name)),
+        )]);
+        let mut nodes = db.query_nodes_params(stmt, params)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Get the functions calling the function with the given name, sorted by name.
+    pub fn get_callers(&mut self, node_name: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+        let stmt = r#"MATCH (caller)-[:CALLS]->(callee) WHERE callee.name = $name RETURN DISTINCT caller;"#;
+        log::debug!("Query statement: {}", stmt);
+        let params = HashMap::from([(
+            "name".to_string(),
+            kuzu::Value::String(db.node_key(&node_name)),
+        )]);
+        let mut nodes = db.query_nodes_params(stmt, params)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Get the nodes which may be impacted by changing the node with the given name, sorted by name.
+    ///
+    /// That is, the nodes referencing, calling or importing the node, directly or transitively.
+    pub fn impact_set(&mut self, node_name: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+
+        // Traverse the incoming edges level by level, since the paths may be cyclic (e.g. recursive calls).
+        let mut visited: HashSet<String> = HashSet::from([node_name.clone()]);
+        let mut frontier = vec![node_name];
+        let mut nodes: Vec<Node> = Vec::new();
+        while !frontier.is_empty() {
+            let stmt = format!(
+                r#"MATCH (a)-[:REFERENCES|CALLS|IMPORTS]->(t) WHERE t.name IN [{}] RETURN DISTINCT a;"#,
+                frontier
+                    .iter()
+                    .map(|name| util::cypher_string_literal(&db.node_key(name)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            log::debug!("Query statement: {}", stmt);
+            frontier = Vec::new();
+            for node in db.query_nodes(&stmt)? {
+                if visited.insert(node.name.clone()) {
+                    frontier.push(node.name.clone());
+                    nodes.push(node);
+                }
+            }
+        }
+
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(nodes)
+    }
+
+    /// Get the files that were parsed by a different tree-sitter grammar than the current one.
+    ///
+    /// These files may be parsed differently now, so they should be re-indexed.
+    pub fn outdated_grammar_files(&mut self) -> Result<Vec<String>, CodeGraphError> {
+        let file_nodes = self.db()?.query_nodes("MATCH (file:File) RETURN file;")?;
+        Ok(file_nodes
+            .into_iter()
+            .filter(|node| node.grammar_version != node.language.grammar_version())
+            .map(|node| node.name)
+            .collect())
+    }
+
+    /// Get the top-level exported definitions (i.e. functions and types) that are never referenced, called or
+    /// imported.
+    ///
+    /// Entry points like `main` and Go's `init` functions are excluded, while the unexported definitions are left
+    /// to the compilers and linters. These definitions are potentially unused code.
+    pub fn unreferenced_definitions(&mut self) -> Result<Vec<Node>, CodeGraphError> {
+        let stmt = r#"
+MATCH (parent)-[:CONTAINS]->(def)
+WHERE label(parent) IN ["File", "Namespace"]
+  AND label(def) IN ["Interface", "Class", "Function", "OtherType"]
+  AND def.exported
+  AND def.short_name <> "main"
+  AND NOT def.short_name STARTS WITH "init#"
+  AND NOT EXISTS { MATCH ()-[:REFERENCES|:CALLS|:IMPORTS]->(def) }
+RETURN def
+ORDER BY def.name;
+        "#;
+        log::debug!("Query statement: {}", stmt);
+        self.db()?.query_nodes(stmt)
+    }
+
+    /// Get the file-level import graph as `(importing file, imported file)` pairs, sorted and deduplicated.
+    ///
+    /// Imports of symbols are collapsed to the files defining them. Note that Go packages are imported
+    /// as a whole, so the imported "file" is the package directory in that case.
+    pub fn import_edges(&mut self) -> Result<Vec<(String, String)>, CodeGraphError> {
+        let stmt =
+            "MATCH (file:File)-[:IMPORTS]->(imported) RETURN file.full_name, imported.full_name;";
+        log::debug!("Query statement: {}", stmt);
+
+        let mut pairs: Vec<(String, String)> = Vec::new();
+        if let Some(result) = self.db()?.query(stmt)? {
+            for row in result {
+                let (kuzu::Value::String(from), kuzu::Value::String(to)) = (&row[0], &row[1])
+                else {
+                    continue;
+                };
+                // "src/a.ts:A" => "src/a.ts"
+                let to = to.split(':').next().unwrap_or(to);
+                pairs.push((from.clone(), to.to_string()));
+            }
+        }
+        pairs.sort();
+        pairs.dedup();
+        Ok(pairs)
+    }
+
+    /// Get the coupling metrics of all the directories containing indexed files, ordered by directory.
+    ///
+    /// The imports are counted between files (like `import_edges`), and only if the importing file and the
+    /// imported file (or Go package) are in different directories.
+    pub fn coupling_metrics(&mut self) -> Result<Vec<DirMetric>, CodeGraphError> {
+        // "src/a.ts" => "src", "a.ts" => "."
+        fn parent_dir(file_name: &str) -> String {
+            match file_name.rsplit_once('/') {
+                Some((dir, _)) => dir.to_string(),
+                None => ".".to_string(),
+            }
+        }
+        fn string(value: &kuzu::Value) -> String {
+            match value {
+                kuzu::Value::String(s) => s.clone(),
+                _ => String::new(),
+            }
+        }
+
+        let mut db = self.db()?;
+
+        let mut metrics: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        let stmt = "MATCH (file:File) RETURN file.full_name;";
+        log::debug!("Query statement: {}", stmt);
+        if let Some(result) = db.query(stmt)? {
+            for row in result {
+                metrics.entry(parent_dir(&string(&row[0]))).or_default();
+            }
+        }
+
+        let stmt = "MATCH (file:File)-[:IMPORTS]->(imported) RETURN file.full_name, imported.full_name, label(imported);";
+        log::debug!("Query statement: {}", stmt);
+        // (importing file, imported file or package, directory of the imported one)
+        let mut imports: Vec<(String, String, String)> = Vec::new();
+        if let Some(result) = db.query(stmt)? {
+            for row in result {
+                let (from, to, label) = (string(&row[0]), string(&row[1]), string(&row[2]));
+                if label == "Directory" {
+                    // Go packages are imported as a whole.
+                    let to_dir = if to.is_empty() {
+                        ".".to_string()
+                    } else {
+                        to.clone()
+                    };
+                    imports.push((from, to, to_dir));
+                } else {
+                    // "src/a.ts:A" => "src/a.ts"
+                    let to = to.split(':').next().unwrap_or(&to).to_string();
+                    let to_dir = parent_dir(&to);
+                    imports.push((from, to, to_dir));
+                }
+            }
+        }
+        // Count each imported file (or package) once per importing file.
+        imports.sort();
+        imports.dedup();
+
+        for (from, _, to_dir) in imports {
+            let from_dir = parent_dir(&from);
+            if from_dir == to_dir {
+                continue;
+            }
+            metrics.entry(from_dir).or_default().1 += 1;
+            metrics.entry(to_dir).or_default().0 += 1;
+        }
+
+        Ok(metrics
+            .into_iter()
+            .map(|(dir, (afferent, efferent))| DirMetric {
+                dir,
+                afferent,
+                efferent,
+                instability: if afferent + efferent == 0 {
+                    0.0
+                } else {
+                    efferent as f64 / (afferent + efferent) as f64
+                },
+            })
+            .collect())
+    }
+
+    /// Get the Go `init` functions of the package in the directory `dir` (relative to the repo path), in the
+    /// order they run at package initialization, i.e. by file name and then by source order within each file.
+    ///
+    /// An empty `dir` (or ".") means the root package.
+    pub fn init_functions(&mut self, dir: String) -> Result<Vec<Node>, CodeGraphError> {
+        let mut db = self.db()?;
+
+        let dir_name = if dir == "." { "" } else { dir.as_str() };
+        let mut dir_key = db.node_key(dir_name);
+        if dir_key.is_empty() {
+            // The root directory is stored with a placeholder name.
+            dir_key = ".".to_string();
+        }
+
+        let stmt = format!(
+            r#"MATCH (dir:Directory)-[:CONTAINS]->(file:File)-[:CONTAINS]->(func:Function) WHERE dir.name = {} AND file.language = "go" AND func.full_name =~ ".*:init#[0-9]+" RETURN func;"#,
+            util::cypher_string_literal(&dir_key)
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut init_nodes = db.query_nodes(stmt.as_str())?;
+
+        // "main.go:init#2" => ("main.go:init", 2)
+        init_nodes.sort_by_key(|node| {
+            let (prefix, number) = node.name.rsplit_once('#').unwrap_or((&node.name, ""));
+            (prefix.to_string(), number.parse::<usize>().unwrap_or(0))
+        });
+        Ok(init_nodes)
+    }
+
+    /// Get the tree of the functions reachable from the `root` function via `Calls` edges.
+    ///
+    /// A function that is already on the current call path (i.e. a recursive call) is included
+    /// without its callees, to break the cycle. `max_depth` limits the depth of the tree, 0 means no limit.
+    pub fn call_tree(
+        &mut self,
+        root: String,
+        max_depth: usize,
+    ) -> Result<CallTree, CodeGraphError> {
+        let mut db = self.db()?;
+
+        let stmt = format!(
+            r#"MATCH (func:Function) WHERE func.name = {} RETURN func;"#,
+            util::cypher_string_literal(&db.node_key(&root))
+        );
+        if db.query_nodes(stmt.as_str())?.is_empty() {
+            return Err(CodeGraphError::NotFound(root));
+        }
+
+        let mut path: Vec<String> = Vec::new();
+        Self::build_call_tree(&mut db, root, max_depth, &mut path)
+    }
+
+    fn build_call_tree(
+        db: &mut Database,
+        name: String,
+        max_depth: usize,
+        path: &mut Vec<String>,
+    ) -> Result<CallTree, CodeGraphError> {
+        let mut tree = CallTree {
+            name: name.clone(),
+            calls: Vec::new(),
+        };
+        if path.contains(&name) || (max_depth > 0 && path.len() >= max_depth) {
+            return Ok(tree);
+        }
+
+        let stmt = format!(
+            r#"
+MATCH (caller:Function)-[:CALLS]->(callee:Function)
+WHERE caller.name = {}
+RETURN callee
+ORDER BY callee.full_name;
+"#,
+            util::cypher_string_literal(&db.node_key(&name))
+        );
+        let callees = db.query_nodes(stmt.as_str())?;
+
+        path.push(name);
+        for callee in callees {
+            tree.calls
+                .push(Self::build_call_tree(db, callee.name, max_depth, path)?);
+        }
+        path.pop();
+
+        Ok(tree)
+    }
+
+    /// Get the skeleton of a file, i.e. the signatures of its definitions without bodies, in source order.
+    ///
+    /// Definitions without a skeleton (e.g. Go structs and interfaces) are included as is.
+    pub fn get_file_skeleton(&mut self, file_path: String) -> Result<String, CodeGraphError> {
+        let mut db = self.db()?;
+        Self::file_skeleton(&mut db, &file_path)
+    }
+
+    /// Get the skeletons of all the files under the directory `dir` (relative to the repo path),
+    /// ordered by file name. An empty `dir` (or ".") means the whole repository.
+    pub fn get_package_skeleton(&mut self, dir: String) -> Result<String, CodeGraphError> {
+        let mut db = self.db()?;
+
+        let dir_name = if dir == "." { "" } else { dir.as_str() };
+        let mut dir_key = db.node_key(dir_name);
+        if dir_key.is_empty() {
+            // The root directory is stored with a placeholder name.
+            dir_key = ".".to_string();
+        }
+
+        let stmt = format!(
+            r#"MATCH (dir:Directory)-[:CONTAINS*]->(file:File) WHERE dir.name = {} RETURN DISTINCT file;"#,
+            util::cypher_string_literal(&dir_key)
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut file_nodes = db.query_nodes(stmt.as_str())?;
+        file_nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut skeletons: Vec<String> = Vec::new();
+        for file_node in file_nodes {
+            let skeleton = Self::file_skeleton(&mut db, &file_node.name)?;
+            if !skeleton.is_empty() {
+                skeletons.push(skeleton);
+            }
+        }
+        Ok(skeletons.join("\n\n"))
+    }
+
+    fn file_skeleton(db: &mut Database, file_name: &str) -> Result<String, CodeGraphError> {
+        let stmt = format!(
+            r#"MATCH (file:File)-[:CONTAINS*1..3]->(def) WHERE file.name = {} RETURN DISTINCT def;"#,
+            util::cypher_string_literal(&db.node_key(file_name))
+        );
+        log::debug!("Query statement: {}", stmt);
+        let mut defs = db.query_nodes(stmt.as_str())?;
+        defs.sort_by_key(|def| (def.start_line, std::cmp::Reverse(def.end_line)));
+
+        let mut parts: Vec<String> = Vec::new();
+        let mut covered_until: Option<usize> = None;
+        for def in defs {
+            // Skip the definitions already covered by the code of the enclosing one (e.g. interface methods).
+            if covered_until.is_some_and(|line| def.end_line <= line) {
+                continue;
+            }
+            if def.skeleton_code.is_empty() {
+                parts.push(def.code);
+                covered_until = Some(def.end_line);
+            } else {
+                parts.push(def.skeleton_code);
+            }
+        }
+        Ok(parts.join("\n\n"))
+    }
+
+    /// Export the graph into a SQLite database at `path`, with the tables `nodes` and `edges`.
+    ///
+    /// The file is overwritten if it already exists. Nodes are identified by their full names.
+    #[cfg(feature = "sqlite")]
+    pub fn export_sqlite(&mut self, path: PathBuf) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+        let nodes = db.query_nodes("MATCH (n) RETURN n ORDER BY n.full_name;")?;
+        let edges = db.query_edges("MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e;")?;
+        export::write_sqlite(&path, &nodes, &edges)
+    }
+
+    /// Export the graph as a Cypher script of `CREATE` statements (nodes then relationships), which
+    /// reconstructs the graph in Neo4j.
+    pub fn export_cypher(&mut self) -> Result<String, CodeGraphError> {
+        let mut db = self.db()?;
+        let nodes = db.query_nodes("MATCH (n) RETURN n ORDER BY n.full_name;")?;
+        let edges = db.query_edges("MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e;")?;
+        Ok(export::write_cypher(&nodes, &edges))
+    }
+
+    /// Export the graph as JSON into `writer`, i.e. `{"nodes": [...], "edges": [...]}`, where the nodes and
+    /// edges are in the shapes of `Node::to_dict` and `Edge::to_dict` (see `export::JsonWriter`).
+    ///
+    /// The nodes and the edges are each read by a single query, whose rows are written as they are read, so the
+    /// graph is never held in memory as a whole.
+    pub fn export_json(&mut self, writer: impl std::io::Write) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+        let mut json = export::JsonWriter::new(writer)?;
+
+        db.for_each_node("MATCH (n) RETURN n;", |node| {
+            json.write_nodes(std::slice::from_ref(&node))
+        })?;
+        db.for_each_edge(
+            "MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e;",
+            |edge| json.write_edges(std::slice::from_ref(&edge)),
+        )?;
+
+        json.finish()
+    }
+
+    /// Replace all the nodes and edges with the ones in the JSON exported by `export_json`, without parsing the
+    /// source files, e.g. to move an index to a machine where the repository is not present.
+    ///
+    /// Returns a `NotFound` error, before touching the database, if the endpoint of any edge is missing from the
+    /// nodes.
+    pub fn import_json(&mut self, reader: impl std::io::Read) -> Result<(), CodeGraphError> {
+        let (nodes, edges) = export::read_json(reader)?;
+        let mut db = self.db()?;
+        db.clean(false)?;
+        db.bulk_insert_nodes_via_csv(&nodes)?;
+        db.bulk_insert_edges_via_csv(&edges)?;
+        Ok(())
+    }
+
+    /// Export the graph as a Graphviz DOT digraph, which can be piped into e.g. `dot -Tsvg`.
+    pub fn export_dot(&mut self) -> Result<String, CodeGraphError> {
+        let mut db = self.db()?;
+        let nodes = db.query_nodes("MATCH (n) RETURN n ORDER BY n.full_name;")?;
+        let edges = db.query_edges("MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e;")?;
+        Ok(export::write_dot(&nodes, &edges))
+    }
+
+    /// Export the graph as GraphML into `writer`, e.g. for visualization in Gephi or yEd.
+    pub fn export_graphml(
+        &mut self,
+        mut writer: impl std::io::Write,
+    ) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+        let nodes = db.query_nodes("MATCH (n) RETURN n ORDER BY n.full_name;")?;
+        let edges = db.query_edges("MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e;")?;
+        export::write_graphml(&mut writer, &nodes, &edges)
+    }
+
+    /// Set the metadata `key` of the node with the given name to `value`, e.g. `owner: team-x`.
+    ///
+    /// Note that the metadata is reset when the node is re-indexed.
+    pub fn set_node_metadata(
+        &mut self,
+        name: String,
+        key: String,
+        value: String,
+    ) -> Result<(), CodeGraphError> {
+        let mut db = self.db()?;
+        let mut metadata = db
+            .node_metadata(&name)?
+            .ok_or_else(|| CodeGraphError::NotFound(name.clone()))?;
+        metadata.insert(key, value);
+        db.set_node_metadata(&name, &metadata)
+    }
+
+    /// Get the metadata of the node with the given name.
+    pub fn get_node_metadata(
+        &mut self,
+        name: String,
+    ) -> Result<BTreeMap<String, String>, CodeGraphError> {
+        let metadata = self
+            .db()?
+            .node_metadata(&name)?
+            .ok_or_else(|| CodeGraphError::NotFound(name.clone()))?;
+        Ok(metadata)
+    }
+
+    /// Recompute the fields derived from the stored name and code of all nodes (i.e. `short_name` and
+    /// `skeleton_code`), which avoids a full re-index after the derivation changes.
+    ///
+    /// Returns the number of refreshed nodes.
+    pub fn refresh_derived_fields(&mut self) -> Result<usize, CodeGraphError> {
+        let parser = Parser::new(self.repo_path.clone(), self.config.clone());
+
+        let mut db = self.db()?;
+        let nodes = db.query_nodes("MATCH (n) RETURN n;")?;
+        for node in &nodes {
+            // The root directory is stored with the placeholder name ".", but its short name is derived from "".
+            let short_name = if node.name == "." {
+                String::new()
+            } else {
+                node.short_name()
+            };
+            let skeleton_code = parser.derive_skeleton_code(node);
+            db.set_derived_fields(&node.name, &short_name, skeleton_code.as_deref())?;
+        }
+        Ok(nodes.len())
+    }
+
+    /// Get the text chunks of all the definition nodes (ordered by name), each of which has at most `max_chars`
+    /// characters, unless the node name alone is longer than that.
+    ///
+    /// Each chunk is the node name followed by (a part of) its code, along with the lines of the code it covers.
+    pub fn chunks(&mut self, max_chars: usize) -> Result<Vec<Chunk>, CodeGraphError> {
+        if max_chars == 0 {
+            return Err("max_chars must be positive".into());
+        }
+
+        let stmt = "MATCH (n:Interface:Class:Function:OtherType:Namespace) RETURN n;";
+        log::debug!("Query statement: {}", stmt);
+        let mut nodes = self.db()?.query_nodes(stmt)?;
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(nodes
+            .iter()
+            .flat_map(|node| node_chunks(node, max_chars))
+            .collect())
+    }
+
+    /// Get the definition nodes whose doc comments contain the given text (case-sensitive), sorted by name.
+    pub fn search_docs(&mut self, text: String) -> Result<Vec<Node>, CodeGraphError> {
+        self.db()?.search_docs(&text)
+    }
+
+    /// Get the (outgoing, incoming) edges of the node with the given name.
+    pub fn incident_edges(
+        &mut self,
+        name: String,
+    ) -> Result<(Vec<Edge>, Vec<Edge>), CodeGraphError> {
+        self.db()?.incident_edges(&name)
+    }
+
+    /// Get the numbers of nodes and edges by table, which are cached and maintained incrementally for cheap
+    /// polling. If `recompute` is true, they are recomputed from scratch instead (see `Database::stats`).
+    pub fn stats(&mut self, recompute: bool) -> Result<GraphStats, CodeGraphError> {
+        self.db()?.stats(recompute)
+    }
+
+    /// Get the Kuzu version and the node and relationship tables (with their columns) of the database.
+    pub fn schema_info(&mut self) -> Result<SchemaInfo, CodeGraphError> {
+        fn strings(db: &mut Database, stmt: &str) -> Result<Vec<Vec<String>>, CodeGraphError> {
+            log::debug!("Query statement: {}", stmt);
+            let mut rows: Vec<Vec<String>> = Vec::new();
+            if let Some(result) = db.query(stmt)? {
+                for row in result {
+                    rows.push(row.iter().map(|value| value.to_string()).collect());
+                }
+            }
+            Ok(rows)
+        }
+
+        let mut db = self.db()?;
+
+        let version = strings(&mut db, "CALL db_version() RETURN *;")?
+            .into_iter()
+            .flatten()
+            .next()
+            .unwrap_or_default();
+
+        let mut schema_info = SchemaInfo {
+            version,
+            node_tables: Vec::new(),
+            rel_tables: Vec::new(),
+        };
+        for table in strings(&mut db, "CALL show_tables() RETURN name, type;")? {
+            let [name, table_type] = table.as_slice() else {
+                continue;
+            };
+            let columns = strings(
+                &mut db,
+                &format!("CALL table_info('{}') RETURN name, type;", name),
+            )?
+            .into_iter()
+            .filter_map(|column| match column.as_slice() {
+                [name, column_type] => Some((name.clone(), column_type.clone())),
+                _ => None,
+            })
+            .collect();
+
+            let table_info = TableInfo {
+                name: name.clone(),
+                columns,
+            };
+            match table_type.as_str() {
+                "NODE" => schema_info.node_tables.push(table_info),
+                "REL" => schema_info.rel_tables.push(table_info),
+                _ => {}
+            }
+        }
+        schema_info.node_tables.sort_by(|a, b| a.name.cmp(&b.name));
+        schema_info.rel_tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(schema_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::LevelFilter;
+    use std::path::{Path, PathBuf};
+
+    fn init() {
+        let _ = env_logger::builder()
+            .filter_level(LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+    }
+
+    fn assert_nodes<S: GraphStore>(graph: &mut CodeGraph<S>, want_node_strings: &[&str]) {
+        let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+        let mut node_strings: Vec<_> = nodes.into_iter().map(|n| n.name).collect();
+        node_strings.sort();
+        assert_eq!(node_strings, want_node_strings);
+    }
+
+    fn assert_edges<S: GraphStore>(graph: &mut CodeGraph<S>, want_edge_strings: &[&str]) {
+        let edges = graph
+            .query_edges("MATCH (a)-[e]->(b) RETURN a.name, b.name, e".to_string())
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(edge_strings, want_edge_strings);
+    }
+
+    #[test]
+    fn test_index_go_memory_store() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), config);
+        graph.index(dir_path, false).unwrap();
+
+        assert_nodes(
+            &mut graph,
+            &[
+                "",
+                "main.go",
+                "main.go:User",
+                "main.go:User.ChangeStatus",
+                "main.go:User.DisplayInfo",
+                "main.go:User.NewUser",
+                "main.go:User.SetAddress",
+                "main.go:User.UpdateEmail",
+                "main.go:main",
+                "types.go",
+                "types.go:Address",
+                "types.go:Hobby",
+                "types.go:Status",
+            ],
+        );
+        assert_edges(
+            &mut graph,
+            &[
+                "-[contains]->main.go",
+                "-[contains]->types.go",
+                "main.go-[contains]->main.go:User",
+                "main.go-[contains]->main.go:main",
+                "main.go:User-[contains]->main.go:User.ChangeStatus",
+                "main.go:User-[contains]->main.go:User.DisplayInfo",
+                "main.go:User-[contains]->main.go:User.NewUser",
+                "main.go:User-[contains]->main.go:User.SetAddress",
+                "main.go:User-[contains]->main.go:User.UpdateEmail",
+                "main.go:main-[calls]->main.go:User.DisplayInfo",
+                "main.go:main-[calls]->main.go:User.NewUser",
+                "main.go:main-[calls]->main.go:User.UpdateEmail",
+                "types.go-[contains]->types.go:Address",
+                "types.go-[contains]->types.go:Hobby",
+                "types.go-[contains]->types.go:Status",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        use std::error::Error;
+
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let mut graph =
+            CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), Config::default());
+
+        // Only the whole repository can be indexed into the in-memory store.
+        let err = graph.index(dir_path.join("diff"), false).unwrap_err();
+        assert!(matches!(err, CodeGraphError::UnsupportedPath(ref path) if path.ends_with("diff")));
+
+        // The I/O error is kept as the source.
+        let err = graph
+            .restore_snapshot(dir_path.join("missing.bin"))
+            .unwrap_err();
+        assert!(matches!(err, CodeGraphError::Io(_)));
+        assert!(err.source().unwrap().is::<std::io::Error>());
+
+        // So is the wrapped error of the other errors.
+        let err = CodeGraphError::from(serde_json::from_str::<u32>("x").unwrap_err());
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+
+        // And the original error of the parser, rather than only its message.
+        let parse_err: Box<dyn Error + Send + Sync> = Box::new(std::fmt::Error);
+        let err = CodeGraphError::from(parse_err);
+        assert!(matches!(err, CodeGraphError::Parse(_)));
+        assert!(err.source().unwrap().is::<std::fmt::Error>());
+
+        // The errors can be sent across threads, e.g. out of a blocking task.
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CodeGraphError>();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_index_async() {
+        use tokio_stream::StreamExt;
+
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), config);
+
+        let progress: Vec<IndexProgress> = graph.index_async(dir_path, false).collect().await;
+        let mut parsed_files: Vec<(String, usize)> = progress
+            .iter()
+            .filter_map(|p| match p {
+                IndexProgress::FileParsed { file, parsed_files } => {
+                    Some((file.clone(), *parsed_files))
+                }
+                _ => None,
+            })
+            .collect();
+        parsed_files.sort();
+        assert_eq!(parsed_files.len(), 2);
+        assert_eq!(parsed_files[0].0, "main.go");
+        assert_eq!(parsed_files[1].0, "types.go");
+        assert_eq!(progress.last(), Some(&IndexProgress::Done));
+
+        assert!(!graph
+            .query_nodes("MATCH (n:Function) RETURN n".to_string())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let config = Config::default().ignore_patterns(vec!["ignored.go".into(), "vendor".into()]);
+        std::fs::write(repo_path.join(".gitignore"), "generated/\n").unwrap();
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+        graph.index(repo_path.clone(), true).unwrap();
+
+        // Create the files once the watching has started.
+        let writer_path = repo_path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            std::fs::create_dir_all(writer_path.join("vendor")).unwrap();
+            std::fs::create_dir_all(writer_path.join("generated")).unwrap();
+            for file in [
+                "ignored.go",
+                "vendor/lib.go",
+                "generated/gen.go",
+                "greet.go",
+            ] {
+                let func_name = file.trim_end_matches(".go").replace('/', "_");
+                std::fs::write(
+                    writer_path.join(file),
+                    format!("package main\n\nfunc {}() {{}}\n", func_name),
+                )
+                .unwrap();
+            }
+        });
+
+        // Watch in another thread, so that the test fails rather than hangs if the events never come.
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let watching = std::thread::spawn(move || {
+            let mut events: Vec<WatchEvent> = Vec::new();
+            graph
+                .watch(|event| {
+                    let indexed = event == WatchEvent::Indexed("greet.go".to_string());
+                    events.push(event);
+                    !indexed
+                })
+                .unwrap();
+            let _ = done_tx.send(());
+            (graph, events)
+        });
+        done_rx
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .expect("timed out waiting for greet.go to be indexed");
+        let (mut graph, events) = watching.join().unwrap();
+        writer.join().unwrap();
+
+        // The files excluded by the ignore patterns (directly or via the parent directory) and by `.gitignore`
+        // are skipped.
+        assert!(events
+            .iter()
+            .all(|event| *event == WatchEvent::Indexed("greet.go".to_string())));
+        assert_nodes(&mut graph, &[".", "greet.go", "greet.go:greet"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_node_types() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.go".into(), "!main.go".into()])
+            .node_types(HashSet::from([NodeType::Directory, NodeType::File]));
+        let mut graph = CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), config);
+        graph.index(dir_path, false).unwrap();
+
+        assert!(graph
+            .query_nodes("MATCH (n:Function) RETURN n".to_string())
+            .unwrap()
+            .is_empty());
+        assert_nodes(&mut graph, &["", "main.go", "types.go"]);
+        assert_edges(
+            &mut graph,
+            &["-[contains]->main.go", "-[contains]->types.go"],
+        );
+    }
+
+    #[test]
+    fn test_index_cancelled() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.go".into(), "!main.go".into()])
+            .cancel_flag(cancel_flag.clone());
+        let mut graph = CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), config);
+        graph.index(dir_path.clone(), false).unwrap();
+        let indexed_nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+
+        // Re-index from scratch, but cancel once the first file is parsed.
+        let parsed_files = Arc::new(AtomicUsize::new(0));
+        let mut parser = Parser::new(dir_path.clone(), graph.config.clone());
+        let (flag, count) = (cancel_flag.clone(), parsed_files.clone());
+        parser.on_file_parsed(Box::new(move |_| {
+            count.fetch_add(1, Ordering::Relaxed);
+            flag.store(true, Ordering::Relaxed);
+        }));
+        let err = graph.index_with_parser(parser, dir_path, true).unwrap_err();
+
+        assert!(matches!(err, CodeGraphError::Cancelled));
+        assert_eq!(parsed_files.load(Ordering::Relaxed), 1);
+        // The previously indexed nodes are left as they were.
+        let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+        assert_eq!(nodes.len(), indexed_nodes.len());
+    }
+
+    #[test]
+    fn test_primary_language() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let examples_path = PathBuf::from(manifest_dir).join("examples");
+
+        let primary_language = |dir_path: PathBuf, config: Config| {
+            let mut graph = CodeGraph::with_store(MemoryStore::new(), dir_path.clone(), config);
+            graph.index(dir_path, false).unwrap();
+            graph.primary_language().unwrap()
+        };
+
+        assert_eq!(
+            primary_language(examples_path.join("go").join("demo"), Config::default()),
+            Some(Language::Go)
+        );
+        assert_eq!(
+            primary_language(examples_path.join("mixed"), Config::default()),
+            Some(Language::Go)
+        );
+        // Each language has a single definition, so the first one by name wins.
+        let config = Config::default().ignore_patterns(vec!["*.go".into()]);
+        assert_eq!(
+            primary_language(examples_path.join("mixed"), config),
+            Some(Language::Python)
+        );
+
+        let mut graph =
+            CodeGraph::with_store(MemoryStore::new(), examples_path.clone(), Config::default());
+        assert_eq!(graph.primary_language().unwrap(), None);
+    }
+
+    #[test]
+    fn test_index_without_root_node() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db_without_root_node");
+
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.go".into(), "!main.go".into()])
+            .emit_root_node(false);
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let nodes = graph
+            .query_nodes("MATCH (n:Directory) RETURN n".to_string())
+            .unwrap();
+        assert!(nodes.is_empty());
+
+        let edges = graph
+            .query_edges(
+                r#"MATCH (a)-[e:CONTAINS]->(b:File) RETURN a.full_name, b.full_name, e"#
+                    .to_string(),
+            )
+            .unwrap();
+        assert!(edges.is_empty());
+
+        // The top-level files are still indexed, along with their definitions.
+        let files = graph
+            .query_nodes("MATCH (n:File) RETURN n".to_string())
+            .unwrap();
+        let mut file_names: Vec<String> = files.into_iter().map(|n| n.name).collect();
+        file_names.sort();
+        assert_eq!(file_names, ["main.go", "types.go"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_go() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        // validate data
+        assert_nodes(
+            &mut graph,
+            &[
+                ".",
+                "main.go",
+                "main.go:User",
+                "main.go:User.ChangeStatus",
+                "main.go:User.DisplayInfo",
+                "main.go:User.NewUser",
+                "main.go:User.SetAddress",
+                "main.go:User.UpdateEmail",
+                "main.go:main",
+                "types.go",
+                "types.go:Address",
+                "types.go:Hobby",
+                "types.go:Status",
+            ],
+        );
+        assert_edges(
+            &mut graph,
+            &[
+                ".-[contains]->main.go",
+                ".-[contains]->types.go",
+                "main.go-[contains]->main.go:User",
+                "main.go-[contains]->main.go:main",
+                "main.go:User-[contains]->main.go:User.ChangeStatus",
+                "main.go:User-[contains]->main.go:User.DisplayInfo",
+                "main.go:User-[contains]->main.go:User.NewUser",
+                "main.go:User-[contains]->main.go:User.SetAddress",
+                "main.go:User-[contains]->main.go:User.UpdateEmail",
+                "main.go:User-[references]->types.go:Address",
+                "main.go:User.ChangeStatus-[references]->types.go:Status",
+                "main.go:User.SetAddress-[references]->types.go:Address",
+                "main.go:User.SetAddress-[references]->types.go:Hobby",
+                "main.go:main-[calls]->main.go:User.DisplayInfo",
+                "main.go:main-[calls]->main.go:User.NewUser",
+                "main.go:main-[calls]->main.go:User.UpdateEmail",
+                "types.go-[contains]->types.go:Address",
+                "types.go-[contains]->types.go:Hobby",
+                "types.go-[contains]->types.go:Status",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_with_extensions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir).join("examples").join("mixed");
+        let db_path = repo_path.join("kuzu_db_extensions");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph
+            .index_with_extensions(repo_path, false, vec!["go".to_string()])
+            .unwrap();
+
+        // The TypeScript and Python files are not indexed.
+        assert_nodes(
+            &mut graph,
+            &[".", "main.go", "main.go:greet", "main.go:main"],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_go_diagnostics() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_diagnostics");
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let diagnostics_path = temp_dir.path().join("diagnostics.jsonl");
+        let config = Config::default().diagnostics_path(diagnostics_path.clone());
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+
+        // One line per indexed file.
+        let diagnostics: Vec<serde_json::Value> = std::fs::read_to_string(&diagnostics_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let mut files: Vec<_> = diagnostics
+            .iter()
+            .map(|d| d["file"].as_str().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["main.go", "types.go"]);
+
+        let main_diagnostics = diagnostics.iter().find(|d| d["file"] == "main.go").unwrap();
+        assert_eq!(main_diagnostics["node_count"], 7);
+        assert!(main_diagnostics["parse_time_ms"].as_f64().unwrap() >= 0.0);
+        assert!(main_diagnostics["error"].is_null());
+
+        // Another run appends to the diagnostics of the previous one.
+        graph.index(repo_path, true).unwrap();
+        let line_count = std::fs::read_to_string(&diagnostics_path)
+            .unwrap()
+            .lines()
+            .count();
+        assert_eq!(line_count, 4);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_index_diagnostics_on_error() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            repo_path.join("main.go"),
+            "package main\n\nfunc main() {}\n",
+        )
+        .unwrap();
+        // A dangling symbolic link, which fails to be read.
+        std::os::unix::fs::symlink(repo_path.join("missing.go"), repo_path.join("broken.go"))
+            .unwrap();
+
+        let diagnostics_dir = tempfile::tempdir().unwrap();
+        let diagnostics_path = diagnostics_dir.path().join("diagnostics.jsonl");
+        let read_diagnostics = || -> Vec<serde_json::Value> {
+            let diagnostics = std::fs::read_to_string(&diagnostics_path).unwrap();
+            std::fs::remove_file(&diagnostics_path).unwrap();
+            diagnostics
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+        let index = |config: Config| {
+            let mut graph = CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), config);
+            graph.index(repo_path.clone(), false)
+        };
+
+        // The diagnostics are written even if the indexing fails.
+        let config = Config::default().diagnostics_path(diagnostics_path.clone());
+        assert!(index(config.clone()).is_err());
+        let diagnostics = read_diagnostics();
+        let broken = diagnostics
+            .iter()
+            .find(|d| d["file"] == "broken.go")
+            .unwrap();
+        assert!(broken["error"].as_str().unwrap().contains("No such file"));
+
+        // Or the broken file is skipped.
+        assert!(index(config.continue_on_error(true)).is_ok());
+        let diagnostics = read_diagnostics();
+        let broken = diagnostics
+            .iter()
+            .find(|d| d["file"] == "broken.go")
+            .unwrap();
+        assert!(!broken["error"].is_null());
+        let main = diagnostics.iter().find(|d| d["file"] == "main.go").unwrap();
+        assert!(main["error"].is_null());
+    }
+
+    #[test]
+    fn test_index_go_concurrently() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_concurrent");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path.clone(), repo_path.clone(), config.clone());
+        graph.clean(true).unwrap();
+
+        // Two graphs on the same database, re-indexing the repository at the same time.
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let (db_path, repo_path, config) =
+                    (db_path.clone(), repo_path.clone(), config.clone());
+                std::thread::spawn(move || {
+                    let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+                    graph.index(repo_path, true).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // The indexing operations are serialized, so the graph is the same as a single indexing.
+        assert_nodes(
+            &mut graph,
+            &[
+                ".",
+                "main.go",
+                "main.go:User",
+                "main.go:User.ChangeStatus",
+                "main.go:User.DisplayInfo",
+                "main.go:User.NewUser",
+                "main.go:User.SetAddress",
+                "main.go:User.UpdateEmail",
+                "main.go:main",
+                "types.go",
+                "types.go:Address",
+                "types.go:Hobby",
+                "types.go:Status",
+            ],
+        );
+        assert_edges(
+            &mut graph,
+            &[
+                ".-[contains]->main.go",
+                ".-[contains]->types.go",
+                "main.go-[contains]->main.go:User",
+                "main.go-[contains]->main.go:main",
+                "main.go:User-[contains]->main.go:User.ChangeStatus",
+                "main.go:User-[contains]->main.go:User.DisplayInfo",
+                "main.go:User-[contains]->main.go:User.NewUser",
+                "main.go:User-[contains]->main.go:User.SetAddress",
+                "main.go:User-[contains]->main.go:User.UpdateEmail",
+                "main.go:User-[references]->types.go:Address",
+                "main.go:User.ChangeStatus-[references]->types.go:Status",
+                "main.go:User.SetAddress-[references]->types.go:Address",
+                "main.go:User.SetAddress-[references]->types.go:Hobby",
+                "main.go:main-[calls]->main.go:User.DisplayInfo",
+                "main.go:main-[calls]->main.go:User.NewUser",
+                "main.go:main-[calls]->main.go:User.UpdateEmail",
+                "types.go-[contains]->types.go:Address",
+                "types.go-[contains]->types.go:Hobby",
+                "types.go-[contains]->types.go:Status",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_go_grammar_version() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_grammar_version");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let file_nodes = graph
+            .query_nodes("MATCH (file:File) RETURN file".to_string())
+            .unwrap();
+        assert_eq!(file_nodes.len(), 2);
+        for node in file_nodes {
+            assert_eq!(node.grammar_version, Language::Go.grammar_version());
+        }
+        assert!(graph.outdated_grammar_files().unwrap().is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_unreferenced_definitions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-unused");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .unreferenced_definitions()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        // The unexported `localHelper` is not reported.
+        assert_eq!(names, ["types.ts:unusedHelper"]);
+
+        graph.clean(true).unwrap();
+
+        // Neither are Go's `init` functions and the unexported functions.
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go-unused");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .unreferenced_definitions()
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, ["main.go:Greet"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_typescript_import_summary() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = repo_path.join("kuzu_db_import_summary");
+
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.ts".into(), "!main.ts".into()])
+            .store_import_summary(true);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), true).unwrap();
+
+        let query = r#"MATCH (file:File) WHERE file.name = "main.ts" RETURN file"#;
+        let file_nodes = graph.query_nodes(query.to_string()).unwrap();
+        assert_eq!(file_nodes[0].imported_files, ["types.ts"]);
+
+        // Keep the summary in sync on upsert.
+        graph.index(repo_path.join("main.ts"), false).unwrap();
+        let file_nodes = graph.query_nodes(query.to_string()).unwrap();
+        assert_eq!(file_nodes[0].imported_files, ["types.ts"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_file_skips_unchanged_content() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let file_path = repo_path.join("main.go");
+        std::fs::write(&file_path, "package main\n\nfunc main() {}\n").unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+        let prepare_count = |graph: &CodeGraph| graph.db().unwrap().prepare_count();
+
+        graph.index(file_path.clone(), false).unwrap();
+        let indexed_prepare_count = prepare_count(&graph);
+        assert!(indexed_prepare_count > 0);
+
+        // Re-indexing the unchanged file runs no upserts at all.
+        graph.index(file_path.clone(), false).unwrap();
+        assert_eq!(prepare_count(&graph), indexed_prepare_count);
+
+        // Unless it is forced.
+        graph.index(file_path.clone(), true).unwrap();
+        assert!(prepare_count(&graph) > indexed_prepare_count);
+
+        // Or the file is changed.
+        std::fs::write(&file_path, "package main\n\nfunc run() {}\n").unwrap();
+        graph.index(file_path.clone(), false).unwrap();
+        assert_nodes(&mut graph, &["main.go", "main.go:run"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_file_repairs_unresolved_imports() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(
+            repo_path.join("greet.ts"),
+            "export function greet(name: string): string {\n  return name;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo_path.join("main.ts"),
+            "import { greet } from './greet';\n",
+        )
+        .unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        // The consumer is indexed first, so its import can not be resolved.
+        graph.index(repo_path.join("main.ts"), false).unwrap();
+        assert_edges(&mut graph, &[]);
+        let query = r#"MATCH (file:File) WHERE file.name = "main.ts" RETURN file"#;
+        let file_nodes = graph.query_nodes(query.to_string()).unwrap();
+        assert_eq!(file_nodes[0].unresolved_imports, ["greet.ts:greet"]);
+
+        // Indexing the provider repairs the import.
+        graph.index(repo_path.join("greet.ts"), false).unwrap();
+        assert_edges(
+            &mut graph,
+            &[
+                "greet.ts-[contains]->greet.ts:greet",
+                "main.ts-[imports]->greet.ts:greet",
+            ],
+        );
+        let file_nodes = graph.query_nodes(query.to_string()).unwrap();
+        assert!(file_nodes[0].unresolved_imports.is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_file_resolves_imports_from_db() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(repo_path.join("models.py"), "class User:\n    pass\n").unwrap();
+        std::fs::write(repo_path.join("main.py"), "from models import User\n").unwrap();
+        std::fs::create_dir(repo_path.join("src")).unwrap();
+        std::fs::write(repo_path.join("src/models.rs"), "pub struct User;\n").unwrap();
+        std::fs::write(
+            repo_path.join("src/main.rs"),
+            "mod models;\n\nuse crate::models::User;\n",
+        )
+        .unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+        let import_edges = |graph: &mut CodeGraph| -> Vec<String> {
+            let edges = graph
+                .query_edges("MATCH (a)-[e:IMPORTS]->(b) RETURN a.name, b.name, e".to_string())
+                .unwrap();
+            let mut edge_strings: Vec<_> = edges
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+        let want_edge_strings = [
+            "main.py-[imports]->models.py:User",
+            "src/main.rs-[imports]->src/models.rs",
+        ];
+
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_eq!(import_edges(&mut graph), want_edge_strings);
+
+        // Re-indexing the importing files alone keeps their imports of the (unparsed) indexed files.
+        graph.index(repo_path.join("main.py"), true).unwrap();
+        graph.index(repo_path.join("src/main.rs"), true).unwrap();
+        assert_eq!(import_edges(&mut graph), want_edge_strings);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_file_resolves_calls_from_db() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let examples_path = PathBuf::from(manifest_dir).join("examples");
+        let call_edges = |graph: &mut CodeGraph| -> Vec<String> {
+            let edges = graph
+                .query_edges("MATCH (a)-[e:CALLS]->(b) RETURN a.name, b.name, e".to_string())
+                .unwrap();
+            let mut edge_strings: Vec<_> = edges
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+
+        for (repo_path, file_name, want_edge_strings) in [
+            (
+                examples_path.join("go").join("crosscall"),
+                "main.go",
+                vec![
+                    "main.go:main-[calls]->main.go:greet",
+                    "main.go:main-[calls]->models/user.go:User.NewUser",
+                ],
+            ),
+            (
+                examples_path.join("typescript-calls"),
+                "main.ts",
+                vec![
+                    "main.ts:run-[calls]->main.ts:log",
+                    "main.ts:run-[calls]->utils.ts:formatName",
+                    "main.ts:run-[calls]->utils.ts:shout",
+                ],
+            ),
+        ] {
+            let db_path = repo_path.join("kuzu_db");
+            let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+            graph.clean(true).unwrap();
+            graph.index(repo_path.clone(), false).unwrap();
+            assert_eq!(call_edges(&mut graph), want_edge_strings);
+
+            // Re-indexing the calling file alone keeps its calls to the (unparsed) indexed files.
+            graph.index(repo_path.join(file_name), true).unwrap();
+            assert_eq!(call_edges(&mut graph), want_edge_strings);
+
+            graph.clean(true).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_index_go_stdlib_references() {
+        init();
+
+        // The standard library is located by `go env GOROOT`.
+        let Ok(go_root) = util::get_go_root() else {
+            return;
+        };
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go-stdlib");
+        let db_path = repo_path.join("kuzu_db");
+
+        let references = |graph: &mut CodeGraph| -> Vec<String> {
+            let stmt = r#"MATCH (a)-[e:REFERENCES]->(b) WHERE a.name = "main.go:Elapsed" RETURN a.name, b.name, e"#;
+            graph
+                .query_edges(stmt.to_string())
+                .unwrap()
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect()
+        };
+
+        // The standard library is not indexed by default.
+        let mut graph = CodeGraph::new(db_path.clone(), repo_path.clone(), Config::default());
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+        assert!(references(&mut graph).is_empty());
+        drop(graph);
+
+        let config = Config::default().index_go_stdlib(true);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+        let want_edge_string = format!(
+            "main.go:Elapsed-[references]->{}:Time",
+            PathBuf::from(go_root)
+                .join("src")
+                .join("time")
+                .join("time.go")
+                .display()
+        );
+        let references = references(&mut graph);
+        assert!(references.contains(&want_edge_string), "{:?}", references);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_go_aliased_imports() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("alias");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let edges = graph
+            .query_edges("MATCH (a)-[e:REFERENCES]->(b) RETURN a.name, b.name, e".to_string())
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:Save-[references]->legacy/models/item.go:Item",
+                "main.go:Save-[references]->models/user.go:User",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_go_type_parameter_constraints() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("generics");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let edges = graph
+            .query_edges("MATCH (a)-[e:REFERENCES]->(b) RETURN a.name, b.name, e".to_string())
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:Max-[references]->constraints.go:Ordered",
+                "main.go:Sum-[references]->constraints.go:Number",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_import_edges() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = repo_path.join("kuzu_db_import_edges");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.ts".into(),
+            "!main.ts".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        // The five symbols imported from `types.ts` are collapsed into one pair.
+        assert_eq!(
+            graph.import_edges().unwrap(),
+            [("main.ts".to_string(), "types.ts".to_string())],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_search_docs() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("docs");
+        let db_path = dir_path.join("kuzu_db_search_docs");
+
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let names = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(graph.search_docs("common divisor".to_string()).unwrap()),
+            vec!["math.ts:gcd"],
+        );
+        assert_eq!(
+            names(graph.search_docs("Euclidean".to_string()).unwrap()),
+            vec!["math.ts:Vector.length", "math.ts:gcd"],
+        );
+        assert_eq!(
+            names(graph.search_docs("radius".to_string()).unwrap()),
+            vec!["shapes.py:Circle"],
+        );
+        assert!(graph
+            .search_docs("no such doc".to_string())
+            .unwrap()
+            .is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_chunks() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db_chunks");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let max_chars = 200;
+        let chunks = graph.chunks(max_chars).unwrap();
+        assert!(chunks.iter().all(|c| c.text.chars().count() <= max_chars));
+
+        let functions = graph
+            .query_nodes("MATCH (n:Function) RETURN n".to_string())
+            .unwrap();
+        assert!(!functions.is_empty());
+        for func in functions {
+            let func_chunks: Vec<&Chunk> = chunks.iter().filter(|c| c.name == func.name).collect();
+            assert!(!func_chunks.is_empty(), "{}", func.name);
+            for chunk in func_chunks {
+                assert!(chunk.text.starts_with(&format!("{}\n", func.name)));
+                assert!(func.start_line <= chunk.start_line && chunk.end_line <= func.end_line);
+            }
+        }
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_node_chunks() {
+        let mut node = Node::from_type_and_name(NodeType::Function, "main.go:Add".to_string());
+        node.start_line = 3;
+        node.end_line = 5;
+        node.code = "func Add(a, b int) int {\n\treturn a + b\n}".to_string();
+
+        let chunks = node_chunks(&node, 100);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, format!("main.go:Add\n{}", node.code));
+
+        // The doc comment precedes the code.
+        node.doc_comment = "// Add returns the sum of a and b.".to_string();
+        let chunks = node_chunks(&node, 100);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0].text,
+            format!("main.go:Add\n{}\n{}", node.doc_comment, node.code)
+        );
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (3, 5));
+    }
+
+    #[test]
+    fn test_incident_edges() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = repo_path.join("kuzu_db_incident_edges");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.ts".into(),
+            "!main.ts".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let edge_strings = |edges: Vec<Edge>| -> Vec<String> {
+            edges
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect()
+        };
+        let (outgoing, incoming) = graph
+            .incident_edges("types.ts:UserService".to_string())
+            .unwrap();
+        assert_eq!(
+            edge_strings(outgoing),
+            [
+                "types.ts:UserService-[contains]->types.ts:UserService.constructor",
+                "types.ts:UserService-[contains]->types.ts:UserService.filterUsers",
+                "types.ts:UserService-[contains]->types.ts:UserService.getUser",
+            ],
+        );
+        assert_eq!(
+            edge_strings(incoming),
+            [
+                "main.ts-[imports]->types.ts:UserService",
+                "main.ts:fetchUserData-[references]->types.ts:UserService",
+                "types.ts-[contains]->types.ts:UserService",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_coupling_metrics() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-layers");
+        let db_path = repo_path.join("kuzu_db_coupling_metrics");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let metrics: Vec<(String, usize, usize, f64)> = graph
+            .coupling_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.dir, m.afferent, m.efferent, m.instability))
+            .collect();
+        // `core` is a leaf package, which is only imported by the others (and imports within itself
+        // are not counted), so it is maximally stable.
+        assert_eq!(
+            metrics,
+            [
+                ("api".to_string(), 0, 3, 1.0),
+                ("core".to_string(), 3, 0, 0.0),
+                ("service".to_string(), 1, 1, 0.5),
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_init_functions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("init");
+        let db_path = repo_path.join("kuzu_db_init_functions");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let init_names: Vec<_> = graph
+            .init_functions("".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|node| node.name)
+            .collect();
+        assert_eq!(
+            init_names,
+            ["config.go:init#1", "main.go:init#1", "main.go:init#2"],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_node_metadata() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("calltree");
+        let db_path = repo_path.join("kuzu_db_node_metadata");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        assert!(graph
+            .get_node_metadata("main.go:main".to_string())
+            .unwrap()
+            .is_empty());
+
+        graph
+            .set_node_metadata("main.go:main".into(), "owner".into(), "team-x".into())
+            .unwrap();
+        graph
+            .set_node_metadata("main.go:main".into(), "coverage".into(), "80%".into())
+            .unwrap();
+        let metadata = graph.get_node_metadata("main.go:main".to_string()).unwrap();
+        assert_eq!(
+            metadata,
+            BTreeMap::from([
+                ("coverage".to_string(), "80%".to_string()),
+                ("owner".to_string(), "team-x".to_string()),
+            ]),
+        );
+
+        // The metadata is also available on the queried nodes.
+        let nodes = graph
+            .query_nodes(r#"MATCH (f:Function) WHERE f.name = "main.go:main" RETURN f;"#.into())
+            .unwrap();
+        assert_eq!(nodes[0].metadata["owner"], "team-x");
+
+        assert!(graph
+            .set_node_metadata("main.go:missing".into(), "owner".into(), "team-x".into())
+            .is_err());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_read_after_write() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("calltree");
+        let db_path = repo_path.join("kuzu_db_read_after_write");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        // Write through the store, and read back immediately through the graph (i.e. new connections).
+        let mut node = Node::from_type_and_name(NodeType::Function, "main.go:helper".into());
+        node.language = Language::Go;
+        node.code = "func helper() {}".into();
+        let file_node = Node::from_type_and_name(NodeType::File, "main.go".into());
+        {
+            let mut db = graph.db().unwrap();
+            db.upsert_nodes(&vec![node.clone()]).unwrap();
+            db.upsert_edges(&vec![Edge {
+                r#type: EdgeType::Contains,
+                from: file_node,
+                to: node,
+                import: None,
+                alias: None,
+                properties: indexmap::IndexMap::new(),
+            }])
+            .unwrap();
+        }
+
+        let nodes = graph
+            .query_nodes(r#"MATCH (f:Function) WHERE f.name = "main.go:helper" RETURN f;"#.into())
+            .unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].code, "func helper() {}");
+        let (_, incoming_edges) = graph.incident_edges("main.go:helper".into()).unwrap();
+        assert_eq!(
+            incoming_edges
+                .iter()
+                .map(|e| format!("{}-[{}]->{}", e.from.name, e.r#type, e.to.name))
+                .collect::<Vec<_>>(),
+            ["main.go-[contains]->main.go:helper"],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_derived_fields() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("calltree");
+        let db_path = repo_path.join("kuzu_db_refresh_derived_fields");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let stmt = r#"MATCH (f:Function) WHERE f.name = "main.go:main" RETURN f;"#;
+        let skeleton_code = graph.query_nodes(stmt.into()).unwrap()[0]
+            .skeleton_code
+            .clone();
+
+        // Corrupt the derived fields.
+        graph
+            .db()
+            .unwrap()
+            .query(
+                r#"MATCH (f:Function) WHERE f.name = "main.go:main" SET f.short_name = "stale", f.skeleton_code = "stale";"#,
+            )
+            .unwrap();
+
+        let count = graph.refresh_derived_fields().unwrap();
+        assert_eq!(
+            count,
+            graph
+                .query_nodes("MATCH (n) RETURN n;".into())
+                .unwrap()
+                .len()
+        );
+
+        let mut short_names: Vec<String> = Vec::new();
+        if let Some(result) = graph
+            .db()
+            .unwrap()
+            .query(r#"MATCH (f:Function) WHERE f.name = "main.go:main" RETURN f.short_name;"#)
+            .unwrap()
+        {
+            for row in result {
+                short_names.push(row[0].to_string());
+            }
+        }
+        assert_eq!(short_names, vec!["main".to_string()]);
+        assert_eq!(
+            graph.query_nodes(stmt.into()).unwrap()[0].skeleton_code,
+            skeleton_code
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_schema_info() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir).join("examples").join("go");
+        let db_path = repo_path.join("kuzu_db_schema_info");
+
+        let mut graph = CodeGraph::new(db_path, repo_path, Config::default());
+        graph.clean(true).unwrap();
+
+        let schema_info = graph.schema_info().unwrap();
+        assert!(!schema_info.version.is_empty());
+
+        let table_names = |tables: &Vec<TableInfo>| -> Vec<String> {
+            tables.iter().map(|table| table.name.clone()).collect()
+        };
+        let node_table_names = table_names(&schema_info.node_tables);
+        assert!(node_table_names.contains(&"File".to_string()));
+        assert!(node_table_names.contains(&"Function".to_string()));
+        assert!(table_names(&schema_info.rel_tables).contains(&"CONTAINS".to_string()));
+
+        let file_table = schema_info
+            .node_tables
+            .iter()
+            .find(|table| table.name == "File")
+            .unwrap();
+        assert!(file_table
+            .columns
+            .contains(&("name".to_string(), "STRING".to_string())));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_stats() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_stats");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+        graph.clean(true).unwrap();
+
+        // The stats are cached before indexing, and then maintained by the writes.
+        let stats = graph.stats(false).unwrap();
+        assert_eq!((stats.node_count(), stats.edge_count()), (0, 0));
+
+        graph.index(repo_path.clone(), false).unwrap();
+        let stats = graph.stats(false).unwrap();
+        assert_eq!((stats.node_count(), stats.edge_count()), (13, 15));
+        assert_eq!(stats, graph.stats(true).unwrap());
+
+        // Deleting nodes also deletes their edges.
+        graph.clean_path(repo_path.join("types.go")).unwrap();
+        let stats = graph.stats(false).unwrap();
+        assert!(stats.node_count() < 13);
+        assert_eq!(stats, graph.stats(true).unwrap());
+
+        graph.clean(true).unwrap();
+
+        // Re-indexing a file deletes its out-going edges, e.g. all the imports.
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(repo_path.join("user.ts"), "export interface User {}\n").unwrap();
+        std::fs::write(
+            repo_path.join("main.ts"),
+            "import { User } from './user';\n\nexport function greet(user: User) {}\n",
+        )
+        .unwrap();
+        let mut graph = CodeGraph::new(
+            repo_path.join("kuzu_db"),
+            repo_path.clone(),
+            Config::default(),
+        );
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_eq!(graph.stats(false).unwrap().edges["IMPORTS"], 1);
+
+        std::fs::write(
+            repo_path.join("main.ts"),
+            "export function greet(name: string) {}\n",
+        )
+        .unwrap();
+        graph.index(repo_path.join("main.ts"), false).unwrap();
+        let stats = graph.stats(false).unwrap();
+        assert_eq!(stats.edges["IMPORTS"], 0);
+        assert_eq!(stats, graph.stats(true).unwrap());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_call_tree() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("calltree");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let tree = graph.call_tree("main.go:main".to_string(), 0).unwrap();
+        assert_eq!(
+            serde_json::to_value(&tree).unwrap(),
+            serde_json::json!({
+                "name": "main.go:main",
+                "calls": [{
+                    "name": "main.go:a",
+                    "calls": [{
+                        "name": "main.go:b",
+                        "calls": [{ "name": "main.go:a", "calls": [] }],
+                    }],
+                }],
+            }),
+        );
+
+        let tree = graph.call_tree("main.go:main".to_string(), 1).unwrap();
+        assert_eq!(
+            tree,
+            CallTree {
+                name: "main.go:main".to_string(),
+                calls: vec![CallTree {
+                    name: "main.go:a".to_string(),
+                    calls: vec![],
+                }],
+            },
+        );
+
+        assert!(graph.call_tree("main.go:c".to_string(), 0).is_err());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_get_package_skeleton() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_skeleton");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let main_skeleton = graph.get_file_skeleton("main.go".to_string()).unwrap();
+        assert!(main_skeleton.starts_with("type User struct {"));
+        assert!(main_skeleton.contains("func (u *User) DisplayInfo() {\n...\n}"));
+        assert!(!main_skeleton.contains("fmt.Printf"));
+
+        let types_skeleton = graph.get_file_skeleton("types.go".to_string()).unwrap();
+        assert!(types_skeleton.contains("Hobby struct {"));
+
+        // The files are ordered by name.
+        let skeleton = graph.get_package_skeleton(".".to_string()).unwrap();
+        assert_eq!(skeleton, format!("{}\n\n{}", main_skeleton, types_skeleton));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_export_sqlite() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_sqlite");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sqlite_path = temp_dir.path().join("graph.sqlite");
+        graph.export_sqlite(sqlite_path.clone()).unwrap();
+
+        let conn = rusqlite::Connection::open(&sqlite_path).unwrap();
+        let node_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(node_count, 13);
+        let method_count: i64 = conn
+            .query_row(
+                r#"SELECT COUNT(*) FROM edges WHERE "from" = 'main.go:User' AND type = 'contains'"#,
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(method_count, 5);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_export_cypher() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_cypher");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let script = graph.export_cypher().unwrap();
+        let node_count = script
+            .lines()
+            .filter(|line| line.starts_with("CREATE ("))
+            .count();
+        assert_eq!(node_count, 13);
+        let rel_count = script
+            .lines()
+            .filter(|line| line.starts_with("MATCH ") && line.contains(" CREATE (a)-["))
+            .count();
+        assert_eq!(rel_count, 15);
+        // The quotes and newlines in the code are escaped.
+        assert!(script.contains(r#"fmt.Printf("User ID: %d\\n", u.ID)\n"#));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_export_json() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = repo_path.join("kuzu_db_json");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.ts".into(),
+            "!main.ts".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        graph.export_json(&mut output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        let all_nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+        let nodes = json["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), all_nodes.len());
+        let names: HashSet<&str> = nodes
+            .iter()
+            .map(|node| node["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains("main.ts"));
+        assert!(names.contains("types.ts:User"));
+
+        let edges = json["edges"].as_array().unwrap();
+        assert!(!edges.is_empty());
+        for edge in edges {
+            for key in ["from", "to", "type", "import", "alias", "_label"] {
+                assert!(edge.get(key).is_some(), "missing {} in {}", key, edge);
+            }
+            assert!(names.contains(edge["from"].as_str().unwrap()));
+            assert!(names.contains(edge["to"].as_str().unwrap()));
+        }
+        // The edges can be read back.
+        let edge: HashMap<String, serde_json::Value> =
+            serde_json::from_value(edges[0].clone()).unwrap();
+        assert!(Edge::from_dict(&edge).is_ok());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_import_json() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_import_json");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let nodes_stmt = "MATCH (n) RETURN n ORDER BY n.full_name".to_string();
+        let edges_stmt =
+            "MATCH (a)-[e]->(b) RETURN a.full_name, b.full_name, e ORDER BY a.full_name, b.full_name"
+                .to_string();
+        let dump = |graph: &mut CodeGraph| {
+            let nodes: Vec<_> = graph
+                .query_nodes(nodes_stmt.clone())
+                .unwrap()
+                .iter()
+                .map(|n| n.to_dict())
+                .collect();
+            let mut edges: Vec<_> = graph
+                .query_edges(edges_stmt.clone())
+                .unwrap()
+                .iter()
+                .map(|e| {
+                    format!(
+                        "{}-[{}]->{} {:?}",
+                        e.from.name,
+                        e.r#type,
+                        e.to.name,
+                        e.to_dict()
+                    )
+                })
+                .collect();
+            edges.sort();
+            (nodes, edges)
+        };
+        let (nodes, edges) = dump(&mut graph);
+
+        let mut output: Vec<u8> = Vec::new();
+        graph.export_json(&mut output).unwrap();
+        graph.clean(true).unwrap();
+        graph.import_json(output.as_slice()).unwrap();
+        assert_eq!(dump(&mut graph), (nodes, edges));
+
+        // The edges to the unknown nodes are rejected.
+        let mut json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        json["edges"][0]["to"] = serde_json::Value::String("unknown.go".to_string());
+        let err = graph.import_json(json.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(err, CodeGraphError::NotFound(name) if name == "unknown.go"));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_export_dot() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_dot");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let dot = graph.export_dot().unwrap();
+        assert!(dot.starts_with("digraph codegraph {"));
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+
+        // Returns the quoted IDs at the start of the given statement, e.g. `"a" -> "b" [...]` => [a, b].
+        let ids = |stmt: &str| -> Vec<String> {
+            let mut ids = Vec::new();
+            let mut rest = stmt.trim();
+            while let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap();
+                ids.push(quoted[..end].to_string());
+                rest = quoted[end + 1..].trim_start();
+                rest = rest.strip_prefix("->").unwrap_or(rest).trim_start();
+            }
+            ids
+        };
+        let mut declared: HashSet<String> = HashSet::new();
+        let mut endpoints: Vec<String> = Vec::new();
+        for stmt in dot.lines() {
+            match ids(stmt).as_slice() {
+                [id] => {
+                    declared.insert(id.clone());
+                }
+                [from, to] => endpoints.extend([from.clone(), to.clone()]),
+                _ => {}
+            }
+        }
+        assert_eq!(declared.len(), 13);
+        assert_eq!(endpoints.len(), 15 * 2);
+        assert!(endpoints.iter().all(|id| declared.contains(id)));
+        assert!(dot.contains(
+            r#""main.go:User" -> "main.go:User.DisplayInfo" [label="contains", style=dashed, color=gray50];"#
+        ));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_export_graphml() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_graphml");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        graph.export_graphml(&mut output).unwrap();
+        let graphml = String::from_utf8(output).unwrap();
+
+        assert!(graphml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert_eq!(graphml.matches("<node id=").count(), 13);
+        assert_eq!(graphml.matches("<edge id=").count(), 15);
+        assert!(graphml.contains(r#"<node id="main.go:User">"#));
+        // The quotes in the code are escaped.
+        assert!(graphml.contains(r#"fmt.Printf(&quot;User ID: %d\n&quot;, u.ID)"#));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let snapshot_path = temp_dir.path().join("graph.snapshot");
+
+        let mut graph =
+            CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), Config::default());
+        graph.index(repo_path.clone(), true).unwrap();
+        graph.save_snapshot(snapshot_path.clone()).unwrap();
+
+        let mut restored =
+            CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), Config::default());
+        restored.restore_snapshot(snapshot_path).unwrap();
+
+        let node_values = |graph: &mut CodeGraph<MemoryStore>| {
+            let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+            let mut values: Vec<_> = nodes
+                .into_iter()
+                .map(|n| serde_json::to_value(n).unwrap())
+                .collect();
+            values.sort_by_key(|v| v["name"].to_string());
+            values
+        };
+        let edge_values = |graph: &mut CodeGraph<MemoryStore>| {
+            let edges = graph
+                .query_edges("MATCH (a)-[e]->(b) RETURN a.name, b.name, e".to_string())
+                .unwrap();
+            // The nodes of the edges are compared by name, since they are stored separately.
+            let mut values: Vec<_> = edges
+                .into_iter()
+                .map(|e| {
+                    serde_json::json!([
+                        e.r#type,
+                        e.from.name,
+                        e.to.name,
+                        e.import,
+                        e.alias,
+                        e.properties,
+                    ])
+                })
+                .collect();
+            values.sort_by_key(|v| v.to_string());
+            values
+        };
+        let nodes = node_values(&mut graph);
+        assert!(!nodes.is_empty());
+        assert_eq!(node_values(&mut restored), nodes);
+        let edges = edge_values(&mut graph);
+        assert!(!edges.is_empty());
+        assert_eq!(edge_values(&mut restored), edges);
+
+        // The version header is checked before the body, whose layout differs across versions.
+        let old_snapshot_path = temp_dir.path().join("old.snapshot");
+        let mut old_snapshot = 1u32.to_le_bytes().to_vec();
+        old_snapshot.extend_from_slice(b"an older layout");
+        std::fs::write(&old_snapshot_path, old_snapshot).unwrap();
+        let err = restored.restore_snapshot(old_snapshot_path).unwrap_err();
+        assert!(err.to_string().contains("Unsupported snapshot version 1"));
+        assert_eq!(node_values(&mut restored), nodes);
+    }
+
+    #[test]
+    fn test_load_snapshot() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_snapshot");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let snapshot_path = temp_dir.path().join("graph.snapshot");
+
+        let mut graph = CodeGraph::new(db_path.clone(), repo_path.clone(), Config::default());
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+        let want_nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+        graph.save_snapshot(snapshot_path.clone()).unwrap();
+        graph.clean(true).unwrap();
+
+        let mut graph =
+            CodeGraph::load_snapshot(snapshot_path, db_path, repo_path, Config::default()).unwrap();
+        let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+        assert_eq!(nodes.len(), want_nodes.len());
+        assert_eq!(
+            graph
+                .get_callers("main.go:User.NewUser".to_string())
+                .unwrap()
+                .into_iter()
+                .map(|n| n.name)
+                .collect::<Vec<_>>(),
+            ["main.go:main"]
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_shared_database_hash_names() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().to_path_buf();
+        let db_path = repo_path.join("kuzu_db");
+
+        let hashed = Config::default().hash_node_names(true);
+        let graph = CodeGraph::try_new(db_path.clone(), repo_path.clone(), hashed.clone()).unwrap();
+
+        // The same database can be shared with the same setting, but not with a different one.
+        assert!(CodeGraph::try_new(db_path.clone(), repo_path.clone(), hashed).is_ok());
+        let err = CodeGraph::try_new(db_path.clone(), repo_path.clone(), Config::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("hash_node_names = true"));
+
+        // The setting is free to change once the database is no longer shared.
+        drop(graph);
+        assert!(CodeGraph::try_new(db_path, repo_path, Config::default()).is_ok());
+    }
+
+    #[test]
+    fn test_index_go_hashed_node_names() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = repo_path.join("kuzu_db_hashed");
+
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.go".into(), "!main.go".into()])
+            .hash_node_names(true);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(repo_path, false).unwrap();
+
+        // Look up by the hash key.
+        let key = graph.db().unwrap().node_key("main.go:User");
+        assert_eq!(key, util::stable_hash("main.go:User"));
+        let nodes = graph
+            .query_nodes(format!(
+                r#"MATCH (n:Class) WHERE n.name = "{}" RETURN n"#,
+                key
+            ))
+            .unwrap();
+        let names: Vec<_> = nodes.into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["main.go:User"]);
+
+        // Look up by the human-readable name.
+        let nodes = graph
+            .query_nodes(
+                r#"MATCH (n:Class) WHERE n.full_name = "main.go:User" RETURN n"#.to_string(),
+            )
+            .unwrap();
+        let names: Vec<_> = nodes.into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["main.go:User"]);
+
+        let edges = graph
+            .query_edges(
+                r#"
+MATCH (a)-[e:REFERENCES]->(b)
+WHERE a.full_name = "main.go:User.SetAddress"
+RETURN a.full_name, b.full_name, e
+                "#
+                .to_string(),
+            )
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:User.SetAddress-[references]->types.go:Address",
+                "main.go:User.SetAddress-[references]->types.go:Hobby",
+            ],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_subdirectory() {
+        init();
+
+        // Copy the Go demo into a temporary repository, with an extra `sub` package.
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let demo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        for file_name in ["go.mod", "main.go", "types.go"] {
+            std::fs::copy(demo_path.join(file_name), repo_path.join(file_name)).unwrap();
+        }
+        let sub_path = repo_path.join("sub");
+        std::fs::create_dir(&sub_path).unwrap();
+        std::fs::write(sub_path.join("a.go"), "package sub\n\nfunc A() {}\n").unwrap();
+        std::fs::write(sub_path.join("b.go"), "package sub\n\nfunc B() {}\n").unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+        graph.index(repo_path.clone(), true).unwrap();
+
+        let outside_node_strings = |graph: &mut CodeGraph| {
+            let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
+            let mut node_strings: Vec<_> = nodes
+                .into_iter()
+                .map(|n| n.name)
+                .filter(|name| !name.starts_with("sub"))
+                .collect();
+            node_strings.sort();
+            node_strings
+        };
+        let want_outside_node_strings = outside_node_strings(&mut graph);
+        assert!(want_outside_node_strings.contains(&"main.go:main".to_string()));
+
+        // Remove a file and add another one, then index only the subdirectory.
+        std::fs::remove_file(sub_path.join("b.go")).unwrap();
+        std::fs::write(sub_path.join("c.go"), "package sub\n\nfunc C() {}\n").unwrap();
+        graph.index(sub_path.clone(), false).unwrap();
+
+        // The nodes outside the subdirectory are untouched.
+        assert_eq!(outside_node_strings(&mut graph), want_outside_node_strings);
+
+        let query =
+            r#"MATCH (dir:Directory)-[:CONTAINS*]->(n) WHERE dir.name = "." RETURN DISTINCT n"#;
+        let nodes = graph.query_nodes(query.to_string()).unwrap();
+        let mut node_strings: Vec<_> = nodes
+            .into_iter()
+            .map(|n| n.name)
+            .filter(|name| name.starts_with("sub"))
+            .collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            ["sub", "sub/a.go", "sub/a.go:A", "sub/c.go", "sub/c.go:C"]
+        );
 
-        Ok(snippets)
+        graph.clean(true).unwrap();
     }
 
-    /// Clean the database.
-    /// If `delete` is true, the database directory will be deleted. Otherwise, the database will be cleaned up.
-    ///
-    /// TODO: support clean specific files or directories.
-    /// - `clean(path: PathBuf)`
-    /// - `clean(path: PathBuf, delete: bool)`
-    pub fn clean(&mut self, delete: bool) -> Result<(), Box<dyn std::error::Error>> {
-        return self.db.clean(delete);
-    }
-}
+    #[test]
+    fn test_index_subdirectory_keeps_outside_edges() {
+        init();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use log::LevelFilter;
-    use std::path::{Path, PathBuf};
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-subtree");
+        let db_path = repo_path.join("kuzu_db");
 
-    fn init() {
-        let _ = env_logger::builder()
-            .filter_level(LevelFilter::Info)
-            .is_test(true)
-            .try_init();
-    }
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+        graph.clean(true).unwrap();
 
-    fn assert_nodes(graph: &mut CodeGraph, want_node_strings: &[&str]) {
-        let nodes = graph.query_nodes("MATCH (n) RETURN n".to_string()).unwrap();
-        let mut node_strings: Vec<_> = nodes.into_iter().map(|n| n.name).collect();
-        node_strings.sort();
-        assert_eq!(node_strings, want_node_strings);
-    }
+        let want_edge_strings = [
+            ".-[contains]->sub",
+            ".-[contains]->types.ts",
+            "sub-[contains]->sub/greet.ts",
+            "sub/greet.ts-[contains]->sub/greet.ts:greet",
+            "sub/greet.ts-[imports]->types.ts:User",
+            "sub/greet.ts:greet-[references]->types.ts:User",
+            "types.ts-[contains]->types.ts:User",
+        ];
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_edges(&mut graph, &want_edge_strings);
 
-    fn assert_edges(graph: &mut CodeGraph, want_edge_strings: &[&str]) {
-        let edges = graph
-            .query_edges("MATCH (a)-[e]->(b) RETURN a.name, b.name, e".to_string())
-            .unwrap();
-        let mut edge_strings: Vec<_> = edges
-            .into_iter()
-            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
-            .collect();
-        edge_strings.sort();
-        assert_eq!(edge_strings, want_edge_strings);
+        // The edges from the subdirectory to the (unparsed) nodes outside of it survive re-indexing it.
+        graph.index(repo_path.join("sub"), false).unwrap();
+        assert_edges(&mut graph, &want_edge_strings);
+
+        graph.clean(true).unwrap();
     }
 
     #[test]
-    fn test_index_go() {
+    fn test_clean_path() {
         init();
 
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let dir_path = PathBuf::from(manifest_dir)
+        let repo_path = PathBuf::from(manifest_dir)
             .join("examples")
             .join("go")
             .join("demo");
-        let db_path = dir_path.join("kuzu_db");
+        let db_path = repo_path.join("kuzu_db");
 
         let config = Config::default().ignore_patterns(vec![
             "*".into(),
             "!types.go".into(),
             "!main.go".into(),
         ]);
-        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
-
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
         graph.clean(true).unwrap();
-        graph.index(dir_path, false).unwrap();
+        graph.index(repo_path.clone(), true).unwrap();
 
-        // validate data
+        // Clean a file by its absolute path, where only its definitions disappear.
+        graph.clean_path(repo_path.join("types.go")).unwrap();
         assert_nodes(
             &mut graph,
             &[
@@ -385,30 +4089,15 @@ mod tests {
                 "main.go:User.UpdateEmail",
                 "main.go:main",
                 "types.go",
-                "types.go:Address",
-                "types.go:Hobby",
-                "types.go:Status",
             ],
         );
+
+        // Clean a file by its relative path, including the nested methods.
+        graph.clean_path(PathBuf::from("main.go")).unwrap();
+        assert_nodes(&mut graph, &[".", "main.go", "types.go"]);
         assert_edges(
             &mut graph,
-            &[
-                ".-[contains]->main.go",
-                ".-[contains]->types.go",
-                "main.go-[contains]->main.go:User",
-                "main.go-[contains]->main.go:main",
-                "main.go:User-[contains]->main.go:User.ChangeStatus",
-                "main.go:User-[contains]->main.go:User.DisplayInfo",
-                "main.go:User-[contains]->main.go:User.NewUser",
-                "main.go:User-[contains]->main.go:User.SetAddress",
-                "main.go:User-[contains]->main.go:User.UpdateEmail",
-                "main.go:User.ChangeStatus-[references]->types.go:Status",
-                "main.go:User.SetAddress-[references]->types.go:Address",
-                "main.go:User.SetAddress-[references]->types.go:Hobby",
-                "types.go-[contains]->types.go:Address",
-                "types.go-[contains]->types.go:Hobby",
-                "types.go-[contains]->types.go:Status",
-            ],
+            &[".-[contains]->main.go", ".-[contains]->types.go"],
         );
 
         graph.clean(true).unwrap();
@@ -467,9 +4156,13 @@ mod tests {
                 "main.go:User-[contains]->main.go:User.NewUser",
                 "main.go:User-[contains]->main.go:User.SetAddress",
                 "main.go:User-[contains]->main.go:User.UpdateEmail",
+                "main.go:User-[references]->types.go:Address",
                 "main.go:User.ChangeStatus-[references]->types.go:Status",
                 "main.go:User.SetAddress-[references]->types.go:Address",
                 "main.go:User.SetAddress-[references]->types.go:Hobby",
+                "main.go:main-[calls]->main.go:User.DisplayInfo",
+                "main.go:main-[calls]->main.go:User.NewUser",
+                "main.go:main-[calls]->main.go:User.UpdateEmail",
                 "types.go-[contains]->types.go:Address",
                 "types.go-[contains]->types.go:Hobby",
                 "types.go-[contains]->types.go:Status",
@@ -529,6 +4222,9 @@ mod tests {
                 "main.go:User-[contains]->main.go:User.UpdateEmail",
                 "main.go:User.ChangeStatus-[references]->types.go:Status",
                 "main.go:User.SetAddress-[references]->types.go:Hobby",
+                "main.go:main-[calls]->main.go:User.DisplayInfo",
+                "main.go:main-[calls]->main.go:User.NewUser",
+                "main.go:main-[calls]->main.go:User.UpdateEmail",
                 "types.go-[contains]->types.go:Address2",
                 "types.go-[contains]->types.go:Hobby",
                 "types.go-[contains]->types.go:Status",
@@ -549,6 +4245,153 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_index_typescript_memory_store() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.ts".into(),
+            "!main.ts".into(),
+        ]);
+        let mut graph = CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), config);
+        graph.index(repo_path.clone(), true).unwrap();
+
+        assert_nodes(
+            &mut graph,
+            &[
+                "",
+                "main.ts",
+                "main.ts:fetchUserData",
+                "main.ts:greetUser",
+                "types.ts",
+                "types.ts:Callback",
+                "types.ts:TaskStatus",
+                "types.ts:User",
+                "types.ts:UserID",
+                "types.ts:UserService",
+                "types.ts:UserService.constructor",
+                "types.ts:UserService.filterUsers",
+                "types.ts:UserService.getUser",
+            ],
+        );
+        // Unlike Kuzu, the references to function parameter types are not resolved.
+        assert_edges(
+            &mut graph,
+            &[
+                "-[contains]->main.ts",
+                "-[contains]->types.ts",
+                "main.ts-[contains]->main.ts:fetchUserData",
+                "main.ts-[contains]->main.ts:greetUser",
+                "main.ts-[imports]->types.ts:Callback",
+                "main.ts-[imports]->types.ts:TaskStatus",
+                "main.ts-[imports]->types.ts:User",
+                "main.ts-[imports]->types.ts:UserID",
+                "main.ts-[imports]->types.ts:UserService",
+                "types.ts-[contains]->types.ts:Callback",
+                "types.ts-[contains]->types.ts:TaskStatus",
+                "types.ts-[contains]->types.ts:User",
+                "types.ts-[contains]->types.ts:UserID",
+                "types.ts-[contains]->types.ts:UserService",
+                "types.ts:UserService-[contains]->types.ts:UserService.constructor",
+                "types.ts:UserService-[contains]->types.ts:UserService.filterUsers",
+                "types.ts:UserService-[contains]->types.ts:UserService.getUser",
+            ],
+        );
+
+        // Indexing a single file is not supported by the in-memory store.
+        assert!(graph.index(repo_path.join("main.ts"), false).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_bodies() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("duplicates");
+
+        let mut graph =
+            CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), Config::default());
+        graph.index(repo_path.clone(), true).unwrap();
+
+        let group_names = |groups: Vec<Vec<Node>>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().map(|n| n.name).collect())
+                .collect()
+        };
+        // The renamed copies are grouped regardless of their indentation and receiver types, while the
+        // one-liners are too short.
+        assert_eq!(
+            group_names(graph.duplicate_bodies(3).unwrap()),
+            [
+                ["main.go:Counter.Count", "main.go:Counter.Total"],
+                ["main.go:addAll", "util.go:sum"],
+            ],
+        );
+        assert_eq!(
+            group_names(graph.duplicate_bodies(1).unwrap()),
+            [
+                ["main.go:Counter.Count", "main.go:Counter.Total"],
+                ["main.go:addAll", "util.go:sum"],
+                ["main.go:one", "util.go:uno"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_index_javascript_jsx() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("javascript-jsx");
+
+        let mut graph =
+            CodeGraph::with_store(MemoryStore::new(), repo_path.clone(), Config::default());
+        graph.index(repo_path.clone(), true).unwrap();
+
+        assert_nodes(
+            &mut graph,
+            &[
+                "",
+                "App.jsx",
+                "App.jsx:App",
+                "components",
+                "components/index.jsx",
+                "components/index.jsx:Button",
+                "utils.js",
+                "utils.js:formatLabel",
+            ],
+        );
+        // The directory import `./components` is resolved to its `index.jsx`.
+        assert_edges(
+            &mut graph,
+            &[
+                "-[contains]->App.jsx",
+                "-[contains]->components",
+                "-[contains]->utils.js",
+                "App.jsx-[contains]->App.jsx:App",
+                "App.jsx-[imports]->components/index.jsx:Button",
+                "App.jsx-[imports]->utils.js:formatLabel",
+                "App.jsx:App-[calls]->utils.js:formatLabel",
+                "App.jsx:App-[references]->components/index.jsx:Button",
+                "components-[contains]->components/index.jsx",
+                "components/index.jsx-[contains]->components/index.jsx:Button",
+                "utils.js-[contains]->utils.js:formatLabel",
+            ],
+        );
+    }
+
     #[test]
     fn test_index_typescript() {
         init();
@@ -786,41 +4629,211 @@ func main() {
         let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
 
         graph.clean(true).unwrap();
-        graph
-            .index_dirty_file(temp_file_path.clone(), temp_file_content.as_bytes())
+        graph
+            .index_dirty_file(temp_file_path.clone(), temp_file_content.as_bytes())
+            .unwrap();
+
+        assert_nodes(&mut graph, &["temp.go", "temp.go:main"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_dirty_file_typescript() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = repo_path.join("kuzu_db");
+
+        let temp_file_path = repo_path.join("temp.ts");
+        let temp_file_content = r#"
+export function greet(name: string): string {
+  console.log(`Hello ${name}!`);
+}
+          "#;
+
+        let config = Config::default();
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph
+            .index_dirty_file(temp_file_path.clone(), temp_file_content.as_bytes())
+            .unwrap();
+
+        assert_nodes(&mut graph, &["temp.ts", "temp.ts:greet"]);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_dirty_files_typescript() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        // The saved versions of the files, which are outdated by the dirty ones.
+        std::fs::write(repo_path.join("greet.ts"), "export function hello() {}\n").unwrap();
+        std::fs::write(repo_path.join("main.ts"), "").unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        // `greet` only exists in the dirty content, so the import can only be resolved within the batch.
+        graph
+            .index_dirty_files(vec![
+                (
+                    repo_path.join("main.ts"),
+                    b"import { greet } from './greet';\n".to_vec(),
+                ),
+                (
+                    repo_path.join("greet.ts"),
+                    b"export function greet(name: string): string {\n  return name;\n}\n".to_vec(),
+                ),
+            ])
+            .unwrap();
+
+        assert_nodes(&mut graph, &["greet.ts", "greet.ts:greet", "main.ts"]);
+        assert_edges(
+            &mut graph,
+            &[
+                "greet.ts-[contains]->greet.ts:greet",
+                "main.ts-[imports]->greet.ts:greet",
+            ],
+        );
+        let query = r#"MATCH (file:File) WHERE file.name = "main.ts" RETURN file"#;
+        let file_nodes = graph.query_nodes(query.to_string()).unwrap();
+        assert!(file_nodes[0].unresolved_imports.is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_typescript_forward_type_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-forward");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        // The types are declared after the functions that use them.
+        let want_edge_strings = [
+            "shapes.ts:area-[references]->shapes.ts:Shape",
+            "shapes.ts:describe-[references]->shapes.ts:Shape",
+            "shapes.ts:describe-[references]->shapes.ts:Unit",
+        ];
+        let reference_edges = |graph: &mut CodeGraph| {
+            let edges = graph
+                .query_edges("MATCH (a)-[e:REFERENCES]->(b) RETURN a.name, b.name, e".to_string())
+                .unwrap();
+            let mut edge_strings: Vec<_> = edges
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+
+        // Bulk indexing of the whole repository.
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_eq!(reference_edges(&mut graph), want_edge_strings);
+
+        // Indexing the single file.
+        graph.clean(true).unwrap();
+        graph.index(repo_path.join("shapes.ts"), false).unwrap();
+        assert_eq!(reference_edges(&mut graph), want_edge_strings);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_typescript_enum_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-enum");
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        // The enum is referenced within its own file and from the importing file.
+        let want_edge_strings = [
+            "status.ts:isDone-[references]->status.ts:TaskStatus",
+            "tasks.ts:updateStatus-[references]->status.ts:TaskStatus",
+        ];
+        let reference_edges = |graph: &mut CodeGraph| {
+            let edges = graph
+                .query_edges("MATCH (a)-[e:REFERENCES]->(b) RETURN a.name, b.name, e".to_string())
+                .unwrap();
+            let mut edge_strings: Vec<_> = edges
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+
+        // Bulk indexing of the whole repository.
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_eq!(reference_edges(&mut graph), want_edge_strings);
+
+        let enum_nodes = graph
+            .query_nodes(
+                r#"MATCH (n:OtherType) WHERE n.name = "status.ts:TaskStatus" RETURN n"#.to_string(),
+            )
             .unwrap();
+        assert_eq!(enum_nodes.len(), 1);
 
-        assert_nodes(&mut graph, &["temp.go", "temp.go:main"]);
+        // Indexing the single files, where the enum of the other file is looked up in the database.
+        graph.clean(true).unwrap();
+        graph.index(repo_path.join("status.ts"), false).unwrap();
+        graph.index(repo_path.join("tasks.ts"), false).unwrap();
+        assert_eq!(reference_edges(&mut graph), want_edge_strings);
 
         graph.clean(true).unwrap();
     }
 
     #[test]
-    fn test_index_dirty_file_typescript() {
+    fn test_index_go_struct_field_references() {
         init();
 
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
         let repo_path = PathBuf::from(manifest_dir)
             .join("examples")
-            .join("typescript");
+            .join("go")
+            .join("fields");
         let db_path = repo_path.join("kuzu_db");
 
-        let temp_file_path = repo_path.join("temp.ts");
-        let temp_file_content = r#"
-export function greet(name: string): string {
-  console.log(`Hello ${name}!`);
-}
-          "#;
-
-        let config = Config::default();
-        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
 
         graph.clean(true).unwrap();
-        graph
-            .index_dirty_file(temp_file_path.clone(), temp_file_content.as_bytes())
-            .unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
 
-        assert_nodes(&mut graph, &["temp.ts", "temp.ts:greet"]);
+        let edges = graph
+            .query_edges("MATCH (a)-[e:REFERENCES]->(b) RETURN a.name, b.name, e".to_string())
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "models.go:User-[references]->models.go:Address",
+                "models.go:User-[references]->models.go:Tag",
+            ],
+        );
 
         graph.clean(true).unwrap();
     }
@@ -863,12 +4876,12 @@ export function greet(name: string): string {
             snippet_strings,
             &[
                 r#"-->types.go:3:6
-Address struct {
+type Address struct {
 		Country string
 		City    string
 	}"#,
                 r#"-->types.go:8:11
-Hobby struct {
+type Hobby struct {
 		Sports bool
 		Music  bool
 	}"#,
@@ -878,6 +4891,464 @@ Hobby struct {
         graph.clean(true).unwrap();
     }
 
+    #[test]
+    fn test_capped_methods() {
+        let method = |start_line: u32, skeleton_code: &str| {
+            kuzu::Value::Struct(vec![
+                ("start_line".to_string(), kuzu::Value::UInt32(start_line)),
+                (
+                    "skeleton_code".to_string(),
+                    kuzu::Value::String(skeleton_code.to_string()),
+                ),
+            ])
+        };
+        let methods = [
+            method(20, "filterUsers() { ... }"),
+            method(4, "constructor() { ... }"),
+            // The nested classes have no skeletons.
+            method(8, ""),
+            method(12, "getUser() { ... }"),
+        ];
+
+        // The methods are kept in source order, regardless of the collected order.
+        assert_eq!(
+            capped_methods(&methods, None),
+            (
+                vec![
+                    "constructor() { ... }",
+                    "getUser() { ... }",
+                    "filterUsers() { ... }"
+                ],
+                0
+            )
+        );
+        assert_eq!(
+            capped_methods(&methods, Some(1)),
+            (vec!["constructor() { ... }"], 2)
+        );
+    }
+
+    #[test]
+    fn test_type_snippet_content() {
+        // The types without methods are their code.
+        assert_eq!(
+            type_snippet_content(
+                &Language::Go,
+                &NodeType::OtherType,
+                "Status int",
+                "",
+                &[],
+                0
+            ),
+            "type Status int"
+        );
+        assert_eq!(
+            type_snippet_content(
+                &Language::TypeScript,
+                &NodeType::OtherType,
+                "type UserID = string | number;",
+                "",
+                &[],
+                0
+            ),
+            "type UserID = string | number;"
+        );
+        assert_eq!(
+            type_snippet_content(
+                &Language::Python,
+                &NodeType::Class,
+                "class User:\n    name: str",
+                "",
+                &[],
+                0
+            ),
+            "class User:\n    name: str"
+        );
+
+        // The classes with methods are their skeletons followed by the method skeletons.
+        assert_eq!(
+            type_snippet_content(
+                &Language::Go,
+                &NodeType::Class,
+                "User struct {\n\tID int\n}",
+                "",
+                &["func (u *User) DisplayInfo() {\n...\n}"],
+                1
+            ),
+            "type User struct {\n\tID int\n}\n\nfunc (u *User) DisplayInfo() {\n...\n}\n\n// ... 1 more"
+        );
+        assert_eq!(
+            type_snippet_content(
+                &Language::TypeScript,
+                &NodeType::Class,
+                "class UserService {\n    private apiUrl: string;\n}",
+                "class UserService { ... }",
+                &["constructor(baseUrl: string) { ... }"],
+                1
+            ),
+            "class UserService {\n  constructor(baseUrl: string) { ... }\n  // ... 1 more\n}"
+        );
+        assert_eq!(
+            type_snippet_content(
+                &Language::Python,
+                &NodeType::Class,
+                "class A:\n    name: str\n\n    def greet(self) -> str:\n        return self.name",
+                "",
+                &["def greet(self) -> str:\n    ..."],
+                1
+            ),
+            "class A:\n    def greet(self) -> str:\n        ...\n    # ... 1 more"
+        );
+    }
+
+    #[test]
+    fn test_get_func_param_types_max_snippet_methods() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+        let db_path = dir_path.join("kuzu_db_max_snippet_methods");
+
+        let config = Config::default()
+            .ignore_patterns(vec!["*".into(), "!types.ts".into(), "!main.ts".into()])
+            .max_snippet_methods(1);
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let line = 25; // fetchUserData()
+        let snippets = graph
+            .get_func_param_types("main.ts".to_string(), line)
+            .unwrap();
+        let snippet = snippets
+            .iter()
+            .find(|s| s.content.starts_with("class UserService"))
+            .unwrap();
+        assert_eq!(
+            snippet.content,
+            r#"class UserService {
+  constructor(baseUrl: string) { ... }
+  // ... 2 more
+}"#,
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_func_param_type_nodes() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db_param_type_nodes");
+
+        let config = Config::default().ignore_patterns(vec![
+            "*".into(),
+            "!types.go".into(),
+            "!main.go".into(),
+        ]);
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), config);
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let line = 37; // SetAddress()
+        let nodes = graph
+            .func_param_type_nodes("main.go".to_string(), line)
+            .unwrap();
+        let node_strings: Vec<_> = nodes
+            .iter()
+            .map(|n| format!("{}:{}:{}", n.name, n.r#type, n.start_line))
+            .collect();
+        assert_eq!(
+            node_strings,
+            ["types.go:Address:Class:3", "types.go:Hobby:Class:8"],
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_implementors_of() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-implements");
+        let db_path = dir_path.join("kuzu_db_implementors");
+
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .implementors_of("shapes.ts:Shape".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, ["shapes.ts:Circle", "shapes.ts:Square"]);
+
+        assert!(graph
+            .implementors_of("shapes.ts:Label".to_string())
+            .unwrap()
+            .is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_find_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("references");
+        let db_path = dir_path.join("kuzu_db_find_references");
+
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .find_references("types.go:Order".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, ["main.go:Cancel", "main.go:Create"]);
+
+        assert!(graph
+            .find_references("main.go:main".to_string())
+            .unwrap()
+            .is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_query_nodes_params_with_quoted_name() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let file_path = repo_path.join(r#"say "hi" \ bye.go"#);
+        std::fs::write(
+            &file_path,
+            "package main\n\ntype Greeting struct{}\n\nfunc Greet(g Greeting) {}\n",
+        )
+        .unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        graph.index(file_path.clone(), false).unwrap();
+
+        let type_name = r#"say "hi" \ bye.go:Greeting"#;
+        let nodes = graph
+            .query_nodes_params(
+                "MATCH (n) WHERE n.name = $name RETURN n".to_string(),
+                HashMap::from([(
+                    "name".to_string(),
+                    kuzu::Value::String(type_name.to_string()),
+                )]),
+            )
+            .unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].name, type_name);
+
+        let names: Vec<_> = graph
+            .find_references(type_name.to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, [r#"say "hi" \ bye.go:Greet"#]);
+
+        // Re-indexing the unchanged file looks it up by the name as well.
+        graph.index(file_path, false).unwrap();
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_path_with_double_quote() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let dir_path = repo_path.join(r#"say "hi""#);
+        std::fs::create_dir(&dir_path).unwrap();
+        let file_path = dir_path.join("main.go");
+        std::fs::write(
+            &file_path,
+            "package main\n\ntype Greeting struct{}\n\nfunc Greet(g Greeting) {\n\tprintln()\n}\n",
+        )
+        .unwrap();
+        let db_path = repo_path.join("kuzu_db");
+
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), Config::default());
+
+        let want_node_strings = [
+            ".",
+            r#"say "hi""#,
+            r#"say "hi"/main.go"#,
+            r#"say "hi"/main.go:Greet"#,
+            r#"say "hi"/main.go:Greeting"#,
+        ];
+
+        // Indexing the repository, then re-indexing the subdirectory, whose old nodes are looked up by its path.
+        graph.index(repo_path.clone(), false).unwrap();
+        assert_nodes(&mut graph, &want_node_strings);
+        graph.index(dir_path.clone(), false).unwrap();
+        assert_nodes(&mut graph, &want_node_strings);
+
+        // Re-indexing the single file, whose old nodes are looked up by its path.
+        graph.index(file_path.clone(), true).unwrap();
+        assert_nodes(&mut graph, &want_node_strings);
+
+        let snippets = graph
+            .get_func_param_types(file_path.to_string_lossy().to_string(), 5)
+            .unwrap();
+        assert_eq!(snippets.len(), 1);
+        assert_eq!(snippets[0].path, r#"say "hi"/main.go"#);
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_index_todo_comments() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let repo_path = PathBuf::from(manifest_dir).join("examples").join("go-todo");
+        let db_path = repo_path.join("kuzu_db");
+
+        let config = Config::default().capture_todo_comments(true);
+        let mut graph = CodeGraph::new(db_path, repo_path.clone(), config);
+
+        // The TODO within the function is contained by the function, which needs its own relation pair.
+        graph.clean(true).unwrap();
+        graph.index(repo_path.clone(), false).unwrap();
+
+        let edges = graph
+            .query_edges(
+                "MATCH (a)-[e:CONTAINS]->(b:OtherType) RETURN a.name, b.name, e".to_string(),
+            )
+            .unwrap();
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go-[contains]->main.go:FIXME@5",
+                "main.go:main-[contains]->main.go:TODO@9",
+            ]
+        );
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_impact_set() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db_impact_set");
+
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .impact_set("types.go:Address".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert!(names.contains(&"main.go:User.SetAddress".to_string()));
+        assert!(!names.contains(&"types.go:Address".to_string()));
+
+        // The callers of an impacted node are impacted transitively.
+        let caller_names: Vec<_> = graph
+            .get_callers("main.go:User.SetAddress".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert!(caller_names.iter().all(|name| names.contains(name)));
+
+        graph.clean(true).unwrap();
+    }
+
+    #[test]
+    fn test_get_callees_and_callers() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let db_path = dir_path.join("kuzu_db_calls");
+
+        let mut graph = CodeGraph::new(db_path, dir_path.clone(), Config::default());
+
+        graph.clean(true).unwrap();
+        graph.index(dir_path, false).unwrap();
+
+        let names: Vec<_> = graph
+            .get_callees("main.go:main".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "main.go:User.DisplayInfo",
+                "main.go:User.NewUser",
+                "main.go:User.UpdateEmail",
+            ]
+        );
+
+        let names: Vec<_> = graph
+            .get_callers("main.go:User.NewUser".to_string())
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, ["main.go:main"]);
+
+        // The calls to the standard library (e.g. `fmt.Println`) are dropped.
+        assert!(graph
+            .get_callees("main.go:User.UpdateEmail".to_string())
+            .unwrap()
+            .is_empty());
+
+        graph.clean(true).unwrap();
+    }
+
     #[test]
     fn test_get_func_param_types_typescript() {
         init();
@@ -918,9 +5389,9 @@ Hobby struct {
 type UserID = string | number;"#,
                 r#"-->types.ts:26:48
 class UserService {
-  public static filterUsers<T extends User>(users: T[], predicate: (user: T) => boolean): T[] { ... }
-  public async getUser(userID: UserID): Promise<User[]> { ... }
   constructor(baseUrl: string) { ... }
+  public async getUser(userID: UserID): Promise<User[]> { ... }
+  public static filterUsers<T extends User>(users: T[], predicate: (user: T) => boolean): T[] { ... }
 }"#,
             ],
         );
@@ -1,13 +1,17 @@
 use glob::Pattern;
-use ignore::overrides::OverrideBuilder;
-use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{Match, WalkBuilder};
 use indexmap::IndexMap;
-use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use strum_macros;
 use tree_sitter;
 use tree_sitter::StreamingIterator;
@@ -17,14 +21,17 @@ use walkdir::WalkDir;
 
 use crate::util;
 use crate::Database;
-use crate::{Edge, EdgeType, Language, Node, NodeType};
+use crate::{Edge, EdgeType, FileSummary, IndexCancelled, Language, Node, NodeType};
 
 mod common;
+mod config_file;
 mod go;
 mod python;
+mod rust;
 mod typescript;
 
-use common::PendingImport;
+pub(crate) use common::mask_definition_name;
+use common::{PendingCall, PendingImport, PendingInherit};
 
 #[derive(Clone, Debug)]
 /// Configuration options for the parser.
@@ -35,7 +42,8 @@ pub struct ParserConfig {
     pub follow_links: bool,
     /// Maximum recursion depth, None means no limit (default is None)
     pub max_depth: usize,
-    /// Whether to continue traversal when encountering errors (default is false)
+    /// Whether to continue traversal when encountering errors, where the files that fail to parse (e.g. to read)
+    /// are skipped (default is false)
     pub continue_on_error: bool,
     /// Ignore patterns following gitignore syntax (default is empty)
     /// Each pattern follows gitignore rules:
@@ -49,6 +57,67 @@ pub struct ParserConfig {
     pub ignore_patterns: Vec<String>,
     /// Whether to use .gitignore files found in directories (default is true)
     pub use_gitignore_files: bool,
+    /// Maximum number of rows per `COPY FROM` statement when bulk inserting, 0 means no limit (default is 0)
+    pub bulk_insert_batch_size: usize,
+    /// Whether to store the names of the imported files on each file node (default is false)
+    pub store_import_summary: bool,
+    /// Whether to use a hash of the full name as the node primary key, to bound the key length (default is false)
+    ///
+    /// The human-readable name is always stored in the `full_name` property.
+    pub hash_node_names: bool,
+    /// Whether to parse YAML/JSON config files, whose top-level keys become child nodes (default is false)
+    pub parse_config_files: bool,
+    /// Whether to skip the definitions in generated files (e.g. `*.pb.go`), whose file nodes are still kept
+    /// and flagged as generated (default is false)
+    pub exclude_generated_files: bool,
+    /// How broadly the type names, which are neither qualified nor imported, are matched when resolving references,
+    /// None means the natural scope of each language, i.e. `Package` for Go and `File` for TypeScript (default is None)
+    pub reference_scope: Option<ReferenceScope>,
+    /// Number of leading body lines kept in the skeleton code of functions and classes, before the `...` marker
+    /// (default is 0)
+    pub skeleton_body_lines: usize,
+    /// Whether to normalize CRLF line endings to LF before parsing, so the stored code is free of `\r` (default is false)
+    pub normalize_line_endings: bool,
+    /// Path of the file to append per-file diagnostics to as JSON lines during indexing, so that every run (e.g. of
+    /// watching) is kept, None means no diagnostics (default is None)
+    pub diagnostics_path: Option<PathBuf>,
+    /// Maximum number of threads of the scoped thread pool for parsing a directory, None means using the global
+    /// pool with all cores (default is None)
+    pub max_parse_threads: Option<usize>,
+    /// Whether to create the root directory node (named `""`), which contains the top-level files and
+    /// directories (default is true)
+    pub emit_root_node: bool,
+    /// Maximum number of method skeletons appended to each type in the snippets of `get_func_param_types`, where
+    /// the omitted ones are replaced by a `// ... N more` marker, None means no limit (default is None)
+    pub max_snippet_methods: Option<usize>,
+    /// Whether to match the paths of the imported modules case-insensitively, e.g. `./Types` for `types.ts`
+    /// (default is true on case-insensitive platforms, i.e. macOS and Windows, and false otherwise)
+    pub case_insensitive_imports: bool,
+    /// The types of the nodes to insert (along with the edges between them), e.g. for a lightweight index of the
+    /// directory structure, where directory and file nodes are always inserted, None means all types (default is None)
+    pub node_types: Option<HashSet<NodeType>>,
+    /// Flag checked before each entry while traversing a directory, where the parsing stops with an
+    /// `IndexCancelled` error once it is set, None means no cancellation (default is None)
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+    /// Whether to capture the `TODO`/`FIXME` comments as `OtherType` nodes, which are contained in their enclosing
+    /// functions or files (default is false)
+    pub capture_todo_comments: bool,
+    /// Whether to parse the Go standard library packages (located by `go env GOROOT`) imported by the parsed
+    /// directory, so the references to their types (e.g. `time.Time`) can be resolved, which is expensive
+    /// (default is false)
+    pub index_go_stdlib: bool,
+    /// The directories of the stub files (e.g. `requests.pyi` or `axios.d.ts`), where the Python and TypeScript
+    /// imports of the modules outside of the repository are looked up. Only the definitions of the imported stub
+    /// files are parsed (i.e. no imports, calls or references from them), so the references to the stubbed types
+    /// can be resolved without indexing the whole dependencies (default is empty)
+    pub stub_paths: Vec<PathBuf>,
+    /// The milliseconds to sleep after parsing each file (in each parsing thread), to throttle the CPU usage
+    /// of indexing, 0 means no throttling (default is 0)
+    pub throttle_ms: u64,
+    /// Whether to omit the file nodes (and their edges from the parent directories) of the parsed files
+    /// without any definitions or imports, e.g. comment-only files, while the files that only import or re-export
+    /// (e.g. barrel `index.ts` files) are kept (default is false)
+    pub skip_empty_files: bool,
 }
 
 impl Default for ParserConfig {
@@ -60,6 +129,26 @@ impl Default for ParserConfig {
             continue_on_error: false,
             ignore_patterns: Vec::new(),
             use_gitignore_files: true,
+            bulk_insert_batch_size: 0,
+            store_import_summary: false,
+            hash_node_names: false,
+            parse_config_files: false,
+            exclude_generated_files: false,
+            reference_scope: None,
+            skeleton_body_lines: 0,
+            normalize_line_endings: false,
+            diagnostics_path: None,
+            max_parse_threads: None,
+            emit_root_node: true,
+            max_snippet_methods: None,
+            case_insensitive_imports: cfg!(any(target_os = "macos", target_os = "windows")),
+            node_types: None,
+            cancel_flag: None,
+            capture_todo_comments: false,
+            index_go_stdlib: false,
+            stub_paths: Vec::new(),
+            throttle_ms: 0,
+            skip_empty_files: false,
         }
     }
 }
@@ -89,6 +178,100 @@ impl ParserConfig {
         self.use_gitignore_files = use_gitignore_files;
         self
     }
+    pub fn bulk_insert_batch_size(mut self, bulk_insert_batch_size: usize) -> Self {
+        self.bulk_insert_batch_size = bulk_insert_batch_size;
+        self
+    }
+    pub fn store_import_summary(mut self, store_import_summary: bool) -> Self {
+        self.store_import_summary = store_import_summary;
+        self
+    }
+    pub fn hash_node_names(mut self, hash_node_names: bool) -> Self {
+        self.hash_node_names = hash_node_names;
+        self
+    }
+    pub fn parse_config_files(mut self, parse_config_files: bool) -> Self {
+        self.parse_config_files = parse_config_files;
+        self
+    }
+    pub fn exclude_generated_files(mut self, exclude_generated_files: bool) -> Self {
+        self.exclude_generated_files = exclude_generated_files;
+        self
+    }
+    pub fn reference_scope(mut self, reference_scope: ReferenceScope) -> Self {
+        self.reference_scope = Some(reference_scope);
+        self
+    }
+    pub fn skeleton_body_lines(mut self, skeleton_body_lines: usize) -> Self {
+        self.skeleton_body_lines = skeleton_body_lines;
+        self
+    }
+    pub fn normalize_line_endings(mut self, normalize_line_endings: bool) -> Self {
+        self.normalize_line_endings = normalize_line_endings;
+        self
+    }
+    pub fn diagnostics_path(mut self, diagnostics_path: PathBuf) -> Self {
+        self.diagnostics_path = Some(diagnostics_path);
+        self
+    }
+    pub fn max_parse_threads(mut self, max_parse_threads: usize) -> Self {
+        self.max_parse_threads = Some(max_parse_threads);
+        self
+    }
+    pub fn emit_root_node(mut self, emit_root_node: bool) -> Self {
+        self.emit_root_node = emit_root_node;
+        self
+    }
+    pub fn max_snippet_methods(mut self, max_snippet_methods: usize) -> Self {
+        self.max_snippet_methods = Some(max_snippet_methods);
+        self
+    }
+    pub fn case_insensitive_imports(mut self, case_insensitive_imports: bool) -> Self {
+        self.case_insensitive_imports = case_insensitive_imports;
+        self
+    }
+    pub fn node_types(mut self, node_types: HashSet<NodeType>) -> Self {
+        self.node_types = Some(node_types);
+        self
+    }
+    pub fn cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+    pub fn capture_todo_comments(mut self, capture_todo_comments: bool) -> Self {
+        self.capture_todo_comments = capture_todo_comments;
+        self
+    }
+    pub fn index_go_stdlib(mut self, index_go_stdlib: bool) -> Self {
+        self.index_go_stdlib = index_go_stdlib;
+        self
+    }
+    pub fn stub_paths(mut self, stub_paths: Vec<PathBuf>) -> Self {
+        self.stub_paths = stub_paths;
+        self
+    }
+    pub fn throttle_ms(mut self, throttle_ms: u64) -> Self {
+        self.throttle_ms = throttle_ms;
+        self
+    }
+    pub fn skip_empty_files(mut self, skip_empty_files: bool) -> Self {
+        self.skip_empty_files = skip_empty_files;
+        self
+    }
+}
+
+/// The scope within which a referenced type is looked up.
+///
+/// A broader scope finds more references (e.g. to the global types that are not imported),
+/// at the cost of precision, since the types with the same name in different files may be matched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum ReferenceScope {
+    /// The types defined in the same file
+    File,
+    /// The types defined in the same package (i.e. directory)
+    Package,
+    /// The types defined anywhere in the repository
+    Repo,
 }
 
 pub struct File<'a> {
@@ -96,12 +279,114 @@ pub struct File<'a> {
     content: &'a [u8],
 }
 
+/// The configuration options that change the parsed nodes and edges of a file, which are covered by the
+/// content hash of the file.
+#[derive(Debug, serde::Serialize)]
+struct ParseOptions<'a> {
+    grammar_version: &'static str,
+    node_types: Option<BTreeSet<String>>,
+    store_import_summary: bool,
+    parse_config_files: bool,
+    exclude_generated_files: bool,
+    reference_scope: Option<ReferenceScope>,
+    skeleton_body_lines: usize,
+    normalize_line_endings: bool,
+    case_insensitive_imports: bool,
+    capture_todo_comments: bool,
+    index_go_stdlib: bool,
+    stub_paths: &'a [PathBuf],
+    skip_empty_files: bool,
+}
+
+impl<'a> ParseOptions<'a> {
+    fn new(config: &'a ParserConfig, language: &Language) -> Self {
+        // Destructure exhaustively, so that every new option has to be either covered or ignored explicitly.
+        let ParserConfig {
+            recursive: _,
+            follow_links: _,
+            max_depth: _,
+            continue_on_error: _,
+            ignore_patterns: _,
+            use_gitignore_files: _,
+            bulk_insert_batch_size: _,
+            store_import_summary,
+            hash_node_names: _,
+            parse_config_files,
+            exclude_generated_files,
+            reference_scope,
+            skeleton_body_lines,
+            normalize_line_endings,
+            diagnostics_path: _,
+            max_parse_threads: _,
+            emit_root_node: _,
+            max_snippet_methods: _,
+            case_insensitive_imports,
+            node_types,
+            cancel_flag: _,
+            capture_todo_comments,
+            index_go_stdlib,
+            stub_paths,
+            throttle_ms: _,
+            skip_empty_files,
+        } = config;
+
+        Self {
+            grammar_version: language.grammar_version(),
+            node_types: node_types
+                .as_ref()
+                .map(|types| types.iter().map(|t| t.to_string()).collect()),
+            store_import_summary: *store_import_summary,
+            parse_config_files: *parse_config_files,
+            exclude_generated_files: *exclude_generated_files,
+            reference_scope: *reference_scope,
+            skeleton_body_lines: *skeleton_body_lines,
+            normalize_line_endings: *normalize_line_endings,
+            case_insensitive_imports: *case_insensitive_imports,
+            capture_todo_comments: *capture_todo_comments,
+            index_go_stdlib: *index_go_stdlib,
+            stub_paths,
+            skip_empty_files: *skip_empty_files,
+        }
+    }
+}
+
+/// An import whose target has not been indexed, e.g. the imported file is excluded by ignore patterns.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnresolvedImport {
+    /// The name of the importing file node
+    pub file: String,
+    /// The path of the imported module (relative to the repository)
+    pub source_path: String,
+    /// The imported symbol, None if the entire module is imported
+    pub symbol: Option<String>,
+}
+
+/// The diagnostics of parsing a file, which are written as a JSON line if `diagnostics_path` is configured.
+#[derive(Debug, Clone, serde::Serialize)]
+struct FileDiagnostics {
+    /// The name of the file node
+    file: String,
+    /// The time spent on parsing the file, in milliseconds
+    parse_time_ms: f64,
+    /// The number of nodes parsed from the file, excluding the file node itself
+    node_count: usize,
+    /// The imports whose targets are not indexed, e.g. `types.ts:User as U`
+    unresolved_imports: Vec<String>,
+    /// The error encountered when parsing the file, if any
+    error: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FuncParamType {
     type_name: String,
+    /// The name of the file or directory node, within which the type is looked up
     package_name: Option<String>,
+    scope: ReferenceScope,
 }
 
+/// Callback called with the relative path of each parsed file.
+pub type FileParsedCallback = Box<dyn FnMut(&str) + Send>;
+
 pub struct Parser {
     repo_path: PathBuf,
     config: ParserConfig,
@@ -110,30 +395,240 @@ pub struct Parser {
 
     pending_imports: HashMap<Language, HashMap<String, Vec<PendingImport>>>, // language -> (file node name -> imported info)
     func_param_types: HashMap<Language, HashMap<String, Vec<FuncParamType>>>, // language -> (function name -> parameter types)
+    pending_calls: HashMap<Language, Vec<PendingCall>>, // language -> calls from functions
+    pending_inherits: HashMap<Language, Vec<PendingInherit>>, // language -> embedded (or extended) types
 
     // Language-specific parsers
     go_parser: go::Parser,
     typescript_parser: typescript::Parser,
     python_parser: python::Parser,
+    rust_parser: rust::Parser,
+    config_file_parser: config_file::Parser,
 
     parsing_file: bool, // Flag to indicate if a file is currently being parsed. Defaults to false.
+    parsing_subtree: bool, // Flag to indicate if a subdirectory is currently being parsed. Defaults to false.
+    extensions: Option<HashSet<String>>, // The only file extensions to parse, if restricted. Defaults to None.
+    diagnostics: Vec<FileDiagnostics>,   // Only collected if `diagnostics_path` is configured.
+    on_file_parsed: Option<Mutex<FileParsedCallback>>, // Called with the name of each parsed file, if set.
 }
 
 impl Parser {
-    pub fn new(repo_path: PathBuf, config: ParserConfig) -> Self {
+    pub fn new(repo_path: PathBuf, mut config: ParserConfig) -> Self {
+        // The stub files are named by their absolute paths, like the ones of the Go standard library.
+        config.stub_paths = config
+            .stub_paths
+            .iter()
+            .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+            .collect();
+
         Self {
             repo_path: repo_path.clone(),
-            config: config,
+            config: config.clone(),
             nodes: IndexMap::new(),
             edges: Vec::new(),
             pending_imports: HashMap::new(),
             func_param_types: HashMap::new(),
+            pending_calls: HashMap::new(),
+            pending_inherits: HashMap::new(),
 
-            go_parser: go::Parser::new(repo_path.clone()),
-            typescript_parser: typescript::Parser::new(repo_path.clone()),
-            python_parser: python::Parser::new(repo_path.clone()),
+            go_parser: go::Parser::new(
+                repo_path.clone(),
+                config.reference_scope.unwrap_or(ReferenceScope::Package),
+                config.skeleton_body_lines,
+                config.index_go_stdlib,
+            ),
+            typescript_parser: typescript::Parser::new(
+                repo_path.clone(),
+                config.reference_scope.unwrap_or(ReferenceScope::File),
+                config.skeleton_body_lines,
+                config.case_insensitive_imports,
+                config.stub_paths.clone(),
+            ),
+            python_parser: python::Parser::new(repo_path.clone(), config.stub_paths.clone()),
+            rust_parser: rust::Parser::new(repo_path.clone(), config.skeleton_body_lines),
+            config_file_parser: config_file::Parser::new(),
 
             parsing_file: false,
+            parsing_subtree: false,
+            extensions: None,
+            diagnostics: Vec::new(),
+            on_file_parsed: None,
+        }
+    }
+
+    /// Sets the callback to be called with the name (i.e. the path relative to the repository) of each file
+    /// once it is parsed, e.g. for reporting the progress.
+    pub fn on_file_parsed(&mut self, on_file_parsed: FileParsedCallback) {
+        // The callback is only called while merging the parsed files, but the parser must be shared across
+        // the threads parsing them.
+        self.on_file_parsed = Some(Mutex::new(on_file_parsed));
+    }
+
+    /// Returns whether the `cancel_flag` is set.
+    fn is_cancelled(&self) -> bool {
+        self.config
+            .cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// Restricts the files to parse to the given extensions (e.g. `go` or `.go`), in addition to the
+    /// supported-language check.
+    pub fn restrict_extensions(&mut self, extensions: Vec<String>) {
+        self.extensions = Some(
+            extensions
+                .into_iter()
+                .map(|ext| ext.trim_start_matches('.').to_string())
+                .collect(),
+        );
+    }
+
+    /// Returns whether the file at the given path has one of the restricted extensions, if any.
+    pub fn has_allowed_extension(&self, path: &Path) -> bool {
+        let Some(extensions) = &self.extensions else {
+            return true;
+        };
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.contains(ext))
+    }
+
+    /// Returns the content hash of the file at the given path, which is stored on the file node to skip
+    /// re-indexing the unchanged file.
+    ///
+    /// Besides the content as is (i.e. before normalizing the line endings), the hash covers the grammar version
+    /// of the language and the configuration options that change the parsed nodes (see `ParseOptions`),
+    /// so that the file is re-parsed once either of them changes.
+    pub fn content_hash(&self, file_path: &Path, content: &[u8]) -> String {
+        let language = Language::from_path(&file_path.to_string_lossy());
+        let options = ParseOptions::new(&self.config, &language);
+        let fingerprint = serde_json::to_string(&options)
+            .expect("Should have been able to serialize the options");
+        util::stable_hash(&format!(
+            "{}|{}",
+            util::stable_hash_bytes(content),
+            fingerprint
+        ))
+    }
+
+    /// Returns whether the file at the given path is of a supported type (.go, .ts, .tsx, .js, .jsx, .py, .rs,
+    /// or config files if enabled), and has one of the restricted extensions, if any.
+    pub fn is_supported_file(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let supported = match extension {
+            Some("go") | Some("ts") | Some("tsx") | Some("js") | Some("jsx") | Some("py")
+            | Some("rs") => true,
+            Some("yaml") | Some("yml") | Some("json") => self.config.parse_config_files,
+            _ => false,
+        };
+        supported && self.has_allowed_extension(path)
+    }
+
+    /// Returns whether the given path (file or directory) is excluded the same way as the directory traversal,
+    /// i.e. the path or any of its parent directories within the repository is hidden, excluded by the configured
+    /// ignore patterns, or ignored by the `.gitignore` files (if enabled).
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(rel_path) = path.strip_prefix(&self.repo_path) else {
+            return self
+                .ignore_overrides()
+                .is_some_and(|overrides| overrides.matched(path, is_dir).is_ignore());
+        };
+        if rel_path
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            return true;
+        }
+
+        // The path itself, followed by its parent directories.
+        let paths: Vec<(&Path, bool)> = path
+            .ancestors()
+            .take_while(|p| p.starts_with(&self.repo_path) && *p != self.repo_path)
+            .enumerate()
+            .map(|(i, p)| (p, i > 0 || is_dir))
+            .collect();
+        if let Some(overrides) = self.ignore_overrides() {
+            if paths
+                .iter()
+                .any(|(p, is_dir)| overrides.matched(p, *is_dir).is_ignore())
+            {
+                return true;
+            }
+        }
+
+        self.config.use_gitignore_files && self.is_gitignored(path, is_dir)
+    }
+
+    /// Returns whether the given path within the repository is ignored by the `.gitignore` files of its parent
+    /// directories or by `.git/info/exclude`, where the closest `.gitignore` file takes precedence.
+    fn is_gitignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut gitignores: Vec<Gitignore> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.repo_path))
+            .map(|dir| dir.join(".gitignore"))
+            .filter(|gitignore_path| gitignore_path.is_file())
+            .map(|gitignore_path| Gitignore::new(gitignore_path).0)
+            .collect();
+        let exclude_path = self.repo_path.join(".git").join("info").join("exclude");
+        if exclude_path.is_file() {
+            let mut builder = GitignoreBuilder::new(&self.repo_path);
+            builder.add(exclude_path);
+            if let Ok(exclude) = builder.build() {
+                gitignores.push(exclude);
+            }
+        }
+
+        for gitignore in gitignores {
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                Match::None => continue,
+                matched => return matched.is_ignore(),
+            }
+        }
+        false
+    }
+
+    /// Builds the overrides of the configured ignore patterns.
+    ///
+    /// The patterns are relative to the repository, even when traversing a subdirectory, so that the anchored
+    /// ones (e.g. `/main.go`) always match the same paths.
+    fn ignore_overrides(&self) -> Option<Override> {
+        if self.config.ignore_patterns.is_empty() {
+            return None;
+        }
+        let mut overrides = OverrideBuilder::new(&self.repo_path);
+
+        for pattern in &self.config.ignore_patterns {
+            // In OverrideBuilder.add(), `!` has the opposite meaning compared to `gitignore`.
+            // See https://docs.rs/ignore/latest/ignore/overrides/struct.OverrideBuilder.html#method.add.
+            //
+            // Therefore, we need to negate the pattern to match gitignore behavior.
+            let ignore_pattern = if pattern.starts_with('!') {
+                // Remove the `!` prefix if present
+                &pattern[1..]
+            } else {
+                // Add the `!` prefix if not present
+                &format!("!{}", pattern)
+            };
+
+            if let Err(e) = overrides.add(ignore_pattern) {
+                log::warn!("Unable to add ignore pattern '{}': {}", pattern, e);
+            }
+        }
+
+        overrides.build().ok()
+    }
+
+    /// Re-derives the skeleton code of the given node from its code, according to the current configuration.
+    ///
+    /// Returns None if the node has no body to skip (e.g. interfaces or type aliases), whose skeleton code is
+    /// not derived.
+    pub fn derive_skeleton_code(&self, node: &Node) -> Option<String> {
+        match node.language {
+            Language::Go => self.go_parser.derive_skeleton_code(node),
+            Language::TypeScript => self.typescript_parser.derive_skeleton_code(node),
+            Language::Rust => self.rust_parser.derive_skeleton_code(node),
+            _ => None,
         }
     }
 
@@ -145,13 +640,52 @@ impl Parser {
     /// # Returns
     /// Tuple of references to parsed nodes and edges vectors
     /// Will write JSON files to configured output directory if specified
+    ///
+    /// If the parsing fails, the diagnostics (if configured) of the files parsed so far, including the failed
+    /// one, are written before returning the error, since there will be no edges to resolve.
     pub fn parse(
         &mut self,
         path: &PathBuf,
         file_content: Option<&[u8]>,
-    ) -> Result<(IndexMap<String, Node>, Vec<Edge>), Box<dyn std::error::Error>> {
+    ) -> Result<(IndexMap<String, Node>, Vec<Edge>), Box<dyn std::error::Error + Send + Sync>> {
+        let parsed = self.parse_path(path, file_content);
+        if parsed.is_err() {
+            if let Err(e) = self.write_file_diagnostics(&BTreeMap::new()) {
+                log::warn!("Unable to write the diagnostics: {}", e);
+            }
+        }
+        parsed
+    }
+
+    fn parse_path(
+        &mut self,
+        path: &PathBuf,
+        file_content: Option<&[u8]>,
+    ) -> Result<(IndexMap<String, Node>, Vec<Edge>), Box<dyn std::error::Error + Send + Sync>> {
         if path.is_dir() {
-            self.traverse_directory(&path)?;
+            // We are currently parsing the repository, or a subdirectory of it.
+            self.parsing_subtree = *path != self.repo_path;
+
+            if let Some(max_parse_threads) = self.config.max_parse_threads {
+                // Run the parse phase in a dedicated pool, instead of the global one, to cap the threads.
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(max_parse_threads)
+                    .build()?;
+                pool.install(|| self.traverse_directory(&path))?;
+            } else {
+                self.traverse_directory(&path)?;
+            }
+
+            // The re-exports are only known after all the files have been parsed.
+            self.resolve_reexported_param_types();
+
+            if self.config.index_go_stdlib {
+                self.parse_go_stdlib_packages()?;
+            }
+
+            if !self.config.stub_paths.is_empty() {
+                self.parse_stub_files()?;
+            }
         } else {
             if file_content.is_none() && !path.is_file() {
                 return Err("Invalid path".into());
@@ -160,8 +694,29 @@ impl Parser {
             // We are currently parsing a single file.
             self.parsing_file = true;
 
-            let (file_node, nodes, edges, pending_imports, func_param_types) =
-                self.parse_file(&path, file_content)?;
+            let started = Instant::now();
+            let parsed = self.parse_file(&path, file_content);
+            self.record_diagnostics(
+                &path,
+                started.elapsed(),
+                parsed
+                    .as_ref()
+                    .map(|p| p.1.len())
+                    .map_err(|e| e.to_string()),
+            );
+            let (
+                file_node,
+                nodes,
+                edges,
+                pending_imports,
+                func_param_types,
+                pending_calls,
+                pending_inherits,
+            ) = parsed?;
+            if self.is_skipped_empty_file(&nodes, &edges, &pending_imports) {
+                log::debug!("Skipping file without definitions: {}", file_node.name);
+                return Ok((self.nodes.clone(), self.edges.clone()));
+            }
 
             let language = file_node.language.clone();
             let file_node_name = file_node.name.clone();
@@ -184,22 +739,58 @@ impl Parser {
                     .or_insert_with(HashMap::new)
                     .extend(func_param_types);
             }
+            if pending_calls.len() > 0 {
+                self.pending_calls
+                    .entry(language.clone())
+                    .or_insert_with(Vec::new)
+                    .extend(pending_calls);
+            }
+            if pending_inherits.len() > 0 {
+                self.pending_inherits
+                    .entry(language.clone())
+                    .or_insert_with(Vec::new)
+                    .extend(pending_inherits);
+            }
         }
 
         Ok((self.nodes.clone(), self.edges.clone()))
     }
 
+    /// Returns whether the parsed file is omitted for having no definitions or imports, if `skip_empty_files`
+    /// is enabled.
+    fn is_skipped_empty_file(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        edges: &[Edge],
+        pending_imports: &[PendingImport],
+    ) -> bool {
+        self.config.skip_empty_files
+            && nodes.is_empty()
+            && edges.is_empty()
+            && pending_imports.is_empty()
+    }
+
     pub fn resolve_pending_edges(
         &self,
-        db: Option<&mut Database>,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+        mut db: Option<&mut Database>,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
         let mut edges: Vec<Edge> = Vec::new();
 
-        let import_edges = self.resolve_pending_imports()?;
+        let import_edges = self.resolve_pending_imports(db.as_deref_mut())?;
         for edge in import_edges {
             edges.push(edge);
         }
 
+        let call_edges = self.resolve_pending_calls(db.as_deref_mut())?;
+        for edge in call_edges {
+            edges.push(edge);
+        }
+
+        let inherit_edges = self.resolve_pending_inherits();
+        for edge in inherit_edges {
+            edges.push(edge);
+        }
+
         if let Some(db) = db {
             let ref_edges = self.resolve_func_param_type_edges(db)?;
             for edge in ref_edges {
@@ -210,53 +801,164 @@ impl Parser {
         Ok(edges)
     }
 
-    fn resolve_pending_imports(&self) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+    /// Whether only a part of the repository (i.e. a single file or a subdirectory) is being parsed, in which case
+    /// the edges to the other parts are resolved against the database.
+    fn parsing_partially(&self) -> bool {
+        self.parsing_file || self.parsing_subtree
+    }
+
+    /// Returns the imports whose targets have not been indexed.
+    ///
+    /// Note that this only makes sense after parsing the repository, since the import targets
+    /// are not available when parsing a single file or a subdirectory.
+    pub fn unresolved_imports(&self) -> Vec<UnresolvedImport> {
+        let mut unresolved_imports: Vec<UnresolvedImport> = Vec::new();
+        if self.parsing_partially() {
+            return unresolved_imports;
+        }
+
         for (language, pending_imports) in &self.pending_imports {
             match language {
                 Language::TypeScript => {
-                    return self
-                        .typescript_parser
-                        .resolve_pending_imports(&self.nodes, &pending_imports);
+                    unresolved_imports.extend(
+                        self.typescript_parser
+                            .unresolved_imports(&self.nodes, &pending_imports),
+                    );
+                }
+                Language::Python => {
+                    unresolved_imports.extend(
+                        self.python_parser
+                            .unresolved_imports(&self.nodes, &pending_imports),
+                    );
+                }
+                Language::Rust => {
+                    unresolved_imports.extend(
+                        self.rust_parser
+                            .unresolved_imports(&self.nodes, &pending_imports),
+                    );
                 }
                 _ => {}
             }
         }
 
-        Ok(vec![])
+        unresolved_imports.sort();
+        unresolved_imports
     }
 
-    fn resolve_func_param_type_edges(
+    /// Returns the pending imports that are not resolved by the given edges, grouped by the importing file.
+    ///
+    /// Every file with pending imports has an entry (which may be empty). The imports are encoded
+    /// like `types.ts:User as U`, to be persisted on the file nodes.
+    pub fn unresolved_pending_imports(
         &self,
-        db: &mut Database,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+        resolved_edges: &[Edge],
+    ) -> BTreeMap<String, Vec<String>> {
+        let resolved: HashSet<(&str, &str)> = resolved_edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Imports))
+            .map(|edge| (edge.from.name.as_str(), edge.to.name.as_str()))
+            .collect();
+
+        let mut unresolved_imports: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for pending_imports in self.pending_imports.values() {
+            for (file_node_name, pending_imports) in pending_imports {
+                let imports = unresolved_imports
+                    .entry(file_node_name.clone())
+                    .or_default();
+                for imp in pending_imports {
+                    let imported_node_name = imp.imported_node_name();
+                    if !resolved.contains(&(file_node_name.as_str(), imported_node_name.as_str())) {
+                        imports.push(imp.encode());
+                    }
+                }
+            }
+        }
+        unresolved_imports
+    }
+
+    /// Resolves the imports persisted on the given file node (see `unresolved_pending_imports`),
+    /// whose imported nodes are parsed now.
+    ///
+    /// Returns the resolved import edges and the imports that are still unresolved.
+    pub fn resolve_persisted_imports(&self, file_node: &Node) -> (Vec<Edge>, Vec<String>) {
         let mut edges: Vec<Edge> = Vec::new();
+        let mut unresolved_imports: Vec<String> = Vec::new();
 
-        for (language, func_param_types) in &self.func_param_types {
+        for encoded in &file_node.unresolved_imports {
+            let imp = PendingImport::decode(file_node.language.clone(), encoded);
+            match self.nodes.get(&imp.imported_node_name()) {
+                Some(imported_node) => edges.push(Edge {
+                    r#type: EdgeType::Imports,
+                    from: file_node.clone(),
+                    to: imported_node.clone(),
+                    import: imp.symbol,
+                    alias: imp.alias,
+                    properties: IndexMap::new(),
+                }),
+                None => unresolved_imports.push(encoded.clone()),
+            }
+        }
+
+        (edges, unresolved_imports)
+    }
+
+    fn resolve_pending_imports(
+        &self,
+        mut db: Option<&mut Database>,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        for unresolved_import in self.unresolved_imports() {
+            log::warn!(
+                "{} imports {:?} from {}, which is not indexed (excluded by ignore patterns?)",
+                unresolved_import.file,
+                unresolved_import.symbol.as_deref().unwrap_or("*"),
+                unresolved_import.source_path,
+            );
+        }
+
+        let mut edges: Vec<Edge> = Vec::new();
+        for (language, pending_imports) in &self.pending_imports {
             match language {
-                Language::Go => {
-                    let go_edges = self.go_parser.resolve_func_param_type_edges(
-                        &self.nodes,
-                        &func_param_types,
-                        db,
-                    )?;
-                    edges.extend(go_edges);
-                }
                 Language::TypeScript => {
-                    let ts_edges = if self.parsing_file {
-                        self.typescript_parser
-                            .resolve_func_param_type_edges_from_db(
-                                &self.nodes,
-                                &func_param_types,
-                                db,
-                            )?
+                    if let (true, Some(db)) = (self.parsing_partially(), db.as_deref_mut()) {
+                        edges.extend(self.typescript_parser.resolve_pending_imports_from_db(
+                            &self.nodes,
+                            &pending_imports,
+                            db,
+                        )?);
                     } else {
-                        self.typescript_parser.resolve_func_param_type_edges(
+                        edges.extend(
+                            self.typescript_parser
+                                .resolve_pending_imports(&self.nodes, &pending_imports)?,
+                        );
+                    }
+                }
+                Language::Python => {
+                    if let (true, Some(db)) = (self.parsing_partially(), db.as_deref_mut()) {
+                        edges.extend(self.python_parser.resolve_pending_imports_from_db(
                             &self.nodes,
-                            &func_param_types,
+                            &pending_imports,
                             db,
-                        )?
-                    };
-                    edges.extend(ts_edges);
+                        )?);
+                    } else {
+                        edges.extend(
+                            self.python_parser
+                                .resolve_pending_imports(&self.nodes, &pending_imports)?,
+                        );
+                    }
+                }
+                Language::Rust => {
+                    if let (true, Some(db)) = (self.parsing_partially(), db.as_deref_mut()) {
+                        edges.extend(self.rust_parser.resolve_pending_imports_from_db(
+                            &self.nodes,
+                            &pending_imports,
+                            db,
+                        )?);
+                    } else {
+                        edges.extend(
+                            self.rust_parser
+                                .resolve_pending_imports(&self.nodes, &pending_imports),
+                        );
+                    }
                 }
                 _ => {}
             }
@@ -265,40 +967,272 @@ impl Parser {
         Ok(edges)
     }
 
-    /// Traverses all files and directories in the specified directory, creates Node and Edge objects
-    /// This method processes files by calling self.parse_file directly when encountering supported file types
-    /// Uses the ignore library for better gitignore handling
-    ///
-    /// # Arguments
-    /// - `dir_path`: The directory path to traverse
-    ///
-    /// # Returns
-    /// - Result indicating success or failure of the traversal operation
-    pub fn traverse_directory(
-        &mut self,
-        dir_path: &PathBuf,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Check if directory exists
-        if !dir_path.exists() {
-            return Err(format!("Directory does not exist: {}", dir_path.display()).into());
+    fn resolve_pending_calls(
+        &self,
+        mut db: Option<&mut Database>,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for (language, pending_calls) in &self.pending_calls {
+            match language {
+                Language::Go => {
+                    if let (true, Some(db)) = (self.parsing_partially(), db.as_deref_mut()) {
+                        edges.extend(self.go_parser.resolve_pending_calls_from_db(
+                            &self.nodes,
+                            &pending_calls,
+                            db,
+                        )?);
+                    } else {
+                        edges.extend(
+                            self.go_parser
+                                .resolve_pending_calls(&self.nodes, &pending_calls),
+                        );
+                    }
+                }
+                Language::TypeScript => {
+                    let pending_imports = self.pending_imports.get(&Language::TypeScript);
+                    if let (true, Some(db)) = (self.parsing_partially(), db.as_deref_mut()) {
+                        edges.extend(self.typescript_parser.resolve_pending_calls_from_db(
+                            &self.nodes,
+                            &pending_calls,
+                            pending_imports,
+                            db,
+                        )?);
+                    } else {
+                        edges.extend(self.typescript_parser.resolve_pending_calls(
+                            &self.nodes,
+                            &pending_calls,
+                            pending_imports,
+                        ));
+                    }
+                }
+                _ => {}
+            }
         }
 
-        let mut processed_paths: std::collections::HashSet<PathBuf> =
-            std::collections::HashSet::new();
+        Ok(edges)
+    }
 
-        // Create WalkBuilder instance with better gitignore support
-        let mut builder = WalkBuilder::new(dir_path);
+    fn resolve_pending_inherits(&self) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
 
-        // Configure basic options
-        builder
-            .follow_links(self.config.follow_links)
-            .git_ignore(self.config.use_gitignore_files)
-            .git_global(self.config.use_gitignore_files)
-            .git_exclude(self.config.use_gitignore_files)
-            .hidden(true);
+        for (language, pending_inherits) in &self.pending_inherits {
+            match language {
+                Language::Go => {
+                    edges.extend(
+                        self.go_parser
+                            .resolve_pending_inherits(&self.nodes, &pending_inherits),
+                    );
+                }
+                Language::TypeScript => {
+                    edges.extend(
+                        self.typescript_parser
+                            .resolve_pending_inherits(&self.nodes, &pending_inherits),
+                    );
+                }
+                _ => {}
+            }
+        }
 
-        // Configure maximum recursion depth
-        if self.config.max_depth > 0 {
+        // The implemented interfaces, which may only be satisfied via the embedded types.
+        let implements_edges = self.go_parser.resolve_implements(&self.nodes, &edges);
+        edges.extend(implements_edges);
+
+        edges
+    }
+
+    /// Parses the Go standard library packages imported by the parsed files, whose nodes are named by their
+    /// absolute paths, e.g. `/usr/local/go/src/time/time.go:Time`.
+    ///
+    /// Only the definitions are kept, i.e. the imports, calls and references within the packages are not resolved.
+    fn parse_go_stdlib_packages(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // The packages of the repository are named relative to it, unlike the ones of the standard library.
+        let package_dirs: BTreeSet<String> = self
+            .edges
+            .iter()
+            .filter(|e| {
+                matches!(e.r#type, EdgeType::Imports)
+                    && e.to.r#type == NodeType::Directory
+                    && Path::new(&e.to.name).is_absolute()
+            })
+            .map(|e| e.to.name.clone())
+            .collect();
+
+        for package_dir in package_dirs {
+            if self.nodes.contains_key(&package_dir) {
+                continue;
+            }
+            let dir_node = directory_node(package_dir.clone());
+            self.add_node(&dir_node)?;
+
+            for entry in fs::read_dir(&package_dir)? {
+                let path = entry?.path();
+                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                if !file_name.ends_with(".go") || file_name.ends_with("_test.go") {
+                    continue;
+                }
+
+                let (file_node, nodes, edges, ..) = match self.parse_file(&path, None) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        log::warn!("Unable to parse {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                self.add_node(&file_node)?;
+                self.edges.push(Edge {
+                    r#type: EdgeType::Contains,
+                    from: dir_node.clone(),
+                    to: file_node,
+                    import: None,
+                    alias: None,
+                    properties: IndexMap::new(),
+                });
+                self.nodes.extend(nodes);
+                self.edges.extend(
+                    edges
+                        .into_iter()
+                        .filter(|e| !matches!(e.r#type, EdgeType::Imports)),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses the stub files imported by the parsed files, which are named by their absolute paths.
+    ///
+    /// Only the definitions are kept, along with their `Contains` (and `Inherits`) edges, i.e. there are no imports,
+    /// calls or references from the stub files or their definitions.
+    fn parse_stub_files(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let stub_files: BTreeSet<String> = self
+            .pending_imports
+            .values()
+            .flat_map(|pending_imports| pending_imports.values().flatten())
+            .map(|imp| imp.source_path.clone())
+            .filter(|source_path| {
+                self.config
+                    .stub_paths
+                    .iter()
+                    .any(|stub_path| Path::new(source_path).starts_with(stub_path))
+            })
+            .collect();
+
+        for stub_file in stub_files {
+            if self.nodes.contains_key(&stub_file) {
+                continue;
+            }
+            let (file_node, nodes, edges, ..) = match self.parse_file(Path::new(&stub_file), None) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    log::warn!("Unable to parse {}: {}", stub_file, e);
+                    continue;
+                }
+            };
+            self.add_node(&file_node)?;
+            self.nodes.extend(nodes);
+            self.edges.extend(edges.into_iter().filter(|e| {
+                !matches!(
+                    e.r#type,
+                    EdgeType::Imports | EdgeType::Calls | EdgeType::References
+                )
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn resolve_reexported_param_types(&mut self) {
+        let (Some(func_param_types), Some(pending_imports)) = (
+            self.func_param_types.get_mut(&Language::TypeScript),
+            self.pending_imports.get(&Language::TypeScript),
+        ) else {
+            return;
+        };
+        self.typescript_parser
+            .resolve_reexported_param_types(func_param_types, pending_imports);
+    }
+
+    fn resolve_func_param_type_edges(
+        &self,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for (language, func_param_types) in &self.func_param_types {
+            match language {
+                Language::Go => {
+                    let go_edges = self.go_parser.resolve_func_param_type_edges(
+                        &self.nodes,
+                        &func_param_types,
+                        db,
+                    )?;
+                    edges.extend(go_edges);
+                }
+                Language::TypeScript => {
+                    let ts_edges = if self.parsing_partially() {
+                        self.typescript_parser
+                            .resolve_func_param_type_edges_from_db(
+                                &self.nodes,
+                                &func_param_types,
+                                db,
+                            )?
+                    } else {
+                        self.typescript_parser.resolve_func_param_type_edges(
+                            &self.nodes,
+                            &func_param_types,
+                            db,
+                        )?
+                    };
+                    edges.extend(ts_edges);
+                }
+                Language::Python => {
+                    edges.extend(
+                        self.python_parser
+                            .resolve_func_param_type_edges(&self.nodes, &func_param_types),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Traverses all files and directories in the specified directory, creates Node and Edge objects
+    /// This method processes files by calling self.parse_file directly when encountering supported file types
+    /// Uses the ignore library for better gitignore handling
+    ///
+    /// # Arguments
+    /// - `dir_path`: The directory path to traverse
+    ///
+    /// # Returns
+    /// - Result indicating success or failure of the traversal operation
+    pub fn traverse_directory(
+        &mut self,
+        dir_path: &PathBuf,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Check if directory exists
+        if !dir_path.exists() {
+            return Err(format!("Directory does not exist: {}", dir_path.display()).into());
+        }
+
+        let mut processed_paths: std::collections::HashSet<PathBuf> =
+            std::collections::HashSet::new();
+
+        // Create WalkBuilder instance with better gitignore support
+        let mut builder = WalkBuilder::new(dir_path);
+
+        // Configure basic options
+        builder
+            .follow_links(self.config.follow_links)
+            .git_ignore(self.config.use_gitignore_files)
+            .git_global(self.config.use_gitignore_files)
+            .git_exclude(self.config.use_gitignore_files)
+            .hidden(true);
+
+        // Configure maximum recursion depth
+        if self.config.max_depth > 0 {
             builder.max_depth(Some(self.config.max_depth));
         }
 
@@ -308,71 +1242,78 @@ impl Parser {
         }
 
         // Add custom ignore patterns using overrides
-        if !self.config.ignore_patterns.is_empty() {
-            let mut overrides = OverrideBuilder::new(dir_path);
-
-            for pattern in &self.config.ignore_patterns {
-                // In OverrideBuilder.add(), `!` has the opposite meaning compared to `gitignore`.
-                // See https://docs.rs/ignore/latest/ignore/overrides/struct.OverrideBuilder.html#method.add.
-                //
-                // Therefore, we need to negate the pattern to match gitignore behavior.
-                let ignore_pattern = if pattern.starts_with('!') {
-                    // Remove the `!` prefix if present
-                    &pattern[1..]
-                } else {
-                    // Add the `!` prefix if not present
-                    &format!("!{}", pattern)
-                };
-
-                if let Err(e) = overrides.add(ignore_pattern) {
-                    log::warn!("Unable to add ignore pattern '{}': {}", pattern, e);
-                }
-            }
-
-            if let Ok(overrides) = overrides.build() {
-                builder.overrides(overrides);
-            }
+        if let Some(overrides) = self.ignore_overrides() {
+            builder.overrides(overrides);
         }
 
         // Build the walker
         let walker = builder.build();
 
+        // Nodes are named relative to the repository, where the root directory itself is named "".
+        let dir_name = dir_path
+            .strip_prefix(&self.repo_path)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let rel_name = |path: &Path| -> String {
+            Path::new(&dir_name)
+                .join(path.strip_prefix(dir_path).unwrap_or(path))
+                .to_string_lossy()
+                .to_string()
+        };
+
         // Create root directory node
-        let root_node = Node {
+        if self.config.emit_root_node {
             // kuzu CSV does not support empty string as node name, so use "" for root directory
-            name: String::from(""),
-            r#type: NodeType::Directory,
-            language: Language::Text,
-            start_line: 0,
-            end_line: 0,
-            code: String::new(),
-            skeleton_code: String::from(""),
-        };
-        self.add_node(&root_node)?;
-        processed_paths.insert(dir_path.clone());
+            self.add_node(&directory_node(String::new()))?;
+        }
+
+        // When traversing a subdirectory, create the directory chain from the root directory down to it,
+        // so that the subdirectory is still reachable from the root directory.
+        if !dir_name.is_empty() {
+            let mut parent_name = String::new();
+            for component in Path::new(&dir_name).components() {
+                let name = Path::new(&parent_name)
+                    .join(component)
+                    .to_string_lossy()
+                    .to_string();
+                let node = directory_node(name.clone());
+                self.add_node(&node)?;
+                self.edges.push(Edge {
+                    r#type: EdgeType::Contains,
+                    from: directory_node(parent_name),
+                    to: node,
+                    import: None,
+                    alias: None,
+                    properties: IndexMap::new(),
+                });
+                parent_name = name;
+            }
+        }
+        processed_paths.insert(canonical_path(dir_path));
 
-        // Traverse directory using ignore library
+        // Traverse directory using ignore library, where the entries to index are collected in the traversal
+        // order, as (path, whether it is a directory, whether its parent directory node is missing).
+        let mut entries: Vec<(PathBuf, bool, bool)> = Vec::new();
         for result in walker {
+            if self.is_cancelled() {
+                return Err(IndexCancelled.into());
+            }
+
             match result {
                 Ok(entry) => {
                     let entry_path = entry.path();
 
-                    // Skip if not supported file types (.go, .ts, .py)
-                    if entry_path.is_file() {
-                        let extension = entry_path.extension().and_then(|ext| ext.to_str());
-                        match extension {
-                            Some("go") | Some("ts") | Some("py") => {
-                                // Continue processing supported files
-                            }
-                            _ => {
-                                // Skip unsupported file types
-                                continue;
-                            }
-                        }
+                    // Skip if not supported file types (.go, .ts, .tsx, .js, .jsx, .py, .rs)
+                    if entry_path.is_file() && !self.is_supported_file(entry_path) {
+                        continue;
                     }
 
-                    // Skip if already processed
-                    if processed_paths.contains(entry_path) {
+                    // Skip if already processed.
+                    //
+                    // Paths are compared in their canonical form, so that the same file or directory
+                    // reached through symbolic links (possibly in a loop) is only processed once.
+                    let entry_canonical_path = canonical_path(entry_path);
+                    if processed_paths.contains(&entry_canonical_path) {
                         continue;
                     }
 
@@ -381,107 +1322,24 @@ impl Parser {
                         continue;
                     }
 
-                    log::trace!("Indexing path: {:?}", entry_path.display());
-
-                    // Create node for current entry
-                    let current_node = if entry_path.is_dir() {
-                        Node {
-                            name: entry_path
-                                .strip_prefix(dir_path)
-                                .unwrap_or(entry_path)
-                                .to_string_lossy()
-                                .to_string(),
-                            r#type: NodeType::Directory,
-                            language: Language::Text,
-                            start_line: 0,
-                            end_line: 0,
-                            code: String::new(),
-                            skeleton_code: String::from(""),
-                        }
-                    } else {
-                        // Parse file and extract nodes/edges
-                        let (file_node, nodes, edges, pending_imports, func_param_types) =
-                            self.parse_file(&entry_path, None)?;
-                        let language = file_node.language.clone();
-
-                        // Add parsed nodes to the collection
-                        for (n_name, n) in nodes {
-                            self.nodes.insert(n_name, n);
-                        }
-
-                        // Add parsed edges to the collection
-                        for edge in edges {
-                            self.edges.push(edge);
-                        }
-
-                        // Store pending imports for later resolution
-                        if pending_imports.len() > 0 {
-                            self.pending_imports
-                                .entry(language.clone())
-                                .or_insert_with(HashMap::new)
-                                .insert(file_node.name.clone(), pending_imports);
-                        }
-
-                        // Store function parameter types for later resolution
-                        if let Some(func_param_types) = func_param_types {
-                            self.func_param_types
-                                .entry(language.clone())
-                                .or_insert_with(HashMap::new)
-                                .extend(func_param_types);
-                        }
-
-                        // Sleep for a short duration to avoid high CPU usage during traversal
-                        thread::sleep(Duration::from_millis(1));
-
-                        file_node
-                    };
-
-                    self.add_node(&current_node)?;
-                    processed_paths.insert(entry_path.to_path_buf());
-
-                    // Create Contains edge from parent to current node
-                    if let Some(parent_path) = entry_path.parent() {
-                        let parent_path_str = if parent_path == dir_path {
-                            // Parent is the root directory
-                            String::from("")
-                        } else {
-                            // Parent is a subdirectory
-                            parent_path
-                                .strip_prefix(dir_path)
-                                .unwrap_or(parent_path)
-                                .to_string_lossy()
-                                .to_string()
-                        };
-
-                        // Ensure parent directory node exists
-                        if !processed_paths.contains(parent_path) && parent_path != dir_path {
-                            let parent_node = Node {
-                                name: parent_path_str.clone(),
-                                r#type: NodeType::Directory,
-                                language: Language::Text,
-                                start_line: 0,
-                                end_line: 0,
-                                code: String::new(),
-                                skeleton_code: String::from(""),
-                            };
-                            self.add_node(&parent_node)?;
-                            processed_paths.insert(parent_path.to_path_buf());
-                        }
-
-                        // Create Contains edge from parent to current node
-                        if let Some(parent_node) = self.nodes.get(&parent_path_str) {
-                            let edge = Edge {
-                                r#type: EdgeType::Contains,
-                                from: parent_node.clone(),
-                                to: current_node.clone(),
-                                import: None,
-                                alias: None,
-                            };
-                            self.edges.push(edge);
-                        }
-                    }
+                    processed_paths.insert(entry_canonical_path);
+                    let missing_parent = entry_path.parent().is_some_and(|parent_path| {
+                        parent_path != dir_path
+                            && processed_paths.insert(canonical_path(parent_path))
+                    });
+                    entries.push((
+                        entry_path.to_path_buf(),
+                        entry_path.is_dir(),
+                        missing_parent,
+                    ));
                 }
                 Err(err) => {
+                    // Symbolic link loops are expected when following links, just skip them.
+                    if is_loop_error(&err) {
+                        log::warn!("Skipping file system loop: {}", err);
+                        continue;
+                    }
+
                     // Handle errors based on configuration
                     if self.config.continue_on_error {
                         eprintln!("Error encountered during traversal, continuing: {}", err);
@@ -493,15 +1351,246 @@ impl Parser {
             }
         }
 
+        // Parse the files in parallel (within the pool capped by `max_parse_threads`, if any), while keeping
+        // the results in the traversal order, so that merging them (e.g. nodes with the same name overwrite
+        // the earlier ones) does not depend on the thread timing.
+        let parsed_files: Option<Vec<_>> = entries
+            .par_iter()
+            .filter(|(_, is_dir, _)| !is_dir)
+            .map(|(entry_path, _, _)| {
+                if self.is_cancelled() {
+                    return None;
+                }
+                let started = Instant::now();
+                let parsed = self.parse_file(entry_path, None);
+                let parse_time = started.elapsed();
+                if self.config.throttle_ms > 0 {
+                    thread::sleep(Duration::from_millis(self.config.throttle_ms));
+                }
+                Some((parse_time, parsed))
+            })
+            .collect();
+        let Some(parsed_files) = parsed_files else {
+            return Err(IndexCancelled.into());
+        };
+        let mut parsed_files = parsed_files.into_iter();
+
+        for (entry_path, is_dir, missing_parent) in entries {
+            if self.is_cancelled() {
+                return Err(IndexCancelled.into());
+            }
+
+            let entry_path = entry_path.as_path();
+            log::trace!("Indexing path: {:?}", entry_path.display());
+
+            // Create node for current entry
+            let current_node = if is_dir {
+                directory_node(rel_name(entry_path))
+            } else {
+                // Merge the nodes/edges of the parsed file
+                let (parse_time, parsed) = parsed_files.next().unwrap();
+                self.record_diagnostics(
+                    entry_path,
+                    parse_time,
+                    parsed
+                        .as_ref()
+                        .map(|p| p.1.len())
+                        .map_err(|e| e.to_string()),
+                );
+                let (
+                    file_node,
+                    nodes,
+                    edges,
+                    pending_imports,
+                    func_param_types,
+                    pending_calls,
+                    pending_inherits,
+                ) = match parsed {
+                    Ok(parsed) => parsed,
+                    // The error is recorded in the diagnostics (if enabled).
+                    Err(e) if self.config.continue_on_error => {
+                        log::warn!("Skipping file {}: {}", entry_path.display(), e);
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+                if self.is_skipped_empty_file(&nodes, &edges, &pending_imports) {
+                    log::debug!("Skipping file without definitions: {}", file_node.name);
+                    continue;
+                }
+                let language = file_node.language.clone();
+
+                // Add parsed nodes to the collection
+                for (n_name, n) in nodes {
+                    self.nodes.insert(n_name, n);
+                }
+
+                // Add parsed edges to the collection
+                for edge in edges {
+                    self.edges.push(edge);
+                }
+
+                // Store pending imports for later resolution
+                if pending_imports.len() > 0 {
+                    self.pending_imports
+                        .entry(language.clone())
+                        .or_insert_with(HashMap::new)
+                        .insert(file_node.name.clone(), pending_imports);
+                }
+
+                // Store function parameter types for later resolution
+                if let Some(func_param_types) = func_param_types {
+                    self.func_param_types
+                        .entry(language.clone())
+                        .or_insert_with(HashMap::new)
+                        .extend(func_param_types);
+                }
+
+                // Store function calls for later resolution
+                if pending_calls.len() > 0 {
+                    self.pending_calls
+                        .entry(language.clone())
+                        .or_insert_with(Vec::new)
+                        .extend(pending_calls);
+                }
+
+                // Store embedded types for later resolution
+                if pending_inherits.len() > 0 {
+                    self.pending_inherits
+                        .entry(language.clone())
+                        .or_insert_with(Vec::new)
+                        .extend(pending_inherits);
+                }
+
+                file_node
+            };
+
+            self.add_node(&current_node)?;
+
+            // Create Contains edge from parent to current node
+            if let Some(parent_path) = entry_path.parent() {
+                let parent_path_str = if parent_path == dir_path {
+                    // Parent is the traversed directory
+                    dir_name.clone()
+                } else {
+                    // Parent is a subdirectory
+                    rel_name(parent_path)
+                };
+
+                // Ensure parent directory node exists
+                if missing_parent {
+                    let parent_node = directory_node(parent_path_str.clone());
+                    self.add_node(&parent_node)?;
+                }
+
+                // Create Contains edge from parent to current node, unless the parent is the root
+                // directory and its node is not emitted.
+                if let Some(parent_node) = self.nodes.get(&parent_path_str) {
+                    let edge = Edge {
+                        r#type: EdgeType::Contains,
+                        from: parent_node.clone(),
+                        to: current_node.clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    };
+                    self.edges.push(edge);
+                }
+            }
+        }
+
+        if !self.config.emit_root_node {
+            // Drop the edges to the root directory, e.g. Go imports of the root package.
+            self.edges
+                .retain(|edge| !edge.from.name.is_empty() && !edge.to.name.is_empty());
+        }
+
+        Ok(())
+    }
+
+    /// Records the diagnostics of parsing the file at `path`, which took `parse_time`, if enabled.
+    ///
+    /// `parsed` is the number of parsed nodes, or the parsing error.
+    ///
+    /// The `on_file_parsed` callback (if any) is also called here.
+    fn record_diagnostics(
+        &mut self,
+        path: &Path,
+        parse_time: Duration,
+        parsed: Result<usize, String>,
+    ) {
+        let file = path
+            .strip_prefix(&self.repo_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        if let Some(on_file_parsed) = &mut self.on_file_parsed {
+            (on_file_parsed.get_mut().unwrap())(&file);
+        }
+        if self.config.diagnostics_path.is_none() {
+            return;
+        }
+        let parse_time_ms = parse_time.as_secs_f64() * 1000.0;
+        let (node_count, error) = match parsed {
+            Ok(node_count) => (node_count, None),
+            Err(e) => (0, Some(e)),
+        };
+        self.diagnostics.push(FileDiagnostics {
+            file,
+            parse_time_ms,
+            node_count,
+            unresolved_imports: Vec::new(),
+            error,
+        });
+    }
+
+    /// Appends the diagnostics of the parsed files to `diagnostics_path` (if configured) as JSON lines,
+    /// along with the imports that are not resolved by `resolved_edges`.
+    pub fn write_diagnostics(
+        &self,
+        resolved_edges: &[Edge],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.config.diagnostics_path.is_none() {
+            return Ok(());
+        }
+        self.write_file_diagnostics(&self.unresolved_pending_imports(resolved_edges))
+    }
+
+    /// Appends the diagnostics of the parsed files to `diagnostics_path` (if configured), along with
+    /// the given unresolved imports by file.
+    fn write_file_diagnostics(
+        &self,
+        unresolved_imports: &BTreeMap<String, Vec<String>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(diagnostics_path) = &self.config.diagnostics_path else {
+            return Ok(());
+        };
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(diagnostics_path)?;
+        let mut writer = std::io::BufWriter::new(file);
+        for diagnostics in &self.diagnostics {
+            let mut diagnostics = diagnostics.clone();
+            if let Some(imports) = unresolved_imports.get(&diagnostics.file) {
+                diagnostics.unresolved_imports = imports.clone();
+            }
+            serde_json::to_writer(&mut writer, &diagnostics)?;
+            std::io::Write::write_all(&mut writer, b"\n")?;
+        }
+        std::io::Write::flush(&mut writer)?;
+
         Ok(())
     }
 
-    fn add_node(&mut self, node: &Node) -> Result<(), Box<dyn std::error::Error>> {
+    fn add_node(&mut self, node: &Node) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.nodes.insert(node.name.clone(), node.clone());
 
         Ok(())
     }
 
+    /// Parses the given file, whose file node is filled in with the summary of the parsed definitions and imports.
     pub fn parse_file(
         &self,
         file_path: &Path,
@@ -513,13 +1602,66 @@ impl Parser {
             Vec<Edge>,
             Vec<PendingImport>,
             Option<HashMap<String, Vec<FuncParamType>>>,
+            Vec<PendingCall>,
+            Vec<PendingInherit>,
+        ),
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
+        let (
+            mut file_node,
+            nodes,
+            edges,
+            pending_imports,
+            func_param_types,
+            pending_calls,
+            pending_inherits,
+        ) = self.parse_file_definitions(file_path, file_content)?;
+        file_node.summary = file_summary(&file_node, &nodes, &edges, &pending_imports);
+        Ok((
+            file_node,
+            nodes,
+            edges,
+            pending_imports,
+            func_param_types,
+            pending_calls,
+            pending_inherits,
+        ))
+    }
+
+    fn parse_file_definitions(
+        &self,
+        file_path: &Path,
+        file_content: Option<&[u8]>,
+    ) -> Result<
+        (
+            Node,
+            IndexMap<String, Node>,
+            Vec<Edge>,
+            Vec<PendingImport>,
+            Option<HashMap<String, Vec<FuncParamType>>>,
+            Vec<PendingCall>,
+            Vec<PendingInherit>,
         ),
-        Box<dyn std::error::Error>,
+        Box<dyn std::error::Error + Send + Sync>,
     > {
-        let final_file_content = if let Some(file_content) = file_content {
-            file_content
+        let read_file_content;
+        let final_file_content = match file_content {
+            Some(file_content) => file_content,
+            None => {
+                read_file_content = fs::read(file_path)?;
+                &read_file_content[..]
+            }
+        };
+        // The hash is of the content as is (i.e. on the disk, unless dirty), which is compared before parsing.
+        let content_hash = self.content_hash(file_path, final_file_content);
+        // All the byte offsets (and thus the code) are based on the normalized content, while the line numbers
+        // stay the same.
+        let normalized_file_content;
+        let final_file_content = if self.config.normalize_line_endings {
+            normalized_file_content = util::normalize_line_endings(final_file_content);
+            &normalized_file_content[..]
         } else {
-            &fs::read(&file_path).expect("Should have been able to read the file")
+            final_file_content
         };
         let file = File {
             path: &file_path.to_path_buf(),
@@ -527,39 +1669,205 @@ impl Parser {
         };
 
         let file_language = Language::from_path(file_path.to_path_buf().to_str().unwrap());
+        let file_node_name = file_path
+            .strip_prefix(&self.repo_path)
+            .unwrap_or(file_path)
+            .to_string_lossy()
+            .to_string();
+        let generated = util::is_generated_file(&file_node_name, final_file_content);
         let file_node = Node {
-            name: file_path
-                .strip_prefix(&self.repo_path)
-                .unwrap_or(file_path)
-                .to_string_lossy()
-                .to_string(),
+            name: file_node_name,
             r#type: NodeType::File,
-            language: file_language,
+            language: file_language.clone(),
             start_line: 0,
             end_line: 0,                     // TODO: add end line number
             code: String::new(),             // TODO: add file code
             skeleton_code: String::from(""), // TODO: add file skeleton code
+            generated,
+            grammar_version: file_language.grammar_version().to_string(),
+            content_hash,
+            ..Default::default()
         };
+        if generated && self.config.exclude_generated_files {
+            return Ok((
+                file_node,
+                IndexMap::new(),
+                vec![],
+                vec![],
+                None,
+                vec![],
+                vec![],
+            ));
+        }
         // Parse the file and add parsed nodes to the collection
         match file_node.language {
             Language::Go => {
-                let (nodes, edges, func_param_types) = self.go_parser.parse(&file_node, &file)?;
-                return Ok((file_node, nodes, edges, vec![], func_param_types));
+                let (mut nodes, mut edges, func_param_types, pending_calls, pending_inherits) =
+                    self.go_parser.parse(&file_node, &file)?;
+                fill_doc_comments(&mut nodes, final_file_content);
+                self.add_todo_comments(&file_node, &mut nodes, &mut edges, final_file_content);
+                return Ok((
+                    file_node,
+                    nodes,
+                    edges,
+                    vec![],
+                    func_param_types,
+                    pending_calls,
+                    pending_inherits,
+                ));
             }
             Language::TypeScript => {
-                let (nodes, edges, pending_imports, func_param_types) =
-                    self.typescript_parser.parse(&file_node, &file)?;
-                return Ok((file_node, nodes, edges, pending_imports, func_param_types));
+                let (
+                    mut nodes,
+                    mut edges,
+                    pending_imports,
+                    func_param_types,
+                    pending_calls,
+                    pending_inherits,
+                ) = self.typescript_parser.parse(&file_node, &file)?;
+                fill_doc_comments(&mut nodes, final_file_content);
+                self.add_todo_comments(&file_node, &mut nodes, &mut edges, final_file_content);
+                return Ok((
+                    file_node,
+                    nodes,
+                    edges,
+                    pending_imports,
+                    func_param_types,
+                    pending_calls,
+                    pending_inherits,
+                ));
             }
             Language::Python => {
-                let (nodes, edges) = self.python_parser.parse(&file_node, &file)?;
-                return Ok((file_node, nodes, edges, vec![], None));
+                let (mut nodes, mut edges, pending_imports, func_param_types) =
+                    self.python_parser.parse(&file_node, &file)?;
+                fill_doc_comments(&mut nodes, final_file_content);
+                self.add_todo_comments(&file_node, &mut nodes, &mut edges, final_file_content);
+                return Ok((
+                    file_node,
+                    nodes,
+                    edges,
+                    pending_imports,
+                    func_param_types,
+                    vec![],
+                    vec![],
+                ));
+            }
+            Language::Rust => {
+                let (mut nodes, mut edges, pending_imports) =
+                    self.rust_parser.parse(&file_node, &file)?;
+                fill_doc_comments(&mut nodes, final_file_content);
+                self.add_todo_comments(&file_node, &mut nodes, &mut edges, final_file_content);
+                return Ok((
+                    file_node,
+                    nodes,
+                    edges,
+                    pending_imports,
+                    None,
+                    vec![],
+                    vec![],
+                ));
+            }
+            Language::Yaml | Language::Json => {
+                if !self.config.parse_config_files {
+                    return Ok((
+                        file_node,
+                        IndexMap::new(),
+                        vec![],
+                        vec![],
+                        None,
+                        vec![],
+                        vec![],
+                    ));
+                }
+                let (nodes, edges) = self.config_file_parser.parse(&file_node, &file)?;
+                return Ok((file_node, nodes, edges, vec![], None, vec![], vec![]));
             }
             Language::Text => {
-                return Ok((file_node, IndexMap::new(), vec![], vec![], None));
+                return Ok((
+                    file_node,
+                    IndexMap::new(),
+                    vec![],
+                    vec![],
+                    None,
+                    vec![],
+                    vec![],
+                ));
             }
         }
     }
+
+    /// Adds the `TODO`/`FIXME` comments of the file as nodes, if enabled.
+    fn add_todo_comments(
+        &self,
+        file_node: &Node,
+        nodes: &mut IndexMap<String, Node>,
+        edges: &mut Vec<Edge>,
+        source_code: &[u8],
+    ) {
+        if !self.config.capture_todo_comments {
+            return;
+        }
+        let (todo_nodes, todo_edges) = common::todo_comments(file_node, nodes, source_code);
+        nodes.extend(todo_nodes);
+        edges.extend(todo_edges);
+    }
+}
+
+/// Returns the summary of the file with the given parsed definitions and imports.
+///
+/// The imports are counted before resolution, i.e. the import edges from the file plus the pending imports.
+fn file_summary(
+    file_node: &Node,
+    nodes: &IndexMap<String, Node>,
+    edges: &[Edge],
+    pending_imports: &[PendingImport],
+) -> FileSummary {
+    let count_nodes = |r#type: NodeType| nodes.values().filter(|n| n.r#type == r#type).count();
+    let import_edges = edges
+        .iter()
+        .filter(|e| matches!(e.r#type, EdgeType::Imports) && e.from.name == file_node.name)
+        .count();
+    FileSummary {
+        function_count: count_nodes(NodeType::Function),
+        class_count: count_nodes(NodeType::Class),
+        import_count: import_edges + pending_imports.len(),
+    }
+}
+
+/// Fills in the doc comments of the parsed definition nodes.
+fn fill_doc_comments(nodes: &mut IndexMap<String, Node>, source_code: &[u8]) {
+    for node in nodes.values_mut() {
+        node.doc_comment = common::doc_comment(node, source_code);
+    }
+}
+
+/// Creates the node of the directory with the given name.
+fn directory_node(name: String) -> Node {
+    Node {
+        name,
+        r#type: NodeType::Directory,
+        language: Language::Text,
+        start_line: 0,
+        end_line: 0,
+        skeleton_code: String::from(""),
+        ..Default::default()
+    }
+}
+
+/// Returns the canonical form of the given path, or the path itself if it cannot be canonicalized.
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Checks whether the given traversal error is caused by a file system loop (i.e. symbolic link loop).
+fn is_loop_error(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop_error(err),
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -574,6 +1882,26 @@ mod tests {
             .try_init();
     }
 
+    /// Returns the names of the type nodes referenced by the parameter types of the given function, as resolved
+    /// against the parsed nodes.
+    fn param_type_references(parser: &Parser, language: Language, func_name: &str) -> Vec<String> {
+        let mut references: Vec<String> = Vec::new();
+        for param_type in &parser.func_param_types[&language][func_name] {
+            let Some(package_name) = &param_type.package_name else {
+                continue;
+            };
+            for type_node in common::find_scoped_type_nodes(
+                &parser.nodes,
+                package_name,
+                param_type.scope,
+                &param_type.type_name,
+            ) {
+                references.push(type_node.name.clone());
+            }
+        }
+        references
+    }
+
     #[test]
     fn test_parse_python() {
         // Create test file
@@ -600,25 +1928,680 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_go() {
-        // Create test file
+    fn test_parse_max_parse_threads() {
+        init();
+
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
         let dir_path = PathBuf::from(manifest_dir)
             .join("examples")
-            .join("go")
-            .join("demo");
+            .join("typescript");
 
-        let config = ParserConfig::default().ignore_patterns(vec!["diff".into()]);
-        let mut parser = Parser::new(dir_path.clone(), config);
+        let parse = |config: ParserConfig| -> (Vec<String>, Vec<String>) {
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let (nodes, mut edges) = parser.parse(&dir_path, None).unwrap();
+            edges.extend(parser.resolve_pending_edges(None).unwrap());
+            let node_strings = nodes.values().map(|n| n.name.clone()).collect();
+            let mut edge_strings: Vec<String> = edges
+                .iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            // The resolved edges are not ordered.
+            edge_strings.sort();
+            (node_strings, edge_strings)
+        };
 
-        let result = parser.parse(&dir_path, None);
-        match result {
-            Ok((nodes, edges)) => {
-                let mut node_strings: Vec<_> = nodes.values().cloned().map(|n| n.name).collect();
-                let mut edge_strings: Vec<_> = edges
-                    .into_iter()
-                    .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
-                    .collect();
+        let (nodes, edges) = parse(ParserConfig::default());
+        assert!(!nodes.is_empty());
+        assert_eq!(
+            parse(ParserConfig::default().max_parse_threads(1)),
+            (nodes, edges),
+        );
+    }
+
+    #[test]
+    fn test_parse_in_parallel_matches_serial() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        for i in 0..4 {
+            let pkg_dir = dir_path.join(format!("pkg{}", i));
+            fs::create_dir(&pkg_dir).unwrap();
+            for j in 0..12 {
+                fs::write(
+                    pkg_dir.join(format!("file{}.go", j)),
+                    format!(
+                        "package pkg{i}\n\ntype T{j} struct{{}}\n\nfunc (t *T{j}) Get() int {{ return {j} }}\n"
+                    ),
+                )
+                .unwrap();
+                // The later definition of `A` overwrites the earlier one.
+                fs::write(
+                    pkg_dir.join(format!("file{}.py", j)),
+                    format!("class A:\n    x = 1\n\nclass A:\n    x = {j}\n"),
+                )
+                .unwrap();
+            }
+        }
+
+        let parse = |config: ParserConfig| -> (String, String) {
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+            assert_eq!(
+                nodes
+                    .values()
+                    .filter(|n| n.r#type == NodeType::File)
+                    .count(),
+                96
+            );
+            (format!("{:?}", nodes), format!("{:?}", edges))
+        };
+
+        // The output (including the order) is the same as parsing the files one at a time.
+        assert_eq!(
+            parse(ParserConfig::default()),
+            parse(ParserConfig::default().max_parse_threads(1)),
+        );
+    }
+
+    #[test]
+    fn test_parse_skip_empty_files() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(dir_path.join("main.go"), "package main\n\nfunc main() {}\n").unwrap();
+        fs::write(
+            dir_path.join("doc.go"),
+            "// Package main is an example.\npackage main\n",
+        )
+        .unwrap();
+        fs::write(dir_path.join("user.ts"), "export interface User {}\n").unwrap();
+        fs::write(
+            dir_path.join("index.ts"),
+            "export { User } from './user';\n",
+        )
+        .unwrap();
+
+        let parse = |config: ParserConfig| -> (Vec<String>, Vec<String>) {
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+            let mut node_strings: Vec<_> = nodes.values().map(|n| n.name.clone()).collect();
+            node_strings.sort();
+            let mut edge_strings: Vec<_> = edges
+                .iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            (node_strings, edge_strings)
+        };
+
+        // The empty files are kept by default.
+        let (nodes, edges) = parse(ParserConfig::default());
+        assert_eq!(
+            nodes,
+            [
+                "",
+                "doc.go",
+                "index.ts",
+                "main.go",
+                "main.go:main",
+                "user.ts",
+                "user.ts:User"
+            ]
+        );
+        assert!(edges.contains(&"-[contains]->doc.go".to_string()));
+
+        // The barrel file is kept, since it re-exports.
+        let (nodes, edges) = parse(ParserConfig::default().skip_empty_files(true));
+        assert_eq!(
+            nodes,
+            [
+                "",
+                "index.ts",
+                "main.go",
+                "main.go:main",
+                "user.ts",
+                "user.ts:User"
+            ]
+        );
+        assert_eq!(
+            edges,
+            [
+                "-[contains]->index.ts",
+                "-[contains]->main.go",
+                "-[contains]->user.ts",
+                "main.go-[contains]->main.go:main",
+                "user.ts-[contains]->user.ts:User"
+            ]
+        );
+
+        // The same applies to parsing a single file.
+        let parse_file = |config: ParserConfig, file_name: &str| -> Vec<String> {
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let (nodes, _) = parser.parse(&dir_path.join(file_name), None).unwrap();
+            nodes.keys().cloned().collect()
+        };
+        assert_eq!(parse_file(ParserConfig::default(), "doc.go"), ["doc.go"]);
+        assert!(parse_file(ParserConfig::default().skip_empty_files(true), "doc.go").is_empty());
+        assert_eq!(
+            parse_file(ParserConfig::default().skip_empty_files(true), "index.ts"),
+            ["index.ts"]
+        );
+    }
+
+    #[test]
+    #[ignore] // A benchmark, run with `cargo test test_parse_throttle_ms -- --ignored --nocapture`.
+    fn test_parse_throttle_ms() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        for i in 0..500 {
+            fs::write(
+                dir_path.join(format!("file{}.go", i)),
+                format!("package main\n\nfunc F{}() {{}}\n", i),
+            )
+            .unwrap();
+        }
+
+        let elapsed = |throttle_ms: u64| -> Duration {
+            let config = ParserConfig::default().throttle_ms(throttle_ms);
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let started = Instant::now();
+            parser.parse(&dir_path, None).unwrap();
+            started.elapsed()
+        };
+
+        let unthrottled = elapsed(0);
+        let throttled = elapsed(5);
+        println!(
+            "parsing 500 files: {:?} with throttle_ms=0, {:?} with throttle_ms=5",
+            unthrottled, throttled
+        );
+        assert!(throttled > unthrottled);
+    }
+
+    #[test]
+    fn test_parse_python_nested_classes() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("python")
+            .join("nested");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let class_names: Vec<&str> = nodes
+            .values()
+            .filter(|node| node.r#type == NodeType::Class)
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(
+            class_names,
+            vec![
+                "shapes.py:Outer",
+                "shapes.py:Outer.Inner",
+                "shapes.py:Outer.Inner.Deepest",
+                "shapes.py:make_local.Local",
+                "shapes.py:Other",
+            ]
+        );
+
+        let contains_edges: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|edge| {
+                matches!(edge.r#type, EdgeType::Contains) && edge.to.r#type == NodeType::Class
+            })
+            .map(|edge| (edge.from.name.as_str(), edge.to.name.as_str()))
+            .collect();
+        assert_eq!(
+            contains_edges,
+            vec![
+                ("shapes.py", "shapes.py:Outer"),
+                ("shapes.py:Outer", "shapes.py:Outer.Inner"),
+                ("shapes.py:Outer.Inner", "shapes.py:Outer.Inner.Deepest"),
+                ("shapes.py", "shapes.py:make_local.Local"),
+                ("shapes.py", "shapes.py:Other"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_python_functions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("python");
+
+        let config = ParserConfig::default().ignore_patterns(vec!["diff".into(), "nested".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let functions: Vec<(&str, &str)> = nodes
+            .values()
+            .filter(|node| node.name.starts_with("a.py:") && node.r#type == NodeType::Function)
+            .map(|node| (node.name.as_str(), node.skeleton_code.as_str()))
+            .collect();
+        assert_eq!(
+            functions,
+            vec![
+                ("a.py:A.greet", "def greet(self) -> str:\n    ..."),
+                ("a.py:make_a", "def make_a(name: str) -> A:\n    ..."),
+            ]
+        );
+
+        let contains_edges: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|edge| {
+                matches!(edge.r#type, EdgeType::Contains) && edge.to.r#type == NodeType::Function
+            })
+            .filter(|edge| edge.to.name.starts_with("a.py:"))
+            .map(|edge| (edge.from.name.as_str(), edge.to.name.as_str()))
+            .collect();
+        assert_eq!(
+            contains_edges,
+            vec![("a.py:A", "a.py:A.greet"), ("a.py", "a.py:make_a")]
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_inherits() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-inherits");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (_, mut edges) = parser.parse(&dir_path, None).unwrap();
+        edges.extend(parser.resolve_pending_edges(None).unwrap());
+
+        let mut inherits_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Inherits))
+            .map(|edge| {
+                format!(
+                    "{}-[{}]->{} ({})",
+                    edge.from.name, edge.r#type, edge.to.name, edge.to.r#type
+                )
+            })
+            .collect();
+        inherits_edges.sort();
+        // `Animal` is imported from another file, while `Pet` is defined locally.
+        assert_eq!(
+            inherits_edges,
+            vec![
+                "dog.ts:Dog-[inherits]->animal.ts:Animal (Class)",
+                "dog.ts:Dog-[inherits]->dog.ts:Pet (Interface)",
+            ]
+        );
+
+        // The imported base is dropped if its file is not indexed.
+        let config = ParserConfig::default().ignore_patterns(vec!["animal.ts".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (_, mut edges) = parser.parse(&dir_path, None).unwrap();
+        edges.extend(parser.resolve_pending_edges(None).unwrap());
+        let inherits_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Inherits))
+            .map(|edge| format!("{}-[{}]->{}", edge.from.name, edge.r#type, edge.to.name))
+            .collect();
+        assert_eq!(inherits_edges, vec!["dog.ts:Dog-[inherits]->dog.ts:Pet"]);
+    }
+
+    #[test]
+    fn test_parse_typescript_member_access_param_types() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-member-access");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut references: Vec<String> = Vec::new();
+        for func_name in ["main.ts:distance", "main.ts:describe"] {
+            for type_name in param_type_references(&parser, Language::TypeScript, func_name) {
+                references.push(format!("{}-[references]->{}", func_name, type_name));
+            }
+        }
+        // The member paths are resolved through the namespace import, the named import of the
+        // namespace and the local namespace, while the unknown one is left unresolved.
+        assert_eq!(
+            references,
+            vec![
+                "main.ts:distance-[references]->geo.ts:Geo.Shapes.Point",
+                "main.ts:distance-[references]->geo.ts:Geo.Shapes.Point",
+                "main.ts:distance-[references]->main.ts:Local.Inner.Options",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_stub_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let example_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("stub-paths");
+        let dir_path = example_path.join("app");
+        let stub_path = example_path.join("stubs").canonicalize().unwrap();
+
+        // The imported modules are not resolved without the stubs.
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+        assert!(param_type_references(&parser, Language::Python, "main.py:fetch").is_empty());
+        assert!(param_type_references(&parser, Language::TypeScript, "main.ts:fetch").is_empty());
+
+        let config = ParserConfig::default().stub_paths(vec![example_path.join("stubs")]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+        assert_eq!(
+            param_type_references(&parser, Language::Python, "main.py:fetch"),
+            [format!(
+                "{}:Session",
+                stub_path.join("requests.pyi").display()
+            )]
+        );
+        assert_eq!(
+            param_type_references(&parser, Language::TypeScript, "main.ts:fetch"),
+            [format!(
+                "{}:AxiosInstance",
+                stub_path.join("axios.d.ts").display()
+            )]
+        );
+
+        // Only the imported definitions are parsed, which are contained in the stub files, without any other
+        // edges from them.
+        let response_name = format!("{}:Response", stub_path.join("requests.pyi").display());
+        assert!(nodes.contains_key(&response_name));
+        assert!(edges.iter().any(|e| matches!(e.r#type, EdgeType::Contains)
+            && e.from.name == stub_path.join("requests.pyi").display().to_string()
+            && e.to.name == response_name));
+        assert!(edges
+            .iter()
+            .filter(|e| Path::new(&e.from.name).starts_with(&stub_path))
+            .all(|e| matches!(e.r#type, EdgeType::Contains | EdgeType::Inherits)));
+    }
+
+    #[test]
+    fn test_parse_todo_comments() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("go-todo");
+
+        // The comments are not captured by default.
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        assert!(!nodes.keys().any(|name| name.contains('@')));
+
+        let config = ParserConfig::default().capture_todo_comments(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let todo_node = &nodes["main.go:TODO@9"];
+        assert_eq!(todo_node.r#type, NodeType::OtherType);
+        assert_eq!(todo_node.code, "TODO: support multiple names");
+        assert_eq!(todo_node.start_line, 8);
+        assert_eq!(todo_node.metadata["tag"], "TODO");
+        // The comment markers within string literals are not comments.
+        assert!(!nodes.contains_key("main.go:TODO@12"));
+
+        let mut todo_edges: Vec<String> = edges
+            .iter()
+            .filter(|e| matches!(e.r#type, EdgeType::Contains) && e.to.name.contains('@'))
+            .map(|e| format!("{}-[contains]->{}", e.from.name, e.to.name))
+            .collect();
+        todo_edges.sort();
+        assert_eq!(
+            todo_edges,
+            vec![
+                "main.go-[contains]->main.go:FIXME@5",
+                "main.go:main-[contains]->main.go:TODO@9",
+            ]
+        );
+
+        // The line numbers of the Python comments are 1-based, like the other Python nodes.
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("python-todo");
+        let config = ParserConfig::default().capture_todo_comments(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        assert_eq!(nodes["main.py:TODO@2"].start_line, 2);
+        assert_eq!(nodes["main.py:FIXME@4"].start_line, 4);
+        let mut todo_edges: Vec<String> = edges
+            .iter()
+            .filter(|e| matches!(e.r#type, EdgeType::Contains) && e.to.name.contains('@'))
+            .map(|e| format!("{}-[contains]->{}", e.from.name, e.to.name))
+            .collect();
+        todo_edges.sort();
+        // The comment right after the function is not contained in it.
+        assert_eq!(
+            todo_edges,
+            vec![
+                "main.py-[contains]->main.py:FIXME@4",
+                "main.py:greet-[contains]->main.py:TODO@2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_aliased_reexport() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-reexport");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let references: Vec<String> =
+            param_type_references(&parser, Language::TypeScript, "main.ts:greet")
+                .into_iter()
+                .map(|type_name| format!("main.ts:greet-[references]->{}", type_name))
+                .collect();
+        // `Account` is imported from `index.ts`, which re-exports `User` of `types.ts` under the alias.
+        assert_eq!(
+            references,
+            vec!["main.ts:greet-[references]->types.ts:User"]
+        );
+    }
+
+    #[test]
+    fn test_parse_tsx_component_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-tsx");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (_, mut edges) = parser.parse(&dir_path, None).unwrap();
+        edges.extend(parser.resolve_pending_edges(None).unwrap());
+
+        let mut reference_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::References))
+            .map(|edge| format!("{}-[{}]->{}", edge.from.name, edge.r#type, edge.to.name))
+            .collect();
+        reference_edges.sort();
+        // Intrinsic elements like `<main>` are not components.
+        assert_eq!(
+            reference_edges,
+            vec![
+                "App.tsx:App-[references]->App.tsx:Badge",
+                "App.tsx:App-[references]->components/UserCard.tsx:UserCard",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_function_calls() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-calls");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .filter(|r| matches!(r.r#type, EdgeType::Calls))
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        // The calls are resolved locally, by the named import and by the namespace import, while the
+        // call to `console.log` is dropped.
+        assert_eq!(
+            edge_strings,
+            [
+                "main.ts:run-[calls]->main.ts:log",
+                "main.ts:run-[calls]->utils.ts:formatName",
+                "main.ts:run-[calls]->utils.ts:shout",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_accessors() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-accessors");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        let mut method_strings: Vec<_> = nodes
+            .values()
+            .filter(|n| n.name.starts_with("temperature.ts:Temperature."))
+            .map(|n| format!("{} {} {}", n.name, n.accessor_kind, n.is_static))
+            .collect();
+        method_strings.sort();
+        // The getter and the setter of `fahrenheit` are distinct methods.
+        assert_eq!(
+            method_strings,
+            [
+                "temperature.ts:Temperature.fahrenheit Getter false",
+                "temperature.ts:Temperature.fahrenheit#set Setter false",
+                "temperature.ts:Temperature.fromFahrenheit None true",
+                "temperature.ts:Temperature.get None false",
+                "temperature.ts:Temperature.zero Getter true",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_python_imports() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("python")
+            .join("imports");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (_, mut edges) = parser.parse(&dir_path, None).unwrap();
+        edges.extend(parser.resolve_pending_edges(None).unwrap());
+
+        let mut import_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Imports))
+            .map(|edge| {
+                format!(
+                    "{}-[{}]->{} (alias: {:?})",
+                    edge.from.name, edge.r#type, edge.to.name, edge.alias
+                )
+            })
+            .collect();
+        import_edges.sort();
+        // `os` is not in the repository.
+        assert_eq!(
+            import_edges,
+            vec![
+                "main.py-[imports]->models.py:User (alias: None)",
+                "main.py-[imports]->pkg/helpers.py (alias: None)",
+                "main.py-[imports]->pkg/utils.py (alias: Some(\"utils\"))",
+            ]
+        );
+        assert!(parser.unresolved_imports().is_empty());
+    }
+
+    #[test]
+    fn test_parse_python_param_type_references() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("python")
+            .join("imports");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        // `greet(user: User, greeting: str)` only references `User`, since `str` is a builtin type, and so does
+        // `greet_all(users: List[User])`, since `List` is a `typing` construct.
+        let edges = parser.python_parser.resolve_func_param_type_edges(
+            &parser.nodes,
+            &parser.func_param_types[&Language::Python],
+        );
+        let mut edge_strings: Vec<String> = edges
+            .iter()
+            .map(|edge| format!("{}-[{}]->{}", edge.from.name, edge.r#type, edge.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            vec![
+                "main.py:greet-[references]->models.py:User",
+                "main.py:greet_all-[references]->models.py:User",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_go() {
+        // Create test file
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        let config = ParserConfig::default().ignore_patterns(vec!["diff".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+
+        let result = parser.parse(&dir_path, None);
+        match result {
+            Ok((nodes, edges)) => {
+                let mut node_strings: Vec<_> = nodes.values().cloned().map(|n| n.name).collect();
+                let mut edge_strings: Vec<_> = edges
+                    .into_iter()
+                    .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                    .collect();
 
                 node_strings.sort();
                 edge_strings.sort();
@@ -659,6 +2642,179 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_rust() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("rust")
+            .join("demo");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, mut edges) = parser.parse(&dir_path, None).unwrap();
+        edges.extend(parser.resolve_pending_edges(None).unwrap());
+
+        let mut node_strings: Vec<_> = nodes.values().map(|n| n.name.clone()).collect();
+        let mut edge_strings: Vec<_> = edges
+            .iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        node_strings.sort();
+        edge_strings.sort();
+
+        assert_eq!(
+            node_strings,
+            [
+                "",
+                "src",
+                "src/main.rs",
+                "src/main.rs:describe",
+                "src/main.rs:main",
+                "src/models.rs",
+                "src/models.rs:Role",
+                "src/models.rs:Shape",
+                "src/models.rs:Shape.area",
+                "src/models.rs:Shape.name",
+                "src/models.rs:User",
+                "src/models.rs:User.Shape.area",
+                "src/models.rs:User.greet",
+                "src/models.rs:User.new",
+            ]
+        );
+        assert_eq!(
+            edge_strings,
+            [
+                "-[contains]->src",
+                "src-[contains]->src/main.rs",
+                "src-[contains]->src/models.rs",
+                "src/main.rs-[contains]->src/main.rs:describe",
+                "src/main.rs-[contains]->src/main.rs:main",
+                "src/main.rs-[imports]->src/models.rs",
+                "src/models.rs-[contains]->src/models.rs:Role",
+                "src/models.rs-[contains]->src/models.rs:Shape",
+                "src/models.rs-[contains]->src/models.rs:User",
+                "src/models.rs:Shape-[contains]->src/models.rs:Shape.area",
+                "src/models.rs:Shape-[contains]->src/models.rs:Shape.name",
+                "src/models.rs:User-[contains]->src/models.rs:User.Shape.area",
+                "src/models.rs:User-[contains]->src/models.rs:User.greet",
+                "src/models.rs:User-[contains]->src/models.rs:User.new",
+            ],
+        );
+
+        // The attributes between the doc comment and the struct are skipped.
+        assert_eq!(
+            nodes["src/models.rs:User"].doc_comment,
+            "A registered user."
+        );
+        assert_eq!(
+            nodes["src/models.rs:User.greet"].skeleton_code,
+            "pub fn greet(&self) -> String {\n...\n}"
+        );
+
+        // The imported module file is ignored, so there is no dangling import edge.
+        let config = ParserConfig::default().ignore_patterns(vec!["models.rs".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        parser.parse(&dir_path, None).unwrap();
+        let edges = parser.resolve_pending_edges(None).unwrap();
+        assert!(!edges
+            .iter()
+            .any(|edge| matches!(edge.r#type, EdgeType::Imports)));
+        assert_eq!(
+            parser
+                .unresolved_imports()
+                .iter()
+                .map(|imp| (imp.file.as_str(), imp.source_path.as_str()))
+                .collect::<Vec<_>>(),
+            [("src/main.rs", "src/models.rs")]
+        );
+    }
+
+    #[test]
+    fn test_parse_rust_impls_and_modules() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("rust")
+            .join("impls");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = edges
+            .iter()
+            .filter(|r| r.from.name.contains(".rs"))
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        // The methods of the trait impls are named after the traits, and the remaining duplicates are numbered.
+        // The methods of the types defined in other files are contained by the files, and the functions of the
+        // inline modules are contained by the modules (i.e. namespaces).
+        assert_eq!(
+            edge_strings,
+            [
+                "src/lib.rs-[contains]->src/lib.rs:Point",
+                "src/lib.rs-[contains]->src/lib.rs:utils",
+                "src/lib.rs:Point-[contains]->src/lib.rs:Point.Debug.fmt",
+                "src/lib.rs:Point-[contains]->src/lib.rs:Point.Display.fmt",
+                "src/lib.rs:Point-[contains]->src/lib.rs:Point.From.from",
+                "src/lib.rs:Point-[contains]->src/lib.rs:Point.From.from#2",
+                "src/lib.rs:utils-[contains]->src/lib.rs:utils.helper",
+                "src/lib.rs:utils-[contains]->src/lib.rs:utils.nested",
+                "src/lib.rs:utils.nested-[contains]->src/lib.rs:utils.nested.deep",
+                "src/models.rs-[contains]->src/models.rs:User",
+                "src/user_impl.rs-[contains]->src/user_impl.rs:User.new",
+            ],
+        );
+        assert_eq!(nodes["src/lib.rs:utils"].r#type, NodeType::Namespace);
+        assert_eq!(
+            nodes["src/lib.rs:utils"].skeleton_code,
+            "pub mod utils { ... }"
+        );
+        assert_eq!(
+            nodes["src/lib.rs:Point.From.from#2"].code,
+            "fn from(x: u8) -> Self {\n        Point { x: x.into() }\n    }"
+        );
+    }
+
+    #[test]
+    fn test_parse_file_summary() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        assert_eq!(
+            nodes["types.ts"].summary,
+            FileSummary {
+                function_count: 3, // constructor, getUser and filterUsers of UserService
+                class_count: 1,
+                import_count: 0, // only third-party modules are imported
+            }
+        );
+        assert_eq!(
+            nodes["main.ts"].summary,
+            FileSummary {
+                function_count: 2,
+                class_count: 0,
+                import_count: 5,
+            }
+        );
+
+        // The summary is computed when parsing a single file as well.
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path.join("types.ts"), None).unwrap();
+        assert_eq!(nodes["types.ts"].summary.function_count, 3);
+        assert_eq!(nodes["types.ts"].summary.class_count, 1);
+    }
+
     #[test]
     fn test_parse_typescript() {
         init();
@@ -749,6 +2905,1161 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_go_interface_methods() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("shapes");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let mut node_strings: Vec<_> = nodes
+            .values()
+            .filter(|n| n.name.starts_with("shapes.go:Shape"))
+            .map(|n| {
+                format!(
+                    "{}:{}:{}\n{}",
+                    n.name, n.start_line, n.end_line, n.skeleton_code
+                )
+            })
+            .collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            [
+                "shapes.go:Shape.Area:6:6\nArea() float64",
+                "shapes.go:Shape.Perimeter:7:7\nPerimeter() float64",
+                "shapes.go:Shape.Scale:8:8\nScale(factor float64) Shape",
+                "shapes.go:Shape:5:9\n",
+            ],
+        );
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .filter(|r| r.from.name == "shapes.go:Shape")
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "shapes.go:Shape-[contains]->shapes.go:Shape.Area",
+                "shapes.go:Shape-[contains]->shapes.go:Shape.Perimeter",
+                "shapes.go:Shape-[contains]->shapes.go:Shape.Scale",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_namespaces() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-namespace");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let mut node_strings: Vec<_> = nodes
+            .values()
+            .map(|n| format!("{}({})", n.name, n.r#type))
+            .collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            [
+                "(Directory)",
+                "geo.ts(File)",
+                "geo.ts:Geo(Namespace)",
+                "geo.ts:Geo.Point(Class)",
+                "geo.ts:Geo.Point.constructor(Function)",
+                "geo.ts:Geo.Point.distanceTo(Function)",
+                "geo.ts:Geo.Shape(Interface)",
+                "geo.ts:Geo.Units(Namespace)",
+                "geo.ts:Geo.Units.Length(OtherType)",
+                "geo.ts:distance(Function)",
+            ],
+        );
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "-[contains]->geo.ts",
+                "geo.ts-[contains]->geo.ts:Geo",
+                "geo.ts-[contains]->geo.ts:distance",
+                "geo.ts:Geo-[contains]->geo.ts:Geo.Point",
+                "geo.ts:Geo-[contains]->geo.ts:Geo.Shape",
+                "geo.ts:Geo-[contains]->geo.ts:Geo.Units",
+                "geo.ts:Geo.Point-[contains]->geo.ts:Geo.Point.constructor",
+                "geo.ts:Geo.Point-[contains]->geo.ts:Geo.Point.distanceTo",
+                "geo.ts:Geo.Units-[contains]->geo.ts:Geo.Units.Length",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_generated_files() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("generated");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        assert!(nodes["user_string.go"].generated);
+        assert!(!nodes["main.go"].generated);
+        assert!(nodes.contains_key("user_string.go:statusNames"));
+
+        // The definitions in the generated file are excluded, while the file node is kept.
+        let config = ParserConfig::default().exclude_generated_files(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        let mut node_names: Vec<_> = nodes.keys().cloned().collect();
+        node_names.sort();
+        assert_eq!(
+            node_names,
+            [
+                "",
+                "main.go",
+                "main.go:Status",
+                "main.go:User",
+                "main.go:main",
+                "user_string.go"
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_config_files() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("config");
+
+        // Config files are not parsed by default.
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        let node_strings: Vec<_> = nodes.values().map(|n| n.name.clone()).collect();
+        assert_eq!(node_strings, [""]);
+
+        let config = ParserConfig::default().parse_config_files(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let mut node_strings: Vec<_> = nodes
+            .values()
+            .filter(|n| n.r#type == NodeType::OtherType)
+            .map(|n| format!("{}:{}:{}", n.name, n.start_line, n.end_line))
+            .collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            [
+                "app.yaml:database:8:10",
+                "app.yaml:features:12:14",
+                "app.yaml:name:1:1",
+                "app.yaml:server:4:6",
+                "app.yaml:version:2:2",
+                "package.json:dependencies:6:8",
+                "package.json:name:1:1",
+                "package.json:scripts:2:5",
+            ],
+        );
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .filter(|r| r.from.name == "app.yaml")
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "app.yaml-[contains]->app.yaml:database",
+                "app.yaml-[contains]->app.yaml:features",
+                "app.yaml-[contains]->app.yaml:name",
+                "app.yaml-[contains]->app.yaml:server",
+                "app.yaml-[contains]->app.yaml:version",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_go_calls() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("calltree");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:a-[calls]->main.go:b",
+                "main.go:b-[calls]->main.go:a",
+                "main.go:main-[calls]->main.go:a",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_normalize_line_endings() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("crlf");
+
+        // The code is stored verbatim by default.
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+        assert!(nodes["main.ts:hello"].code.contains("\r\n"));
+
+        let config = ParserConfig::default().normalize_line_endings(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        let greeter = &nodes["main.ts:Greeter"];
+        assert_eq!(
+            greeter.code,
+            "interface Greeter {\n    greet(name: string): string;\n}",
+        );
+        assert_eq!((greeter.start_line, greeter.end_line), (0, 2));
+
+        let hello = &nodes["main.ts:hello"];
+        assert_eq!(
+            hello.code,
+            "function hello(name: string): string {\n    const greeting = \"Hello\";\n    return `${greeting}, ${name}!`;\n}",
+        );
+        assert_eq!(
+            hello.skeleton_code,
+            "function hello(name: string): string { ... }"
+        );
+        assert_eq!((hello.start_line, hello.end_line), (4, 7));
+    }
+
+    #[test]
+    fn test_parse_doc_comments() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir).join("examples").join("docs");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        let doc_comment = |name: &str| nodes[name].doc_comment.as_str();
+        assert_eq!(
+            doc_comment("math.ts:gcd"),
+            "Computes the greatest common divisor of two integers\nusing the Euclidean algorithm.",
+        );
+        assert_eq!(
+            doc_comment("math.ts:lcm"),
+            "Returns the least common multiple of two integers.",
+        );
+        assert_eq!(doc_comment("math.ts:square"), "");
+        assert_eq!(doc_comment("math.ts:Vector"), "A 2D vector.");
+        assert_eq!(
+            doc_comment("math.ts:Vector.length"),
+            "Returns the Euclidean length of the vector.",
+        );
+        assert_eq!(
+            doc_comment("shapes.py:Circle"),
+            "A circle with the given radius.",
+        );
+        assert_eq!(doc_comment("shapes.py:Square"), "");
+    }
+
+    #[test]
+    fn test_parse_exported_definitions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let examples_path = PathBuf::from(manifest_dir).join("examples");
+
+        let exported = |dir_path: PathBuf, node_names: &[&str]| -> Vec<bool> {
+            let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+            let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+            node_names
+                .iter()
+                .map(|name| nodes[*name].exported)
+                .collect()
+        };
+
+        assert_eq!(
+            exported(
+                examples_path.join("go-unused"),
+                &["main.go:Greet", "main.go:greet", "main.go:init#1"],
+            ),
+            [true, false, false],
+        );
+        assert_eq!(
+            exported(
+                examples_path.join("typescript-unused"),
+                &[
+                    "types.ts:User",
+                    "types.ts:unusedHelper",
+                    "types.ts:localHelper"
+                ],
+            ),
+            [true, true, false],
+        );
+        assert_eq!(
+            exported(
+                examples_path.join("python-unused"),
+                &["main.py:greet", "main.py:_format"],
+            ),
+            [true, false],
+        );
+        assert_eq!(
+            exported(
+                examples_path.join("rust").join("demo"),
+                &[
+                    "src/models.rs:User",
+                    "src/models.rs:User.new",
+                    "src/main.rs:describe"
+                ],
+            ),
+            [true, true, false],
+        );
+    }
+
+    #[test]
+    fn test_parse_skeleton_body_lines() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let go_dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let ts_dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+
+        let skeleton_code = |dir_path: &PathBuf, body_lines: usize, node_name: &str| -> String {
+            let config = ParserConfig::default().skeleton_body_lines(body_lines);
+            let mut parser = Parser::new(dir_path.clone(), config);
+            let (nodes, _) = parser.parse(dir_path, None).unwrap();
+            nodes[node_name].skeleton_code.clone()
+        };
+
+        assert_eq!(
+            skeleton_code(&go_dir_path, 0, "main.go:main"),
+            "func main() {\n...\n}",
+        );
+        assert_eq!(
+            skeleton_code(&go_dir_path, 1, "main.go:main"),
+            "func main() {\n\tuser := NewUser(1, \"gopher\", \"gopher@example.com\")\n...\n}",
+        );
+        assert_eq!(
+            skeleton_code(&ts_dir_path, 1, "main.ts:fetchUserData"),
+            "async function fetchUserData(userId: UserID, svc: UserService): Promise<User | null> {\n    try {\n...\n}",
+        );
+        // The body is kept as is if it is not longer than the retained lines.
+        assert_eq!(
+            skeleton_code(&ts_dir_path, 1, "main.ts:greetUser"),
+            "function greetUser(user: User): string {\n    return `Hello, ${user.name}!`;\n}",
+        );
+    }
+
+    #[test]
+    fn test_derive_skeleton_code() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let go_dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+        let ts_dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript");
+
+        for dir_path in [go_dir_path, ts_dir_path] {
+            for body_lines in [0, 1] {
+                let config = ParserConfig::default().skeleton_body_lines(body_lines);
+                let mut parser = Parser::new(dir_path.clone(), config);
+                let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+                let mut derived_count = 0;
+                for node in nodes.values() {
+                    if let Some(skeleton_code) = parser.derive_skeleton_code(node) {
+                        assert_eq!(skeleton_code, node.skeleton_code, "{}", node.name);
+                        derived_count += 1;
+                    }
+                }
+                assert!(derived_count > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_go_init_functions() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("init");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        // Each `init` function is captured distinctly, numbered in source order within its file.
+        let init_nodes: Vec<_> = nodes
+            .values()
+            .filter(|node| node.name.contains(":init#"))
+            .map(|node| (node.name.as_str(), node.code.as_str()))
+            .collect();
+        assert_eq!(init_nodes.len(), 3);
+        assert!(init_nodes.contains(&(
+            "main.go:init#1",
+            "func init() {\n\tregistry[\"a\"] = \"alpha\"\n}"
+        )));
+        assert!(init_nodes.contains(&(
+            "main.go:init#2",
+            "func init() {\n\tregistry[\"b\"] = \"beta\"\n}"
+        )));
+        assert!(init_nodes.contains(&("config.go:init#1", "func init() {\n\tdebug = true\n}")));
+        assert!(!nodes.contains_key("main.go:init"));
+    }
+
+    #[test]
+    fn test_parse_go_method_calls() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("receiver");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        // The receiver types are recorded for both pointer and value receivers, but not for functions.
+        let receiver = |name: &str| nodes[name].receiver.as_str();
+        assert_eq!(receiver("user.go:User.SetAddress"), "User");
+        assert_eq!(receiver("user.go:User.Greeting"), "User");
+        assert_eq!(receiver("user.go:User.NewUser"), "");
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .filter(|r| matches!(r.r#type, EdgeType::Calls))
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        // Method calls are resolved by the declared types of the receiver variables (i.e. parameters,
+        // variables initialized by constructors and variables with explicit types).
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:main-[calls]->main.go:register",
+                "main.go:main-[calls]->user.go:User.Greeting",
+                "main.go:main-[calls]->user.go:User.NewUser",
+                "main.go:main-[calls]->user.go:User.SetAddress",
+                "main.go:register-[calls]->main.go:SetAddress",
+                "main.go:register-[calls]->user.go:User.SetAddress",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_go_imported_package_calls() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("crosscall");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .filter(|r| matches!(r.r#type, EdgeType::Calls))
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        // The calls to the standard library (e.g. `fmt.Println`) are dropped.
+        assert_eq!(
+            edge_strings,
+            [
+                "main.go:main-[calls]->main.go:greet",
+                "main.go:main-[calls]->models/user.go:User.NewUser",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_go_type_parameter_constraints() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("generics");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let type_names = |func_name: &str| -> Vec<String> {
+            let mut type_names: Vec<_> = parser.func_param_types[&Language::Go][func_name]
+                .iter()
+                .map(|t| t.type_name.clone())
+                .collect();
+            type_names.sort();
+            type_names
+        };
+        // The type parameters themselves (e.g. `T`) are kept as is, since they never match any type node.
+        assert_eq!(type_names("main.go:Max"), ["Ordered", "T"]);
+        assert_eq!(type_names("main.go:Sum"), ["Number", "V"]);
+    }
+
+    #[test]
+    fn test_parse_go_struct_field_types() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("fields");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        // The builtin types and the inline struct types are skipped, and the duplicate types are merged.
+        let field_type_strings: Vec<_> = parser.func_param_types[&Language::Go]["models.go:User"]
+            .iter()
+            .map(|t| format!("{:?}:{}", t.package_name, t.type_name))
+            .collect();
+        assert_eq!(
+            field_type_strings,
+            [r#"Some("."):Address"#, r#"Some("."):Tag"#, "None:Time",],
+        );
+        assert!(!parser.func_param_types[&Language::Go].contains_key("models.go:Tag"));
+    }
+
+    #[test]
+    fn test_parse_go_embedding_inherits() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("embedding");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+        let edges = parser.resolve_pending_edges(None).unwrap();
+
+        let mut inherits_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Inherits))
+            .map(|edge| format!("{}-[{}]->{}", edge.from.name, edge.r#type, edge.to.name))
+            .collect();
+        inherits_edges.sort();
+        // `Audit` is embedded from the imported package, while the others are defined locally.
+        assert_eq!(
+            inherits_edges,
+            vec![
+                "types.go:Admin-[inherits]->models/audit.go:Audit",
+                "types.go:Admin-[inherits]->types.go:User",
+                "types.go:ReadWriter-[inherits]->types.go:Reader",
+                "types.go:ReadWriter-[inherits]->types.go:Writer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_implements_via_embedding() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("implements");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+        let edges = parser.resolve_pending_edges(None).unwrap();
+
+        let mut inherits_edges: Vec<String> = edges
+            .iter()
+            .filter(|edge| matches!(edge.r#type, EdgeType::Inherits))
+            .map(|edge| format!("{}-[{}]->{}", edge.from.name, edge.r#type, edge.to.name))
+            .collect();
+        inherits_edges.sort();
+        // `LoggedFile` only implements `Closer` (and `ReadCloser`) via the method promoted from `Base`, while `File`
+        // does via the method declared in another file.
+        assert_eq!(
+            inherits_edges,
+            vec![
+                "types.go:Base-[inherits]->types.go:Closer",
+                "types.go:File-[inherits]->types.go:Closer",
+                "types.go:File-[inherits]->types.go:ReadCloser",
+                "types.go:File-[inherits]->types.go:Reader",
+                "types.go:LoggedFile-[inherits]->types.go:Base",
+                "types.go:LoggedFile-[inherits]->types.go:Closer",
+                "types.go:LoggedFile-[inherits]->types.go:ReadCloser",
+                "types.go:LoggedFile-[inherits]->types.go:Reader",
+                "types.go:ReadCloser-[inherits]->types.go:Closer",
+                "types.go:ReadCloser-[inherits]->types.go:Reader",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_aliased_import_param_types() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("alias");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        // `m` is an alias of "models", while `models` is an alias of "legacy/models".
+        let param_type_strings: Vec<_> = parser.func_param_types[&Language::Go]["main.go:Save"]
+            .iter()
+            .map(|t| format!("{:?}:{}", t.package_name, t.type_name))
+            .collect();
+        assert_eq!(
+            param_type_strings,
+            [r#"Some("models"):User"#, r#"Some("legacy/models"):Item"#],
+        );
+    }
+
+    #[test]
+    fn test_parse_go_import_groups_param_types() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("importgroups");
+
+        // The alias `models` (of "legacy/models") wins over the package name `models` (of "models"),
+        // although the latter is imported first.
+        for _ in 0..3 {
+            let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+            parser.parse(&dir_path, None).unwrap();
+
+            let param_type_strings: Vec<_> = parser.func_param_types[&Language::Go]["main.go:Save"]
+                .iter()
+                .map(|t| format!("{:?}:{}", t.package_name, t.type_name))
+                .collect();
+            assert_eq!(
+                param_type_strings,
+                [r#"Some("legacy/models"):Item"#, r#"Some("models"):User"#],
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_traverse_directory_with_symlink_loop() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir(dir_path.join("sub")).unwrap();
+        fs::write(dir_path.join("a.py"), "class A:\n    pass\n").unwrap();
+        fs::write(dir_path.join("sub").join("b.py"), "class B:\n    pass\n").unwrap();
+        // sub/loop => the root directory
+        std::os::unix::fs::symlink(&dir_path, dir_path.join("sub").join("loop")).unwrap();
+
+        let config = ParserConfig::default().follow_links(true);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        let mut node_strings: Vec<_> = nodes.values().map(|n| n.name.clone()).collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            ["", "a.py", "a.py:A", "sub", "sub/b.py", "sub/b.py:B"]
+        );
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "-[contains]->a.py",
+                "-[contains]->sub",
+                "a.py-[contains]->a.py:A",
+                "sub-[contains]->sub/b.py",
+                "sub/b.py-[contains]->sub/b.py:B",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_ignore_patterns_whitelist() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("go")
+            .join("demo");
+
+        // Everything is ignored except for `main.go`.
+        let config = ParserConfig::default().ignore_patterns(vec!["*".into(), "!main.go".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        let (nodes, _) = parser.parse(&dir_path, None).unwrap();
+
+        let mut file_names: Vec<_> = nodes
+            .values()
+            .filter(|n| matches!(n.r#type, NodeType::File | NodeType::Directory))
+            .map(|n| n.name.clone())
+            .collect();
+        file_names.sort();
+        assert_eq!(file_names, ["", "main.go"]);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let repo_path = PathBuf::from("/repo");
+        let path = repo_path.join("main.go");
+        let hash = |config: ParserConfig, content: &[u8]| {
+            Parser::new(repo_path.clone(), config).content_hash(&path, content)
+        };
+
+        let content = b"package main\n";
+        assert_eq!(
+            hash(ParserConfig::default(), content),
+            hash(ParserConfig::default().throttle_ms(10), content)
+        );
+        assert_ne!(
+            hash(ParserConfig::default(), content),
+            hash(ParserConfig::default(), b"package demo\n")
+        );
+        // The options that change the parsed nodes change the hash as well.
+        assert_ne!(
+            hash(ParserConfig::default(), content),
+            hash(ParserConfig::default().skeleton_body_lines(2), content)
+        );
+        assert_ne!(
+            hash(ParserConfig::default(), content),
+            hash(ParserConfig::default().skip_empty_files(true), content)
+        );
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(repo_path.join(".gitignore"), "generated/\n*.pb.go\n").unwrap();
+        fs::create_dir_all(repo_path.join("api")).unwrap();
+        fs::write(repo_path.join("api").join(".gitignore"), "!keep.pb.go\n").unwrap();
+
+        let config = ParserConfig::default().ignore_patterns(vec!["vendor".into()]);
+        let parser = Parser::new(repo_path.clone(), config);
+        let is_ignored = |path: &str| parser.is_ignored(&repo_path.join(path), false);
+
+        assert!(!is_ignored("main.go"));
+        // By the ignore patterns, via the parent directory
+        assert!(is_ignored("vendor/lib/lib.go"));
+        // By `.gitignore`, where the closest one takes precedence
+        assert!(is_ignored("generated/gen.go"));
+        assert!(is_ignored("api/user.pb.go"));
+        assert!(!is_ignored("api/keep.pb.go"));
+        // Hidden files and directories
+        assert!(is_ignored(".cache/main.go"));
+
+        // `.gitignore` files are not honoured if disabled.
+        let config = ParserConfig::default().use_gitignore_files(false);
+        let parser = Parser::new(repo_path.clone(), config);
+        assert!(!parser.is_ignored(&repo_path.join("generated/gen.go"), false));
+    }
+
+    #[test]
+    fn test_parse_ignore_patterns_glob() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let src_path = repo_path.join("src");
+        fs::create_dir_all(src_path.join("user")).unwrap();
+        for file_path in [
+            repo_path.join("setup.test.ts"),
+            repo_path.join("legacy.ts"),
+            src_path.join("legacy.ts"),
+            src_path.join("user").join("user.ts"),
+            src_path.join("user").join("user.test.ts"),
+        ] {
+            fs::write(file_path, "export const x = 1;\n").unwrap();
+        }
+
+        // Test files are excluded at any depth, while `/legacy.ts` is anchored to the repository.
+        let config = ParserConfig::default()
+            .ignore_patterns(vec!["**/*.test.ts".into(), "/legacy.ts".into()]);
+        let file_names = |dir_path: &PathBuf| {
+            let mut parser = Parser::new(repo_path.clone(), config.clone());
+            let (nodes, _) = parser.parse(dir_path, None).unwrap();
+            let mut file_names: Vec<_> = nodes
+                .values()
+                .filter(|n| n.r#type == NodeType::File)
+                .map(|n| n.name.clone())
+                .collect();
+            file_names.sort();
+            file_names
+        };
+        assert_eq!(
+            file_names(&repo_path),
+            ["src/legacy.ts", "src/user/user.ts"]
+        );
+
+        // The patterns are still relative to the repository when traversing a subdirectory.
+        assert_eq!(file_names(&src_path), ["src/legacy.ts", "src/user/user.ts"]);
+    }
+
+    #[test]
+    fn test_traverse_subdirectory() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let dir_path = repo_path.join("pkg").join("sub");
+        fs::create_dir_all(dir_path.join("inner")).unwrap();
+        fs::write(repo_path.join("a.py"), "class A:\n    pass\n").unwrap();
+        fs::write(dir_path.join("b.py"), "class B:\n    pass\n").unwrap();
+        fs::write(dir_path.join("inner").join("c.py"), "class C:\n    pass\n").unwrap();
+
+        let mut parser = Parser::new(repo_path.clone(), ParserConfig::default());
+        let (nodes, edges) = parser.parse(&dir_path, None).unwrap();
+
+        // Only the subtree is parsed, whose nodes are named relative to the repository.
+        let mut node_strings: Vec<_> = nodes.values().map(|n| n.name.clone()).collect();
+        node_strings.sort();
+        assert_eq!(
+            node_strings,
+            [
+                "",
+                "pkg",
+                "pkg/sub",
+                "pkg/sub/b.py",
+                "pkg/sub/b.py:B",
+                "pkg/sub/inner",
+                "pkg/sub/inner/c.py",
+                "pkg/sub/inner/c.py:C",
+            ]
+        );
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "-[contains]->pkg",
+                "pkg-[contains]->pkg/sub",
+                "pkg/sub-[contains]->pkg/sub/b.py",
+                "pkg/sub-[contains]->pkg/sub/inner",
+                "pkg/sub/b.py-[contains]->pkg/sub/b.py:B",
+                "pkg/sub/inner-[contains]->pkg/sub/inner/c.py",
+                "pkg/sub/inner/c.py-[contains]->pkg/sub/inner/c.py:C",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_workspace_package_imports() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-monorepo");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+
+        // `@myorg/models` is resolved via `exports`, and `@myorg/utils` via `types`,
+        // while `lodash` is not in the repository.
+        assert_eq!(
+            edge_strings,
+            [
+                "packages/app/src/main.ts-[imports]->packages/models/src/index.ts:User",
+                "packages/app/src/main.ts-[imports]->packages/utils/src/index.ts:formatName",
+                "packages/app/src/main.ts:greet-[calls]->packages/utils/src/index.ts:formatName",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_case_insensitive_imports() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-case");
+
+        let import_edges = |case_insensitive_imports: bool| -> Vec<String> {
+            let config = ParserConfig::default().case_insensitive_imports(case_insensitive_imports);
+            let mut parser = Parser::new(dir_path.clone(), config);
+            parser.parse(&dir_path, None).unwrap();
+
+            let mut edge_strings: Vec<_> = parser
+                .resolve_pending_edges(None)
+                .unwrap()
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+
+        // Both `./Types` and `./TYPES.ts` refer to `types.ts`.
+        assert_eq!(
+            import_edges(true),
+            [
+                "main.ts-[imports]->types.ts",
+                "main.ts-[imports]->types.ts:User",
+            ],
+        );
+        assert!(import_edges(false).is_empty());
+    }
+
+    #[test]
+    fn test_parse_typescript_require_imports() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-commonjs");
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+
+        let mut edge_strings: Vec<_> = parser
+            .resolve_pending_edges(None)
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+
+        // `const X = require(...)` and `import X = require(...)` import the whole module, while the
+        // destructured requires import the symbols.
+        assert_eq!(
+            edge_strings,
+            [
+                "legacy.ts-[imports]->types.ts",
+                "main.ts-[imports]->types.ts",
+                "main.ts-[imports]->types.ts:createUser",
+                "main.ts:newAdmin-[calls]->types.ts:createUser",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_reference_scope() {
+        init();
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let dir_path = PathBuf::from(manifest_dir)
+            .join("examples")
+            .join("typescript-scope");
+
+        let reference_edges = |config: ParserConfig| -> Vec<String> {
+            let mut parser = Parser::new(dir_path.clone(), config);
+            parser.parse(&dir_path, None).unwrap();
+
+            let mut db = Database::new(PathBuf::from(""));
+            let mut edge_strings: Vec<_> = parser
+                .resolve_pending_edges(Some(&mut db))
+                .unwrap()
+                .into_iter()
+                .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+                .collect();
+            edge_strings.sort();
+            edge_strings
+        };
+
+        // The global type `AppConfig` is referenced without an explicit import.
+        assert_eq!(
+            reference_edges(ParserConfig::default()),
+            ["main.ts:start-[references]->main.ts:Logger"],
+        );
+        assert_eq!(
+            reference_edges(ParserConfig::default().reference_scope(ReferenceScope::Package)),
+            ["main.ts:start-[references]->main.ts:Logger"],
+        );
+        assert_eq!(
+            reference_edges(ParserConfig::default().reference_scope(ReferenceScope::Repo)),
+            [
+                "main.ts:start-[references]->main.ts:Logger",
+                "main.ts:start-[references]->types/globals.d.ts:AppConfig",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_unresolved_imports() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            dir_path.join("types.ts"),
+            "export interface User {\n  name: string;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir_path.join("main.ts"),
+            "import { User } from './types';\nimport * as utils from './utils';\n",
+        )
+        .unwrap();
+        fs::write(dir_path.join("utils.ts"), "export const x = 1;\n").unwrap();
+
+        let config = ParserConfig::default().ignore_patterns(vec!["types.ts".into()]);
+        let mut parser = Parser::new(dir_path.clone(), config);
+        parser.parse(&dir_path, None).unwrap();
+
+        assert_eq!(
+            parser.unresolved_imports(),
+            [UnresolvedImport {
+                file: "main.ts".to_string(),
+                source_path: "types.ts".to_string(),
+                symbol: Some("User".to_string()),
+            }],
+        );
+    }
+
+    #[test]
+    fn test_parse_typescript_explicit_js_extension_imports() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            dir_path.join("types.ts"),
+            "export interface User {\n  name: string;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir_path.join("main.ts"),
+            "import { User } from './types.js';\n",
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        parser.parse(&dir_path, None).unwrap();
+        assert_eq!(parser.unresolved_imports(), []);
+
+        let mut db = Database::new(PathBuf::from(""));
+        let edge_strings: Vec<_> = parser
+            .resolve_pending_edges(Some(&mut db))
+            .unwrap()
+            .into_iter()
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        assert_eq!(edge_strings, ["main.ts-[imports]->types.ts:User"]);
+    }
+
+    #[test]
+    fn test_parse_typescript_builtin_generic_param_type() {
+        init();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            dir_path.join("users.ts"),
+            r#"
+export interface User {
+  name: string;
+}
+
+export function groupUsers(users: Record<string, User>): void {
+  console.log(users);
+}
+"#,
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(dir_path.clone(), ParserConfig::default());
+        let (_, edges) = parser.parse(&dir_path, None).unwrap();
+        let mut db = Database::new(PathBuf::from(""));
+        let resolved_edges = parser.resolve_pending_edges(Some(&mut db)).unwrap();
+
+        let mut edge_strings: Vec<_> = edges
+            .into_iter()
+            .chain(resolved_edges)
+            .map(|r| format!("{}-[{}]->{}", r.from.name, r.r#type, r.to.name))
+            .collect();
+        edge_strings.sort();
+        assert_eq!(
+            edge_strings,
+            [
+                "-[contains]->users.ts",
+                "users.ts-[contains]->users.ts:User",
+                "users.ts-[contains]->users.ts:groupUsers",
+                "users.ts:groupUsers-[references]->users.ts:User",
+            ],
+        );
+    }
+
     /*
     #[test]
     fn test_traverse_directory_with_gitignore() {
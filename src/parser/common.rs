@@ -1,4 +1,8 @@
+use crate::util;
+use crate::{Database, FuncParamType, ReferenceScope};
 use crate::{Edge, EdgeType, Language, Node, NodeType};
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::path::PathBuf;
 use tree_sitter;
@@ -13,6 +17,8 @@ pub struct PendingImport {
     // - TypeScript: Some<"export default"> if the default export is imported
     pub symbol: Option<String>,
     pub alias: Option<String>,
+    // TypeScript: true if the symbol is re-exported rather than imported, e.g. `export { X as Y } from 'Z'`
+    pub reexport: bool,
 }
 
 impl PendingImport {
@@ -25,6 +31,70 @@ impl PendingImport {
             unreachable!()
         }
     }
+
+    /// Returns the name of the imported node, e.g. `types.ts:User`, or `types.ts` if the entire module is imported.
+    pub fn imported_node_name(&self) -> String {
+        if let Some(symbol) = &self.symbol {
+            format!("{}:{}", self.source_path, symbol)
+        } else {
+            self.source_path.clone()
+        }
+    }
+
+    /// Encodes the import as `<imported node name>[ as <alias>]`, e.g. `types.ts:User as U`.
+    ///
+    /// This is how unresolved imports are persisted on the file nodes.
+    pub fn encode(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{} as {}", self.imported_node_name(), alias),
+            None => self.imported_node_name(),
+        }
+    }
+
+    /// Decodes an import encoded by [`PendingImport::encode`].
+    pub fn decode(language: Language, encoded: &str) -> Self {
+        let (name, alias) = match encoded.rsplit_once(" as ") {
+            Some((name, alias)) => (name, Some(alias.to_string())),
+            None => (encoded, None),
+        };
+        let (source_path, symbol) = match name.rsplit_once(':') {
+            Some((source_path, symbol)) => (source_path, Some(symbol.to_string())),
+            None => (name, None),
+        };
+        Self {
+            language,
+            source_path: source_path.to_string(),
+            symbol,
+            alias,
+            reexport: false,
+        }
+    }
+}
+
+/// A pending call relationship that needs to be resolved as an edge.
+#[derive(Debug, Clone)]
+pub struct PendingCall {
+    // The name of the calling function node
+    pub caller: String,
+    // The called function expression, e.g. `foo`, `u.SetAddress` or `fmt.Println`
+    pub callee: String,
+    // The declared type of the receiver variable of a method call, e.g. `User` for `u.SetAddress` where `u *User`,
+    // or the called constructor if the variable is initialized by a function call, e.g. `NewUser()`
+    pub receiver_type: Option<String>,
+    // The package (i.e. directory) of the called function if it is qualified by an imported package, e.g. `models`
+    // for `models.NewUser` where `import "example.com/app/models"`
+    pub package: Option<String>,
+    // Whether the callee is referenced rather than called, e.g. the component `UserCard` in `<UserCard />`
+    pub reference: bool,
+}
+
+/// A pending inheritance relationship (e.g. Go struct embedding) that needs to be resolved as an edge.
+#[derive(Debug, Clone)]
+pub struct PendingInherit {
+    // The name of the inheriting type node, e.g. `types.go:Admin`
+    pub from: String,
+    // The embedded (or extended) type, e.g. `User` in `type Admin struct { User }`
+    pub base: FuncParamType,
 }
 
 pub fn parse_simple_interface(
@@ -55,7 +125,7 @@ pub fn parse_simple_interface(
                     start_line: capture.node.start_position().row,
                     end_line: capture.node.end_position().row,
                     code: capture_node_text,
-                    skeleton_code: String::new(),
+                    ..Default::default()
                 });
             }
             "definition.interface.name" => {
@@ -105,7 +175,7 @@ pub fn parse_simple_class(
                     start_line: capture.node.start_position().row,
                     end_line: capture.node.end_position().row,
                     code: capture_node_text,
-                    skeleton_code: String::new(),
+                    ..Default::default()
                 });
             }
             "definition.class.name" => {
@@ -155,7 +225,7 @@ pub fn parse_simple_enum(
                     start_line: capture.node.start_position().row,
                     end_line: capture.node.end_position().row,
                     code: capture_node_text,
-                    skeleton_code: String::new(),
+                    ..Default::default()
                 });
             }
             "definition.enum.name" => {
@@ -205,7 +275,7 @@ pub fn parse_simple_type_alias(
                     start_line: capture.node.start_position().row,
                     end_line: capture.node.end_position().row,
                     code: capture_node_text,
-                    skeleton_code: String::new(),
+                    ..Default::default()
                 });
             }
             "definition.type_alias.name" => {
@@ -240,3 +310,505 @@ pub fn log_capture(
         capture.node.to_sexp()
     );
 }
+
+/// Returns the skeleton code of a definition starting at `start_byte`, i.e. its signature followed by `marker` instead
+/// of the `body`.
+///
+/// If `body_lines` is non-zero, the leading lines of the body are kept before the `...` marker, e.g. with one line:
+///
+/// ```text
+/// func main() {
+///     fmt.Println("hello")
+/// ...
+/// }
+/// ```
+pub fn skeleton_code(
+    source_code: &[u8],
+    start_byte: usize,
+    body: tree_sitter::Node,
+    body_lines: usize,
+    marker: &str,
+) -> String {
+    let signature = String::from_utf8_lossy(&source_code[start_byte..body.start_byte()]);
+    if body_lines == 0 {
+        return signature.to_string() + marker;
+    }
+
+    let body_code = String::from_utf8_lossy(&source_code[body.start_byte()..body.end_byte()]);
+    let lines: Vec<&str> = body_code.lines().collect();
+    // The first line is the opening brace, and the last line is the closing brace.
+    if lines.len() <= body_lines + 2 {
+        return signature.to_string() + &body_code;
+    }
+    format!(
+        "{}{}\n...\n{}",
+        signature,
+        lines[..=body_lines].join("\n"),
+        lines[lines.len() - 1]
+    )
+}
+
+/// Re-derives the skeleton code from the code of a definition, which is parsed on its own after `prefix`.
+///
+/// The body is the shallowest node of one of the `body_kinds` that is the `body` field of its parent and is
+/// located after `prefix`. Returns None if no such body is found.
+pub fn derive_skeleton_code(
+    language: &tree_sitter::Language,
+    prefix: &str,
+    code: &str,
+    body_kinds: &[&str],
+    body_lines: usize,
+    marker: &str,
+) -> Option<String> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(language)
+        .expect("Error loading language parser");
+
+    let source_code = format!("{}{}", prefix, code);
+    let tree = parser.parse(&source_code, None)?;
+
+    // Search level by level, so that nested bodies (e.g. of closures in default parameters) are skipped.
+    let mut queue = std::collections::VecDeque::from([tree.root_node()]);
+    while let Some(node) = queue.pop_front() {
+        let is_body = node.start_byte() >= prefix.len()
+            && body_kinds.contains(&node.kind())
+            && node
+                .parent()
+                .and_then(|parent| parent.child_by_field_name("body"))
+                .is_some_and(|body| body.id() == node.id());
+        if is_body {
+            return Some(skeleton_code(
+                source_code.as_bytes(),
+                prefix.len(),
+                node,
+                body_lines,
+                marker,
+            ));
+        }
+        let mut cursor = node.walk();
+        queue.extend(node.children(&mut cursor));
+    }
+    None
+}
+
+/// Returns the doc comment of the given definition node without the comment markers, or an empty string if
+/// there is none.
+///
+/// For Python, this is the docstring of the definition; for the others, this is the block of comment lines
+/// right above the definition.
+pub fn doc_comment(node: &Node, source_code: &[u8]) -> String {
+    if node.language == Language::Python {
+        let Some((_, body)) = node.code.split_once('\n') else {
+            return String::new();
+        };
+        let body = body.trim_start();
+        for quote in ["\"\"\"", "'''"] {
+            if let Some(rest) = body.strip_prefix(quote) {
+                if let Some((docstring, _)) = rest.split_once(quote) {
+                    return join_comment_lines(docstring.lines());
+                }
+            }
+        }
+        return String::new();
+    }
+
+    let source_code = String::from_utf8_lossy(source_code);
+    let lines: Vec<&str> = source_code.lines().take(node.start_line).collect();
+    // The attributes of Rust items (e.g. `#[derive(Debug)]`) may sit between the doc comment and the item.
+    let is_attribute = |line: &str| node.language == Language::Rust && line.starts_with("#[");
+    let comment_lines: Vec<&str> = lines
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| {
+            line.starts_with("//")
+                || line.starts_with("/*")
+                || line.starts_with('*')
+                || line.ends_with("*/")
+                || is_attribute(line)
+        })
+        .filter(|line| !is_attribute(line))
+        .collect();
+    join_comment_lines(comment_lines.into_iter().rev().map(|line| {
+        let line = line.strip_suffix("*/").unwrap_or(line);
+        line.trim_start_matches('/').trim_start_matches('*')
+    }))
+}
+
+/// Trims the given lines and joins the non-empty ones with newlines.
+fn join_comment_lines<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    lines
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses the given source code with the grammar of the language, where the file name tells TSX from TypeScript.
+///
+/// Returns None for the languages without a tree-sitter grammar (e.g. YAML).
+fn parse_source(
+    language: &Language,
+    file_name: &str,
+    source_code: &[u8],
+) -> Option<tree_sitter::Tree> {
+    let language: tree_sitter::Language = match language {
+        Language::Go => tree_sitter_go::LANGUAGE.into(),
+        Language::TypeScript => super::typescript::grammar_for_path(Path::new(file_name)),
+        Language::Python => tree_sitter_python::LANGUAGE.into(),
+        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+        _ => return None,
+    };
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(source_code, None)
+}
+
+/// Returns the code of the given definition, where its own name (e.g. `sum` in `func sum(...)`) is replaced
+/// with `_`, so that renamed copies of the same code compare equal.
+///
+/// The name is located by tree-sitter, as the outermost `name` field with the short name of the definition.
+/// The code is returned as is if the name is not found, e.g. for a class method parsed out of its class.
+pub fn mask_definition_name(node: &Node) -> String {
+    // "main.go:sum" => sum, "main.go:User.Sum" => Sum
+    let short_name = node.name.rsplit([':', '.']).next().unwrap_or(&node.name);
+    let file_name = node.name.split(':').next().unwrap_or(&node.name);
+    let Some(tree) = parse_source(&node.language, file_name, node.code.as_bytes()) else {
+        return node.code.clone();
+    };
+
+    let mut stack = vec![tree.root_node()];
+    while let Some(ts_node) = stack.pop() {
+        if let Some(name) = ts_node.child_by_field_name("name") {
+            if name.utf8_text(node.code.as_bytes()) == Ok(short_name) {
+                return format!(
+                    "{}_{}",
+                    &node.code[..name.start_byte()],
+                    &node.code[name.end_byte()..]
+                );
+            }
+        }
+        // Visit the children in order, so that the outermost (and first) name wins.
+        let mut cursor = ts_node.walk();
+        let children: Vec<_> = ts_node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+
+    node.code.clone()
+}
+
+/// Sets whether the parsed definitions are exported, i.e. visible outside of their file (or package for Go):
+///
+/// - Go: the name is capitalized.
+/// - Python: the name does not start with an underscore.
+/// - TypeScript: the definition is declared in (or listed by) an `export` statement.
+/// - Rust: the definition has a visibility modifier, e.g. `pub` or `pub(crate)`.
+pub fn mark_exported_definitions(
+    language: &Language,
+    nodes: &mut IndexMap<String, Node>,
+    root_node: tree_sitter::Node,
+    source_code: &[u8],
+) {
+    // The start lines of the exported declarations, along with the names listed by `export { ... }`.
+    let mut exported_lines: HashSet<usize> = HashSet::new();
+    let mut exported_names: HashSet<String> = HashSet::new();
+    if matches!(language, Language::TypeScript | Language::Rust) {
+        let mut stack = vec![root_node];
+        while let Some(ts_node) = stack.pop() {
+            match (language, ts_node.kind()) {
+                (Language::TypeScript, "export_statement") => {
+                    if let Some(declaration) = ts_node.child_by_field_name("declaration") {
+                        exported_lines.insert(declaration.start_position().row);
+                    }
+                }
+                (Language::TypeScript, "export_specifier") => {
+                    if let Some(name) = ts_node
+                        .child_by_field_name("name")
+                        .and_then(|name| name.utf8_text(source_code).ok())
+                    {
+                        exported_names.insert(name.to_string());
+                    }
+                }
+                (Language::Rust, "visibility_modifier") => {
+                    if let Some(item) = ts_node.parent() {
+                        exported_lines.insert(item.start_position().row);
+                    }
+                }
+                _ => {}
+            }
+            let mut cursor = ts_node.walk();
+            stack.extend(ts_node.children(&mut cursor));
+        }
+    }
+
+    for node in nodes.values_mut() {
+        if !matches!(
+            node.r#type,
+            NodeType::Interface
+                | NodeType::Class
+                | NodeType::Function
+                | NodeType::OtherType
+                | NodeType::Namespace
+        ) {
+            continue;
+        }
+        // "main.go:User.Name" => Name
+        let short_name = node.name.rsplit([':', '.']).next().unwrap_or(&node.name);
+        node.exported = match language {
+            Language::Go => short_name.starts_with(|c: char| c.is_uppercase()),
+            Language::Python => !short_name.starts_with('_'),
+            Language::TypeScript => {
+                exported_lines.contains(&node.start_line) || exported_names.contains(short_name)
+            }
+            Language::Rust => exported_lines.contains(&node.start_line),
+            _ => false,
+        };
+    }
+}
+
+/// The tags of the comments captured by [`todo_comments`].
+const TODO_TAGS: [&str; 2] = ["TODO", "FIXME"];
+
+/// Returns the `TODO`/`FIXME` comments of the given file as `OtherType` nodes, along with the `Contains` edges
+/// from their innermost enclosing functions (or the file if there is none).
+///
+/// A comment node is named after its tag and (1-based) line number, e.g. `main.go:TODO@12`, where the code is
+/// the comment text without the comment markers, and the tag is kept in the `tag` metadata.
+///
+/// The comments are found by tree-sitter, so that comment markers within string literals (e.g. `"http://..."`)
+/// are not mistaken for comments.
+pub fn todo_comments(
+    file_node: &Node,
+    nodes: &IndexMap<String, Node>,
+    source_code: &[u8],
+) -> (IndexMap<String, Node>, Vec<Edge>) {
+    let mut todo_nodes: IndexMap<String, Node> = IndexMap::new();
+    let mut edges: Vec<Edge> = Vec::new();
+
+    let Some(tree) = parse_source(&file_node.language, &file_node.name, source_code) else {
+        return (todo_nodes, edges);
+    };
+
+    // The lines of all the comments, e.g. `// TODO: ...` and the lines of `/* ... */`.
+    let mut comment_lines: Vec<(usize, &str)> = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(ts_node) = stack.pop() {
+        if ts_node.kind().contains("comment") {
+            let text = ts_node.utf8_text(source_code).unwrap_or("");
+            let start_row = ts_node.start_position().row;
+            comment_lines.extend(
+                text.lines()
+                    .enumerate()
+                    .map(|(i, line)| (start_row + i, line)),
+            );
+            continue;
+        }
+        let mut cursor = ts_node.walk();
+        stack.extend(ts_node.children(&mut cursor));
+    }
+    comment_lines.sort_by_key(|(line_number, _)| *line_number);
+
+    // The line numbers of the Python nodes are 1-based, unlike the other languages.
+    let first_line = match file_node.language {
+        Language::Python => 1,
+        _ => 0,
+    };
+
+    for (row, line) in comment_lines {
+        let line_number = row + first_line;
+        let Some((tag, text)) = todo_comment(line) else {
+            continue;
+        };
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("tag".to_string(), tag.to_string());
+        let node = Node {
+            name: format!("{}:{}@{}", file_node.name, tag, row + 1),
+            r#type: NodeType::OtherType,
+            language: file_node.language.clone(),
+            start_line: line_number,
+            end_line: line_number,
+            code: text.clone(),
+            skeleton_code: text,
+            metadata,
+            ..Default::default()
+        };
+
+        let parent = nodes
+            .values()
+            .filter(|n| {
+                n.r#type == NodeType::Function
+                    && n.start_line <= line_number
+                    && line_number <= n.end_line
+            })
+            .max_by_key(|n| n.start_line)
+            .unwrap_or(file_node);
+        edges.push(Edge {
+            r#type: EdgeType::Contains,
+            from: parent.clone(),
+            to: node.clone(),
+            import: None,
+            alias: None,
+            properties: IndexMap::new(),
+        });
+        todo_nodes.insert(node.name.clone(), node);
+    }
+
+    (todo_nodes, edges)
+}
+
+/// Returns the tag and the text of the `TODO`/`FIXME` comment in the given comment line, e.g. `TODO` and
+/// `TODO: handle errors` for `// TODO: handle errors`.
+fn todo_comment(line: &str) -> Option<(&'static str, String)> {
+    // Strip the comment markers, e.g. `//`, `/*`, the leading `*` of block comments, `#` and `///`.
+    let text = line.trim().trim_start_matches(['/', '*', '#', '!']).trim();
+    let text = text.strip_suffix("*/").unwrap_or(text).trim();
+    for tag in TODO_TAGS {
+        // The tag must be a whole word, e.g. `TODO:` or `FIXME(#12)` rather than `TODOS`.
+        let Some(rest) = text.strip_prefix(tag) else {
+            continue;
+        };
+        if rest
+            .chars()
+            .next()
+            .map_or(true, |c| !c.is_alphanumeric() && c != '_')
+        {
+            return Some((tag, text.to_string()));
+        }
+    }
+    None
+}
+
+/// Returns the package (i.e. directory) of the given file node, e.g. "pkg/a.go" => "pkg" and "a.go" => ".".
+pub fn package_dir(file_name: &str) -> String {
+    let dir = Path::new(file_name)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if dir.is_empty() {
+        ".".to_string()
+    } else {
+        dir
+    }
+}
+
+/// Finds the parsed type nodes named `type_name`, within the given scope of the file or directory node `package_name`.
+pub fn find_scoped_type_nodes<'a>(
+    nodes: &'a IndexMap<String, Node>,
+    package_name: &str,
+    scope: ReferenceScope,
+    type_name: &str,
+) -> Vec<&'a Node> {
+    if scope == ReferenceScope::File {
+        return nodes
+            .get(&format!("{}:{}", package_name, type_name))
+            .into_iter()
+            .collect();
+    }
+
+    nodes
+        .values()
+        .filter(|node| {
+            matches!(
+                node.r#type,
+                NodeType::Interface | NodeType::Class | NodeType::OtherType
+            )
+        })
+        .filter(|node| match node.name.split_once(':') {
+            Some((file_name, name)) => {
+                name == type_name
+                    && (scope == ReferenceScope::Repo || package_dir(file_name) == package_name)
+            }
+            None => false,
+        })
+        .collect()
+}
+
+/// Returns the given parsed nodes, along with the nodes named `names` that are not parsed but found in the database.
+///
+/// Mainly used when indexing a single file or a subdirectory, whose edges may point to the nodes outside of it.
+pub fn with_indexed_nodes(
+    nodes: &IndexMap<String, Node>,
+    names: impl IntoIterator<Item = String>,
+    db: &mut Database,
+) -> Result<IndexMap<String, Node>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut nodes = nodes.clone();
+    let node_keys: HashSet<String> = names
+        .into_iter()
+        .filter(|name| !nodes.contains_key(name))
+        .map(|name| util::cypher_string_literal(&db.node_key(&name)))
+        .collect();
+    if node_keys.is_empty() {
+        return Ok(nodes);
+    }
+
+    let stmt = format!(
+        r#"
+MATCH (n)
+WHERE n.name IN [{}]
+RETURN n;
+        "#,
+        node_keys.into_iter().collect::<Vec<_>>().join(", "),
+    );
+    log::trace!("Query Stmt: {:}", stmt);
+    for node in db.query_nodes(stmt.as_str())? {
+        nodes.insert(node.name.clone(), node);
+    }
+
+    Ok(nodes)
+}
+
+/// Queries the type nodes, whose short names are in `short_names`, within the given scope of
+/// the file or directory node `package_name`.
+pub fn query_scoped_type_nodes(
+    db: &mut Database,
+    package_name: &str,
+    scope: ReferenceScope,
+    short_names: &HashSet<String>,
+) -> Result<Vec<Node>, Box<dyn std::error::Error + Send + Sync>> {
+    let stmt = match scope {
+        ReferenceScope::File => {
+            r#"
+MATCH (file { name: $package_name })
+MATCH (file)-[:CONTAINS]->(typ)
+WHERE typ.short_name IN $short_names
+RETURN typ;
+            "#
+        }
+        ReferenceScope::Package => {
+            r#"
+MATCH (pkg { name: $package_name })
+MATCH (pkg)-[:CONTAINS*2]->(typ)
+WHERE typ.short_name IN $short_names
+RETURN typ;
+            "#
+        }
+        ReferenceScope::Repo => {
+            r#"
+MATCH (typ)
+WHERE typ.short_name IN $short_names AND label(typ) IN ["Interface", "Class", "OtherType"]
+RETURN typ;
+            "#
+        }
+    };
+    log::trace!("Query Stmt: {:}", stmt);
+
+    let mut params = HashMap::from([(
+        "short_names".to_string(),
+        kuzu::Value::List(
+            kuzu::LogicalType::String,
+            short_names
+                .iter()
+                .map(|s| kuzu::Value::String(s.to_lowercase()))
+                .collect(),
+        ),
+    )]);
+    if scope != ReferenceScope::Repo {
+        params.insert(
+            "package_name".to_string(),
+            kuzu::Value::String(db.node_key(package_name)),
+        );
+    }
+    Ok(db.query_nodes_params(stmt, params)?)
+}
@@ -0,0 +1,156 @@
+use indexmap::IndexMap;
+
+use crate::File;
+use crate::{Edge, EdgeType, Language, Node, NodeType};
+
+/// A minimal parser for config files (i.e. YAML and JSON), which only extracts the top-level keys.
+pub struct Parser {}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn parse(
+        &self,
+        file_node: &Node,
+        file: &File,
+    ) -> Result<(IndexMap<String, Node>, Vec<Edge>), Box<dyn std::error::Error + Send + Sync>> {
+        let mut nodes: IndexMap<String, Node> = IndexMap::new();
+        let mut edges: Vec<Edge> = Vec::new();
+
+        let source_code = String::from_utf8_lossy(file.content);
+        let lines: Vec<&str> = source_code.lines().collect();
+
+        // The keys, and the (exclusive) line number where the last key ends.
+        let (keys, end_line) = match file_node.language {
+            Language::Yaml => (yaml_top_level_keys(&lines), lines.len()),
+            Language::Json => json_top_level_keys(&source_code),
+            _ => (vec![], 0),
+        };
+
+        for (i, (key, start_line)) in keys.iter().enumerate() {
+            // A key spans until the next key (or the end of the document).
+            let next_line = keys.get(i + 1).map(|(_, line)| *line).unwrap_or(end_line);
+            let end_line = (*start_line..next_line)
+                .rev()
+                .find(|&line| !is_blank_line(lines[line]))
+                .unwrap_or(*start_line);
+
+            let node = Node {
+                name: format!("{}:{}", file_node.name, key),
+                r#type: NodeType::OtherType,
+                language: file_node.language.clone(),
+                start_line: *start_line,
+                end_line,
+                code: lines[*start_line..=end_line].join("\n"),
+                skeleton_code: lines[*start_line].to_string(),
+                ..Default::default()
+            };
+            if nodes.contains_key(&node.name) {
+                continue;
+            }
+
+            nodes.insert(node.name.clone(), node.clone());
+            edges.push(Edge {
+                r#type: EdgeType::Contains,
+                from: file_node.clone(),
+                to: node,
+                import: None,
+                alias: None,
+                properties: IndexMap::new(),
+            });
+        }
+
+        Ok((nodes, edges))
+    }
+}
+
+fn is_blank_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#') || line == "---"
+}
+
+/// Returns the top-level keys of the YAML document and their (0-based) line numbers.
+///
+/// Top-level keys are the mapping keys without indentation, e.g. `services:` or `"name": demo`.
+fn yaml_top_level_keys(lines: &[&str]) -> Vec<(String, usize)> {
+    let mut keys: Vec<(String, usize)> = Vec::new();
+
+    for (line_number, line) in lines.iter().enumerate() {
+        if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+            continue;
+        }
+
+        // Split at the first colon that is followed by a space or the end of the line.
+        let Some(pos) = line
+            .match_indices(':')
+            .map(|(pos, _)| pos)
+            .find(|&pos| line[pos + 1..].is_empty() || line[pos + 1..].starts_with(' '))
+        else {
+            continue;
+        };
+        let key = line[..pos].trim().trim_matches(|c| c == '"' || c == '\'');
+        if !key.is_empty() {
+            keys.push((key.to_string(), line_number));
+        }
+    }
+
+    keys
+}
+
+/// Returns the keys of the top-level JSON object and their (0-based) line numbers,
+/// as well as the line number of the closing brace of the object.
+fn json_top_level_keys(source_code: &str) -> (Vec<(String, usize)>, usize) {
+    let mut keys: Vec<(String, usize)> = Vec::new();
+    let mut end_line_number = 0;
+
+    let mut depth = 0;
+    let mut line_number = 0;
+    let mut chars = source_code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line_number += 1,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    end_line_number = line_number;
+                }
+            }
+            '"' => {
+                let string_line_number = line_number;
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                string.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        '\n' => {
+                            line_number += 1;
+                            string.push(c);
+                        }
+                        _ => string.push(c),
+                    }
+                }
+
+                // A string directly inside the top-level object, which is followed by a colon, is a key.
+                while chars
+                    .peek()
+                    .is_some_and(|c| c.is_whitespace() && *c != '\n')
+                {
+                    chars.next();
+                }
+                if depth == 1 && chars.peek() == Some(&':') {
+                    keys.push((string, string_line_number));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (keys, end_line_number)
+}
@@ -3,16 +3,54 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use strum_macros;
 use tree_sitter;
 use tree_sitter::StreamingIterator;
 use tree_sitter_go;
 
 use super::common;
+use super::common::{PendingCall, PendingInherit};
 use crate::util;
 use crate::Database;
 use crate::{Edge, EdgeType, Language, Node, NodeType};
-use crate::{File, FuncParamType};
+use crate::{File, FuncParamType, ReferenceScope};
+
+/// The directories of the packages imported by a file, by their local names.
+///
+/// An aliased package is only accessible by its alias, e.g. `m.User` for `import m "example.com/app/models"`,
+/// and the aliases take precedence over the (unaliased) package names. Among the imports with the same local
+/// name, the first one wins, so the lookup does not depend on anything but the order of the imports.
+#[derive(Debug, Default)]
+struct ImportedPackages {
+    aliased: HashMap<String, String>,
+    unaliased: HashMap<String, String>,
+}
+
+impl ImportedPackages {
+    /// Adds the package imported by the given import edge.
+    fn add(&mut self, edge: &Edge) {
+        // The directory of the imported package, where the root directory is named ".".
+        let dir = if edge.to.name.is_empty() {
+            ".".to_string()
+        } else {
+            edge.to.name.clone()
+        };
+        let (names, local_name) = match (&edge.alias, &edge.import) {
+            (Some(alias), _) => (&mut self.aliased, alias),
+            (None, Some(import)) => (&mut self.unaliased, import),
+            (None, None) => return,
+        };
+        names.entry(local_name.clone()).or_insert(dir);
+    }
+
+    /// Returns the directory of the package with the given local name.
+    fn get(&self, local_name: &str) -> Option<&String> {
+        self.aliased
+            .get(local_name)
+            .or_else(|| self.unaliased.get(local_name))
+    }
+}
 
 /// The tree-sitter definition query source for Go.
 pub const GO_DEFINITIONS_QUERY_SOURCE: &str = include_str!("queries/go-definitions.scm");
@@ -33,13 +71,28 @@ enum QueryPattern {
 pub struct Parser {
     repo_path: PathBuf,
     go_module_path: Option<String>,
+    reference_scope: ReferenceScope,
+    skeleton_body_lines: usize,
+    index_stdlib: bool,
+    // The source directory of the standard library (i.e. `$GOROOT/src`), which is only looked up on the first
+    // import of a standard library package.
+    stdlib_src_dir: OnceLock<Option<PathBuf>>,
 }
 
 impl Parser {
-    pub fn new(repo_path: PathBuf) -> Self {
+    pub fn new(
+        repo_path: PathBuf,
+        reference_scope: ReferenceScope,
+        skeleton_body_lines: usize,
+        index_stdlib: bool,
+    ) -> Self {
         Self {
             repo_path: repo_path.clone(),
             go_module_path: util::get_go_repo_module_path(&repo_path),
+            reference_scope,
+            skeleton_body_lines,
+            index_stdlib,
+            stdlib_src_dir: OnceLock::new(),
         }
     }
 
@@ -52,13 +105,18 @@ impl Parser {
             IndexMap<String, Node>,
             Vec<Edge>,
             Option<HashMap<String, Vec<FuncParamType>>>,
+            Vec<PendingCall>,
+            Vec<PendingInherit>,
         ),
-        Box<dyn std::error::Error>,
+        Box<dyn std::error::Error + Send + Sync>,
     > {
         let query_source = GO_DEFINITIONS_QUERY_SOURCE.to_string();
         let mut nodes: IndexMap<String, Node> = IndexMap::new();
         let mut edges: Vec<Edge> = Vec::new();
         let mut func_param_types: HashMap<String, Vec<FuncParamType>> = HashMap::new();
+        let mut pending_calls: Vec<PendingCall> = Vec::new();
+        let mut pending_inherits: Vec<PendingInherit> = Vec::new();
+        let mut imported_packages = ImportedPackages::default();
 
         let source_code = file.content;
 
@@ -114,33 +172,40 @@ impl Parser {
                                         _ => unreachable!(),
                                     };
 
-                                    if let Some(go_module_path) = self.go_module_path.clone() {
-                                        let mod_file_path = util::get_repo_module_file_path(
-                                            &PathBuf::from(""),
-                                            &go_module_path,
-                                            &mod_import_path,
-                                        );
+                                    // The packages of the repository, or of the standard library if indexed.
+                                    let mod_file_path = self
+                                        .go_module_path
+                                        .as_ref()
+                                        .and_then(|go_module_path| {
+                                            util::get_repo_module_file_path(
+                                                &PathBuf::from(""),
+                                                go_module_path,
+                                                &mod_import_path,
+                                            )
+                                        })
+                                        .or_else(|| self.stdlib_package_dir(&mod_import_path));
 
-                                        if let Some(mod_file_path) = mod_file_path {
-                                            let parts: Vec<&str> =
-                                                mod_import_path.rsplitn(2, '/').collect();
-                                            let mod_name = parts.first().unwrap_or(&""); // get module name
-
-                                            let edge = Edge {
-                                                r#type: EdgeType::Imports,
-                                                from: Node::from_type_and_name(
-                                                    file_node.r#type.clone(),
-                                                    file_node.name.clone(),
-                                                ),
-                                                to: Node::from_type_and_name(
-                                                    NodeType::Directory,
-                                                    mod_file_path.to_string_lossy().to_string(),
-                                                ),
-                                                import: Some(mod_name.to_string()),
-                                                alias: alias,
-                                            };
-                                            edges.push(edge);
-                                        }
+                                    if let Some(mod_file_path) = mod_file_path {
+                                        let parts: Vec<&str> =
+                                            mod_import_path.rsplitn(2, '/').collect();
+                                        let mod_name = parts.first().unwrap_or(&""); // get module name
+
+                                        let edge = Edge {
+                                            r#type: EdgeType::Imports,
+                                            from: Node::from_type_and_name(
+                                                file_node.r#type.clone(),
+                                                file_node.name.clone(),
+                                            ),
+                                            to: Node::from_type_and_name(
+                                                NodeType::Directory,
+                                                mod_file_path.to_string_lossy().to_string(),
+                                            ),
+                                            import: Some(mod_name.to_string()),
+                                            alias: alias,
+                                            properties: IndexMap::new(),
+                                        };
+                                        imported_packages.add(&edge);
+                                        edges.push(edge);
                                     }
                                 }
                                 _ => {}
@@ -165,7 +230,52 @@ impl Parser {
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
+
+                            // Capture the method requirements of the interface as its child nodes.
+                            let interface_ts_node = mat.captures.iter().find(|capture| {
+                                query.capture_names()[capture.index as usize]
+                                    == "definition.interface"
+                            });
+                            if let Some(interface_ts_node) = interface_ts_node {
+                                // Embedded interfaces, e.g. `Reader` in `type ReadWriter interface { Reader; Writer }`.
+                                if let Some(interface_type) =
+                                    interface_ts_node.node.child_by_field_name("type")
+                                {
+                                    for embedded_type_name in
+                                        Self::embedded_types(interface_type, source_code)
+                                    {
+                                        if let Some(base) = self.parse_func_param_type(
+                                            &curr_node.name,
+                                            &embedded_type_name,
+                                            &imported_packages,
+                                        ) {
+                                            pending_inherits.push(PendingInherit {
+                                                from: curr_node.name.clone(),
+                                                base,
+                                            });
+                                        }
+                                    }
+                                }
+
+                                let method_nodes = Self::parse_interface_methods(
+                                    &curr_node,
+                                    interface_ts_node.node,
+                                    &source_code,
+                                );
+                                for method_node in method_nodes {
+                                    nodes.insert(method_node.name.clone(), method_node.clone());
+                                    edges.push(Edge {
+                                        r#type: EdgeType::Contains,
+                                        from: curr_node.clone(),
+                                        to: method_node,
+                                        import: None,
+                                        alias: None,
+                                        properties: IndexMap::new(),
+                                    });
+                                }
+                            }
                         }
                     }
 
@@ -179,6 +289,55 @@ impl Parser {
                             &source_code,
                         );
                         if let Some(curr_node) = current_node {
+                            // The field types of the struct are referenced as well, e.g. `Address` in
+                            // `type User struct { Address *Address }`.
+                            let struct_ts_node = mat
+                                .captures
+                                .iter()
+                                .find(|c| {
+                                    query.capture_names()[c.index as usize] == "definition.class"
+                                })
+                                .and_then(|c| c.node.child_by_field_name("type"));
+                            if let Some(struct_ts_node) = struct_ts_node {
+                                // Embedded types, e.g. `User` in `type Admin struct { User }`.
+                                for embedded_type_name in
+                                    Self::embedded_types(struct_ts_node, source_code)
+                                {
+                                    if let Some(base) = self.parse_func_param_type(
+                                        &curr_node.name,
+                                        &embedded_type_name,
+                                        &imported_packages,
+                                    ) {
+                                        pending_inherits.push(PendingInherit {
+                                            from: curr_node.name.clone(),
+                                            base,
+                                        });
+                                    }
+                                }
+
+                                for field_type_name in
+                                    Self::struct_field_types(struct_ts_node, source_code)
+                                {
+                                    let field_type = self.parse_func_param_type(
+                                        &curr_node.name,
+                                        &field_type_name,
+                                        &imported_packages,
+                                    );
+                                    let Some(field_type) = field_type else {
+                                        continue;
+                                    };
+                                    // Different fields may have the same type, e.g. `[]Tag` and `map[string]Tag`.
+                                    let field_types =
+                                        func_param_types.entry(curr_node.name.clone()).or_default();
+                                    if !field_types.iter().any(|t| {
+                                        t.type_name == field_type.type_name
+                                            && t.package_name == field_type.package_name
+                                    }) {
+                                        field_types.push(field_type);
+                                    }
+                                }
+                            }
+
                             nodes.insert(curr_node.name.clone(), curr_node.clone());
                             edges.push(Edge {
                                 r#type: EdgeType::Contains,
@@ -186,6 +345,7 @@ impl Parser {
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
@@ -220,7 +380,7 @@ impl Parser {
                                         start_line: capture.node.start_position().row,
                                         end_line: capture.node.end_position().row,
                                         code: capture_node_text,
-                                        skeleton_code: String::new(),
+                                        ..Default::default()
                                     });
                                     current_tree_sitter_main_node = Some(capture.node);
                                 }
@@ -258,15 +418,15 @@ impl Parser {
                                     if let Some(current_tree_sitter_main_node) =
                                         current_tree_sitter_main_node
                                     {
-                                        let start_byte = current_tree_sitter_main_node.start_byte();
-                                        let body_start_byte = capture.node.start_byte();
                                         if let Some(curr_node) = &mut current_node {
                                             // Skip the body and keep only the signature.
-                                            curr_node.skeleton_code = String::from_utf8_lossy(
-                                                &source_code[start_byte..body_start_byte],
-                                            )
-                                            .to_string()
-                                                + "{\n...\n}";
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{\n...\n}",
+                                            );
                                         }
                                     }
                                 }
@@ -287,14 +447,31 @@ impl Parser {
                                     parent_struct_name.clone(),
                                     node_name
                                 );
+                            } else if curr_node.name.ends_with(":init") {
+                                // A package may have multiple `init` functions, even in the same file, so number
+                                // them in source order, e.g. "main.go:init#1" and "main.go:init#2".
+                                let prefix = format!("{}#", curr_node.name);
+                                let init_names: Vec<&String> = nodes
+                                    .keys()
+                                    .filter(|name| name.starts_with(&prefix))
+                                    .collect();
+                                curr_node.name = init_names
+                                    .iter()
+                                    .find(|name| {
+                                        nodes[name.as_str()].start_line == curr_node.start_line
+                                    })
+                                    .map(|name| name.to_string())
+                                    .unwrap_or_else(|| {
+                                        format!("{}{}", prefix, init_names.len() + 1)
+                                    });
                             }
 
                             // Parse the parameter types of the current function.
                             for param_type_name in param_type_names {
-                                let param_type = Self::parse_func_param_type(
+                                let param_type = self.parse_func_param_type(
                                     &curr_node.name,
                                     &param_type_name,
-                                    &edges,
+                                    &imported_packages,
                                 );
                                 if let Some(param_type) = param_type {
                                     func_param_types
@@ -311,6 +488,36 @@ impl Parser {
                             if !nodes.contains_key(&curr_node.name) {
                                 nodes.insert(curr_node.name.clone(), curr_node.clone());
 
+                                if let Some(current_tree_sitter_main_node) =
+                                    current_tree_sitter_main_node
+                                {
+                                    pending_calls.extend(Self::parse_calls(
+                                        &curr_node.name,
+                                        current_tree_sitter_main_node,
+                                        source_code,
+                                        &imported_packages,
+                                    ));
+
+                                    // The constraints of the type parameters are referenced as well, e.g. `Ordered` in
+                                    // `func Max[T Ordered](a, b T) T`. Only parse them once for all the matches.
+                                    for constraint in Self::type_parameter_constraints(
+                                        current_tree_sitter_main_node,
+                                        source_code,
+                                    ) {
+                                        let param_type = self.parse_func_param_type(
+                                            &curr_node.name,
+                                            &constraint,
+                                            &imported_packages,
+                                        );
+                                        if let Some(param_type) = param_type {
+                                            func_param_types
+                                                .entry(curr_node.name.clone())
+                                                .or_insert_with(Vec::new)
+                                                .push(param_type);
+                                        }
+                                    }
+                                }
+
                                 let edge = if let Some(parent_struct_name) = &parent_struct_name {
                                     let parent_node_name = curr_node
                                         .name
@@ -325,6 +532,7 @@ impl Parser {
                                         to: curr_node.clone(),
                                         import: None,
                                         alias: None,
+                                        properties: IndexMap::new(),
                                     }
                                 } else {
                                     Edge {
@@ -333,6 +541,7 @@ impl Parser {
                                         to: curr_node.clone(),
                                         import: None,
                                         alias: None,
+                                        properties: IndexMap::new(),
                                     }
                                 };
                                 edges.push(edge);
@@ -370,7 +579,7 @@ impl Parser {
                                         start_line: capture.node.start_position().row,
                                         end_line: capture.node.end_position().row,
                                         code: capture_node_text,
-                                        skeleton_code: String::new(),
+                                        ..Default::default()
                                     });
                                     current_tree_sitter_main_node = Some(capture.node);
                                 }
@@ -387,6 +596,9 @@ impl Parser {
                                     }
                                 }
                                 "definition.method.receiver_type" => {
+                                    if let Some(curr_node) = &mut current_node {
+                                        curr_node.receiver = capture_node_text.clone();
+                                    }
                                     // Try to find the parent struct of the current method.
                                     let struct_node_name = format!(
                                         "{}:{}",
@@ -408,15 +620,15 @@ impl Parser {
                                     if let Some(current_tree_sitter_main_node) =
                                         current_tree_sitter_main_node
                                     {
-                                        let start_byte = current_tree_sitter_main_node.start_byte();
-                                        let body_start_byte = capture.node.start_byte();
                                         if let Some(curr_node) = &mut current_node {
                                             // Skip the body and keep only the signature.
-                                            curr_node.skeleton_code = String::from_utf8_lossy(
-                                                &source_code[start_byte..body_start_byte],
-                                            )
-                                            .to_string()
-                                                + "{\n...\n}";
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{\n...\n}",
+                                            );
                                         }
                                     }
                                 }
@@ -441,10 +653,10 @@ impl Parser {
 
                             // Parse the parameter types of the current method.
                             for param_type_name in param_type_names {
-                                let param_type = Self::parse_func_param_type(
+                                let param_type = self.parse_func_param_type(
                                     &curr_node.name,
                                     &param_type_name,
-                                    &edges,
+                                    &imported_packages,
                                 );
                                 if let Some(param_type) = param_type {
                                     func_param_types
@@ -461,6 +673,17 @@ impl Parser {
                             if !nodes.contains_key(&curr_node.name) {
                                 nodes.insert(curr_node.name.clone(), curr_node.clone());
 
+                                if let Some(current_tree_sitter_main_node) =
+                                    current_tree_sitter_main_node
+                                {
+                                    pending_calls.extend(Self::parse_calls(
+                                        &curr_node.name,
+                                        current_tree_sitter_main_node,
+                                        source_code,
+                                        &imported_packages,
+                                    ));
+                                }
+
                                 let edge = if let Some(parent_struct_name) = &parent_struct_name {
                                     let parent_node_name = curr_node
                                         .name
@@ -475,6 +698,7 @@ impl Parser {
                                         to: curr_node.clone(),
                                         import: None,
                                         alias: None,
+                                        properties: IndexMap::new(),
                                     }
                                 } else {
                                     Edge {
@@ -483,6 +707,7 @@ impl Parser {
                                         to: curr_node.clone(),
                                         import: None,
                                         alias: None,
+                                        properties: IndexMap::new(),
                                     }
                                 };
                                 edges.push(edge);
@@ -507,6 +732,7 @@ impl Parser {
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
@@ -514,7 +740,724 @@ impl Parser {
             }
         }
 
-        Ok((nodes, edges, Some(func_param_types)))
+        common::mark_exported_definitions(
+            &file_node.language,
+            &mut nodes,
+            tree.root_node(),
+            source_code,
+        );
+
+        Ok((
+            nodes,
+            edges,
+            Some(func_param_types),
+            pending_calls,
+            pending_inherits,
+        ))
+    }
+
+    /// Returns the constraint types of the type parameters of the given generic function,
+    /// e.g. `Ordered` and `fmt.Stringer` for `func F[T Ordered, U fmt.Stringer | ~int]()`.
+    ///
+    /// Inline interface constraints are skipped, and so are the builtin ones (e.g. `any`), which
+    /// are filtered out later like the builtin parameter types.
+    /// Re-derives the skeleton code of the given node from its code, if it is a function or method with a body.
+    pub fn derive_skeleton_code(&self, node: &Node) -> Option<String> {
+        if node.r#type != NodeType::Function {
+            return None;
+        }
+        common::derive_skeleton_code(
+            &tree_sitter_go::LANGUAGE.into(),
+            "",
+            &node.code,
+            &["block"],
+            self.skeleton_body_lines,
+            "{\n...\n}",
+        )
+    }
+
+    /// Returns the types of the fields of the given `struct_type` node, e.g. `["int", "*Address"]` for
+    /// `struct { ID int; Address *Address }`.
+    fn struct_field_types(struct_ts_node: tree_sitter::Node, source_code: &[u8]) -> Vec<String> {
+        let mut field_types = Vec::new();
+        let mut cursor = struct_ts_node.walk();
+        for child in struct_ts_node.named_children(&mut cursor) {
+            if child.kind() != "field_declaration_list" {
+                continue;
+            }
+            let mut field_cursor = child.walk();
+            for field in child.named_children(&mut field_cursor) {
+                if field.kind() != "field_declaration" {
+                    continue;
+                }
+                if let Some(type_node) = field.child_by_field_name("type") {
+                    if let Ok(type_text) = type_node.utf8_text(source_code) {
+                        field_types.push(type_text.to_string());
+                    }
+                }
+            }
+        }
+        field_types
+    }
+
+    /// Returns the names of the types embedded in the given struct or interface type, e.g. `User` in
+    /// `struct { User; Name string }` and `io.Reader` in `interface { io.Reader; Close() error }`.
+    ///
+    /// Type constraints of interfaces (e.g. `~int | ~string`) are not embedded types and are skipped.
+    fn embedded_types(type_ts_node: tree_sitter::Node, source_code: &[u8]) -> Vec<String> {
+        let mut embedded_types = Vec::new();
+        let mut candidates: Vec<tree_sitter::Node> = Vec::new();
+        let mut cursor = type_ts_node.walk();
+        for child in type_ts_node.named_children(&mut cursor) {
+            match child.kind() {
+                // struct { User; *Profile; models.Audit }
+                "field_declaration_list" => {
+                    let mut field_cursor = child.walk();
+                    for field in child.named_children(&mut field_cursor) {
+                        if field.kind() != "field_declaration"
+                            || field.child_by_field_name("name").is_some()
+                        {
+                            continue;
+                        }
+                        if let Some(type_node) = field.child_by_field_name("type") {
+                            candidates.push(type_node);
+                        }
+                    }
+                }
+                // interface { Reader; io.Writer }
+                "type_elem" if child.named_child_count() == 1 => {
+                    if let Some(type_node) = child.named_child(0) {
+                        candidates.push(type_node);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for type_node in candidates {
+            // Embedded generic types, e.g. `List[T]`, embed the generic type itself.
+            let type_node = match type_node.kind() {
+                "generic_type" => match type_node.child_by_field_name("type") {
+                    Some(type_node) => type_node,
+                    None => continue,
+                },
+                _ => type_node,
+            };
+            if !matches!(type_node.kind(), "type_identifier" | "qualified_type") {
+                continue;
+            }
+            if let Ok(type_text) = type_node.utf8_text(source_code) {
+                embedded_types.push(type_text.to_string());
+            }
+        }
+        embedded_types
+    }
+
+    fn type_parameter_constraints(
+        func_ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> Vec<String> {
+        let mut constraints: Vec<String> = Vec::new();
+
+        let Some(type_parameters) = func_ts_node.child_by_field_name("type_parameters") else {
+            return constraints;
+        };
+        let mut cursor = type_parameters.walk();
+        let mut stack: Vec<tree_sitter::Node> = type_parameters
+            .named_children(&mut cursor)
+            .filter_map(|decl| decl.child_by_field_name("type"))
+            .collect();
+        stack.reverse();
+        while let Some(ts_node) = stack.pop() {
+            match ts_node.kind() {
+                "type_identifier" | "qualified_type" => {
+                    let constraint = ts_node.utf8_text(source_code).unwrap_or("").to_string();
+                    if !constraints.contains(&constraint) {
+                        constraints.push(constraint);
+                    }
+                }
+                "interface_type" => {}
+                _ => {
+                    // E.g. unions (`A | B`), approximations (`~int`) and generic types (`Number[T]`).
+                    let mut cursor = ts_node.walk();
+                    let children: Vec<_> = ts_node.named_children(&mut cursor).collect();
+                    stack.extend(children.into_iter().rev());
+                }
+            }
+        }
+
+        constraints
+    }
+
+    /// Parses the call expressions inside the given function (or method) into pending calls.
+    fn parse_calls(
+        caller: &str,
+        func_ts_node: tree_sitter::Node,
+        source_code: &[u8],
+        imported_packages: &ImportedPackages,
+    ) -> Vec<PendingCall> {
+        let mut pending_calls: Vec<PendingCall> = Vec::new();
+        let var_types = Self::local_var_types(func_ts_node, source_code);
+
+        let mut stack = vec![func_ts_node];
+        while let Some(ts_node) = stack.pop() {
+            if ts_node.kind() == "call_expression" {
+                if let Some(function) = ts_node.child_by_field_name("function") {
+                    if function.kind() == "identifier" || function.kind() == "selector_expression" {
+                        // e.g. `u` in `u.SetAddress(...)`
+                        let operand = function
+                            .child_by_field_name("operand")
+                            .filter(|operand| operand.kind() == "identifier")
+                            .and_then(|operand| operand.utf8_text(source_code).ok());
+                        let receiver_type =
+                            operand.and_then(|operand| var_types.get(operand).cloned());
+                        // e.g. `models` in `models.NewUser(...)`, unless it is shadowed by a local variable
+                        let package = operand
+                            .filter(|_| receiver_type.is_none())
+                            .and_then(|operand| imported_packages.get(operand).cloned());
+                        pending_calls.push(PendingCall {
+                            caller: caller.to_string(),
+                            callee: function.utf8_text(source_code).unwrap_or("").to_string(),
+                            receiver_type,
+                            package,
+                            reference: false,
+                        });
+                    }
+                }
+            }
+
+            let mut cursor = ts_node.walk();
+            stack.extend(ts_node.children(&mut cursor));
+        }
+
+        pending_calls
+    }
+
+    /// Returns the declared types of the parameters (including the receiver) and the local variables of the given
+    /// function (or method), e.g. `u` => `User` for `u *User`, `var u User` or `u := &User{}`.
+    ///
+    /// For variables initialized by a function call (e.g. `u := NewUser()`), the type is the called function
+    /// followed by `()`, which is resolved later if the function is a struct constructor.
+    fn local_var_types(
+        func_ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> HashMap<String, String> {
+        fn type_name(type_ts_node: tree_sitter::Node, source_code: &[u8]) -> Option<String> {
+            match type_ts_node.kind() {
+                "type_identifier" => type_ts_node.utf8_text(source_code).ok().map(String::from),
+                // `*User` => User
+                "pointer_type" => type_name(type_ts_node.named_child(0)?, source_code),
+                // `Box[T]` => Box
+                "generic_type" => type_name(type_ts_node.child_by_field_name("type")?, source_code),
+                _ => None,
+            }
+        }
+
+        fn expr_type(expr: tree_sitter::Node, source_code: &[u8]) -> Option<String> {
+            match expr.kind() {
+                // `User{...}`
+                "composite_literal" => type_name(expr.child_by_field_name("type")?, source_code),
+                // `&User{...}`
+                "unary_expression" => expr_type(expr.child_by_field_name("operand")?, source_code),
+                "call_expression" => {
+                    let function = expr.child_by_field_name("function")?;
+                    if function.kind() != "identifier" {
+                        return None;
+                    }
+                    let function_name = function.utf8_text(source_code).ok()?;
+                    if function_name == "new" {
+                        // `new(User)`
+                        let arguments = expr.child_by_field_name("arguments")?;
+                        type_name(arguments.named_child(0)?, source_code)
+                    } else {
+                        Some(format!("{}()", function_name))
+                    }
+                }
+                _ => None,
+            }
+        }
+
+        let mut var_types: HashMap<String, String> = HashMap::new();
+
+        for field in ["receiver", "parameters"] {
+            let Some(params) = func_ts_node.child_by_field_name(field) else {
+                continue;
+            };
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                let Some(param_type) = param
+                    .child_by_field_name("type")
+                    .and_then(|t| type_name(t, source_code))
+                else {
+                    continue;
+                };
+                let mut name_cursor = param.walk();
+                for name in param.children_by_field_name("name", &mut name_cursor) {
+                    if let Ok(name) = name.utf8_text(source_code) {
+                        var_types.insert(name.to_string(), param_type.clone());
+                    }
+                }
+            }
+        }
+
+        let mut stack = vec![func_ts_node];
+        while let Some(ts_node) = stack.pop() {
+            let (names, values): (Vec<tree_sitter::Node>, Vec<tree_sitter::Node>) =
+                match ts_node.kind() {
+                    // `var u User` or `var u = NewUser()`
+                    "var_spec" => {
+                        let mut cursor = ts_node.walk();
+                        let names: Vec<tree_sitter::Node> = ts_node
+                            .children_by_field_name("name", &mut cursor)
+                            .collect();
+                        if let Some(var_type) = ts_node
+                            .child_by_field_name("type")
+                            .and_then(|t| type_name(t, source_code))
+                        {
+                            for name in &names {
+                                if let Ok(name) = name.utf8_text(source_code) {
+                                    var_types.insert(name.to_string(), var_type.clone());
+                                }
+                            }
+                            (vec![], vec![])
+                        } else {
+                            let values = ts_node
+                                .child_by_field_name("value")
+                                .map(|v| {
+                                    let mut cursor = v.walk();
+                                    v.named_children(&mut cursor).collect()
+                                })
+                                .unwrap_or_default();
+                            (names, values)
+                        }
+                    }
+                    // `u := NewUser()`
+                    "short_var_declaration" => {
+                        let named_children = |field: &str| -> Vec<tree_sitter::Node> {
+                            ts_node
+                                .child_by_field_name(field)
+                                .map(|list| {
+                                    let mut cursor = list.walk();
+                                    list.named_children(&mut cursor).collect()
+                                })
+                                .unwrap_or_default()
+                        };
+                        (named_children("left"), named_children("right"))
+                    }
+                    _ => (vec![], vec![]),
+                };
+            for (name, value) in names.iter().zip(values.iter()) {
+                if let (Ok(name), Some(value_type)) =
+                    (name.utf8_text(source_code), expr_type(*value, source_code))
+                {
+                    var_types.insert(name.to_string(), value_type);
+                }
+            }
+
+            let mut cursor = ts_node.walk();
+            stack.extend(ts_node.children(&mut cursor));
+        }
+
+        var_types
+    }
+
+    /// Resolves the pending inherits to the embedded structs or interfaces, which are defined within the
+    /// configured scope of the embedding type, or in the imported packages.
+    pub fn resolve_pending_inherits(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_inherits: &Vec<PendingInherit>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for inherit in pending_inherits {
+            let Some(from_node) = nodes.get(&inherit.from) else {
+                continue;
+            };
+            let Some(package_name) = &inherit.base.package_name else {
+                continue;
+            };
+            let base_nodes = common::find_scoped_type_nodes(
+                nodes,
+                package_name,
+                inherit.base.scope,
+                &inherit.base.type_name,
+            );
+            for base_node in base_nodes {
+                if base_node.language != Language::Go
+                    || !matches!(base_node.r#type, NodeType::Class | NodeType::Interface)
+                {
+                    continue;
+                }
+                edges.push(Edge {
+                    r#type: EdgeType::Inherits,
+                    from: from_node.clone(),
+                    to: base_node.clone(),
+                    import: None,
+                    alias: None,
+                    properties: IndexMap::new(),
+                });
+            }
+        }
+
+        edges
+    }
+
+    /// Resolves the interfaces implemented by the structs, i.e. the struct's method set includes all the methods
+    /// required by the interface, where both sides follow the embedding (i.e. the given inherits edges):
+    ///
+    /// - An interface requires the methods of its embedded interfaces as well.
+    /// - A struct has the methods promoted from its embedded structs (or interfaces) as well.
+    ///
+    /// The receivers are not distinguished, i.e. the method set of a struct `T` is the one of `*T`. Interfaces
+    /// requiring no methods (e.g. type constraints) are skipped, as well as the already embedded ones.
+    pub fn resolve_implements(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        inherit_edges: &[Edge],
+    ) -> Vec<Edge> {
+        let mut bases: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in inherit_edges {
+            bases
+                .entry(edge.from.name.as_str())
+                .or_default()
+                .push(edge.to.name.as_str());
+        }
+
+        // The methods declared by each interface (or struct), keyed by the interface (or struct) name.
+        let mut methods: HashMap<String, HashSet<&str>> = HashMap::new();
+        for node in nodes.values() {
+            if node.r#type != NodeType::Function || node.language != Language::Go {
+                continue;
+            }
+            if !node.receiver.is_empty() {
+                // The methods of a struct may be declared in any file of its package.
+                // "user.go:User.Save" => ("user.go", "Save")
+                let (Some((file_name, _)), Some(method)) = (
+                    node.name.split_once(':'),
+                    node.name.rsplit([':', '.']).next(),
+                ) else {
+                    continue;
+                };
+                let key = Self::package_type_key(file_name, &node.receiver);
+                methods.entry(key).or_default().insert(method);
+                continue;
+            }
+            // "types.go:Reader.Read" => ("types.go:Reader", "Read")
+            let Some((owner, method)) = node.name.rsplit_once('.') else {
+                continue;
+            };
+            if nodes
+                .get(owner)
+                .is_some_and(|owner_node| owner_node.r#type == NodeType::Interface)
+            {
+                methods.entry(owner.to_string()).or_default().insert(method);
+            }
+        }
+
+        // Collects the methods of the given type and its embedded types, transitively.
+        fn collect_methods<'a>(
+            name: &'a str,
+            nodes: &IndexMap<String, Node>,
+            bases: &HashMap<&str, Vec<&'a str>>,
+            methods: &HashMap<String, HashSet<&'a str>>,
+            visited: &mut HashSet<&'a str>,
+            method_set: &mut HashSet<&'a str>,
+        ) {
+            if !visited.insert(name) {
+                return;
+            }
+            let key = match nodes.get(name) {
+                Some(node) if node.r#type == NodeType::Class => name
+                    .split_once(':')
+                    .map(|(file_name, type_name)| Parser::package_type_key(file_name, type_name))
+                    .unwrap_or_default(),
+                _ => name.to_string(),
+            };
+            if let Some(own_methods) = methods.get(&key) {
+                method_set.extend(own_methods.iter().copied());
+            }
+            for base in bases.get(name).into_iter().flatten() {
+                collect_methods(base, nodes, bases, methods, visited, method_set);
+            }
+        }
+        let method_set = |name: &str| {
+            let mut method_set: HashSet<&str> = HashSet::new();
+            let name = nodes
+                .get_key_value(name)
+                .map(|(k, _)| k.as_str())
+                .unwrap_or_default();
+            collect_methods(
+                name,
+                nodes,
+                &bases,
+                &methods,
+                &mut HashSet::new(),
+                &mut method_set,
+            );
+            method_set
+        };
+
+        let go_nodes = |r#type: NodeType| {
+            nodes
+                .values()
+                .filter(move |node| node.r#type == r#type && node.language == Language::Go)
+        };
+        let interfaces: Vec<(&Node, HashSet<&str>)> = go_nodes(NodeType::Interface)
+            .map(|node| (node, method_set(&node.name)))
+            .filter(|(_, required)| !required.is_empty())
+            .collect();
+        if interfaces.is_empty() {
+            return Vec::new();
+        }
+
+        let mut edges: Vec<Edge> = Vec::new();
+        for struct_node in go_nodes(NodeType::Class) {
+            let struct_methods = method_set(&struct_node.name);
+            let embedded = bases.get(struct_node.name.as_str());
+            for (interface_node, required) in &interfaces {
+                if embedded.is_some_and(|embedded| embedded.contains(&interface_node.name.as_str()))
+                    || !required.is_subset(&struct_methods)
+                {
+                    continue;
+                }
+                edges.push(Edge {
+                    r#type: EdgeType::Inherits,
+                    from: struct_node.clone(),
+                    to: (*interface_node).clone(),
+                    import: None,
+                    alias: None,
+                    properties: IndexMap::new(),
+                });
+            }
+        }
+
+        edges
+    }
+
+    /// Returns the key of the type in its package (i.e. directory), e.g. `models/User` for `models/user.go:User`.
+    fn package_type_key(file_name: &str, type_name: &str) -> String {
+        let dir = Path::new(file_name)
+            .parent()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+        format!("{}/{}", dir, type_name)
+    }
+
+    /// Resolves the pending calls to the functions in the same package (i.e. directory), or in the imported packages
+    /// of the repository (e.g. `models.NewUser`).
+    ///
+    /// Calls on selectors (e.g. `u.SetAddress`) are resolved to the methods of the declared type of the receiver
+    /// variable, if it is known and defined in the same package. Calls to the packages outside the repository
+    /// (e.g. `fmt.Println`) are dropped.
+    pub fn resolve_pending_calls(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_calls: &Vec<PendingCall>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        // Index the package-level functions (including struct constructors) by directory and function name.
+        let mut package_funcs: HashMap<(String, String), &Node> = HashMap::new();
+        for node in nodes.values() {
+            if node.r#type != NodeType::Function
+                || node.language != Language::Go
+                || !node.receiver.is_empty()
+            {
+                continue;
+            }
+            // "main.go:main" => main, "main.go:User.NewUser" => NewUser
+            if let (Some((file_name, _)), Some(func_name)) = (
+                node.name.split_once(':'),
+                node.name.rsplit(['.', ':']).next(),
+            ) {
+                package_funcs.insert(
+                    (common::package_dir(file_name), func_name.to_string()),
+                    node,
+                );
+            }
+        }
+
+        // Index the methods by directory, receiver type and method name.
+        let mut package_methods: HashMap<(String, String, String), &Node> = HashMap::new();
+        for node in nodes.values() {
+            if node.r#type != NodeType::Function || node.language != Language::Go {
+                continue;
+            }
+            if node.receiver.is_empty() {
+                continue;
+            }
+            if let (Some((file_name, _)), Some(method_name)) = (
+                node.name.split_once(':'),
+                node.name.rsplit(['.', ':']).next(),
+            ) {
+                package_methods.insert(
+                    (
+                        common::package_dir(file_name),
+                        node.receiver.clone(),
+                        method_name.to_string(),
+                    ),
+                    node,
+                );
+            }
+        }
+
+        let mut resolved: HashSet<(String, String)> = HashSet::new();
+        for call in pending_calls {
+            let Some(caller_node) = nodes.get(&call.caller) else {
+                continue;
+            };
+            let Some((file_name, _)) = call.caller.split_once(':') else {
+                continue;
+            };
+            let package_dir = common::package_dir(file_name);
+
+            let callee_node = if let Some(package) = &call.package {
+                let Some((_, func_name)) = call.callee.rsplit_once('.') else {
+                    continue;
+                };
+                package_funcs.get(&(package.clone(), func_name.to_string()))
+            } else if let Some((_, method_name)) = call.callee.rsplit_once('.') {
+                let Some(receiver_type) = &call.receiver_type else {
+                    continue;
+                };
+                let receiver_type = match receiver_type.strip_suffix("()") {
+                    // The struct of the constructor, e.g. "main.go:User.NewUser" => User
+                    Some(func_name) => package_funcs
+                        .get(&(package_dir.clone(), func_name.to_string()))
+                        .and_then(|func| func.name.split_once(':'))
+                        .and_then(|(_, name)| name.split_once('.'))
+                        .map(|(struct_name, _)| struct_name.to_string()),
+                    None => Some(receiver_type.clone()),
+                };
+                receiver_type.and_then(|receiver_type| {
+                    package_methods.get(&(package_dir, receiver_type, method_name.to_string()))
+                })
+            } else {
+                package_funcs.get(&(package_dir, call.callee.clone()))
+            };
+
+            if let Some(callee_node) = callee_node {
+                if resolved.insert((caller_node.name.clone(), callee_node.name.clone())) {
+                    edges.push(Edge {
+                        r#type: EdgeType::Calls,
+                        from: caller_node.clone(),
+                        to: (*callee_node).clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Same as `resolve_pending_calls`, but also looks up the functions of the calling and called packages that
+    /// are not parsed in the database.
+    ///
+    /// Mainly used when indexing a single file or a subdirectory.
+    pub fn resolve_pending_calls_from_db(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_calls: &Vec<PendingCall>,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut dirs: HashSet<String> = HashSet::new();
+        for call in pending_calls {
+            if let Some((file_name, _)) = call.caller.split_once(':') {
+                dirs.insert(common::package_dir(file_name));
+            }
+            if let Some(package) = &call.package {
+                dirs.insert(package.clone());
+            }
+        }
+        if dirs.is_empty() {
+            return Ok(self.resolve_pending_calls(nodes, pending_calls));
+        }
+
+        // The functions (and methods) of the files directly under the package directories.
+        let stmt = format!(
+            r#"
+MATCH (dir:Directory)-[:CONTAINS]->(:File)-[:CONTAINS*1..2]->(func:Function)
+WHERE dir.name IN [{}]
+RETURN func;
+            "#,
+            dirs.iter()
+                .map(|dir| util::cypher_string_literal(&db.node_key(dir)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        log::trace!("Query Stmt: {:}", stmt);
+        let mut nodes = nodes.clone();
+        for node in db.query_nodes(stmt.as_str())? {
+            if node.language == Language::Go {
+                nodes.entry(node.name.clone()).or_insert(node);
+            }
+        }
+
+        Ok(self.resolve_pending_calls(&nodes, pending_calls))
+    }
+
+    /// Parses the method elements of the given interface (e.g. `Area() float64`) into function nodes.
+    ///
+    /// Since interface methods have no body, their skeleton code is the same as their code.
+    fn parse_interface_methods(
+        interface_node: &Node,
+        interface_ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> Vec<Node> {
+        let mut method_nodes: Vec<Node> = Vec::new();
+
+        let Some(interface_type) = interface_ts_node.child_by_field_name("type") else {
+            return method_nodes;
+        };
+        let mut cursor = interface_type.walk();
+        for child in interface_type.named_children(&mut cursor) {
+            if child.kind() != "method_elem" {
+                // Skip type elements, e.g. embedded interfaces and type constraints.
+                continue;
+            }
+            let Some(name) = child.child_by_field_name("name") else {
+                continue;
+            };
+            let method_name = name.utf8_text(source_code).unwrap_or("");
+            let code = child.utf8_text(source_code).unwrap_or("").to_string();
+            method_nodes.push(Node {
+                name: format!("{}.{}", interface_node.name, method_name),
+                r#type: NodeType::Function,
+                language: interface_node.language.clone(),
+                start_line: child.start_position().row,
+                end_line: child.end_position().row,
+                code: code.clone(),
+                skeleton_code: code,
+                ..Default::default()
+            });
+        }
+
+        method_nodes
+    }
+
+    /// Returns the directory of the given standard library package, e.g. `/usr/local/go/src/time` for `time`,
+    /// or None if the standard library is not indexed or the package is not found.
+    fn stdlib_package_dir(&self, import_path: &str) -> Option<PathBuf> {
+        // Unlike the third-party packages (e.g. `github.com/...`), the first path element has no dot.
+        if !self.index_stdlib || import_path.split('/').next()?.contains('.') {
+            return None;
+        }
+        let src_dir = self
+            .stdlib_src_dir
+            .get_or_init(|| match util::get_go_root() {
+                Ok(go_root) => Some(PathBuf::from(go_root).join("src")),
+                Err(e) => {
+                    log::warn!("Unable to locate the Go standard library: {}", e);
+                    None
+                }
+            })
+            .as_ref()?;
+        let package_dir = src_dir.join(import_path);
+        package_dir.is_dir().then_some(package_dir)
     }
 
     pub fn resolve_func_param_type_edges(
@@ -522,39 +1465,26 @@ impl Parser {
         nodes: &IndexMap<String, Node>,
         func_param_types: &HashMap<String, Vec<FuncParamType>>,
         db: &mut Database,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
         let mut edges: Vec<Edge> = Vec::new();
 
-        let mut pkg_types: IndexMap<String, HashSet<String>> = IndexMap::new();
+        let mut pkg_types: IndexMap<(String, ReferenceScope), HashSet<String>> = IndexMap::new();
         for (func_name, param_types) in func_param_types {
             for param_type in param_types {
                 if let Some(package_name) = &param_type.package_name {
                     pkg_types
-                        .entry(package_name.clone())
+                        .entry((package_name.clone(), param_type.scope))
                         .or_insert_with(HashSet::new)
                         .insert(param_type.type_name.clone());
                 };
             }
         }
 
-        let mut pkgtype_to_node = IndexMap::new(); // "{pkg_name}:{type_name}" => type_node
-        for (pkg_name, type_names) in pkg_types {
-            let quoted_type_names: Vec<String> = type_names
-                .iter()
-                .map(|s| format!("\"{}\"", s.to_lowercase()))
-                .collect();
-            let type_names_str = format!("[{}]", quoted_type_names.join(", "));
-            let stmt = format!(
-                r#"
-MATCH (pkg {{ name: "{}" }})
-MATCH (pkg)-[:CONTAINS*2]->(typ)
-WHERE typ.short_name IN {}
-RETURN typ;
-                "#,
-                pkg_name, type_names_str,
-            );
-            log::trace!("Query Stmt: {:}", stmt);
-            let nodes = db.query_nodes(stmt.as_str())?;
+        // (pkg_name, scope, type_name) => type_nodes
+        let mut pkgtype_to_nodes: HashMap<(String, ReferenceScope, String), Vec<Node>> =
+            HashMap::new();
+        for ((pkg_name, scope), type_names) in pkg_types {
+            let nodes = common::query_scoped_type_nodes(db, &pkg_name, scope, &type_names)?;
 
             if log::log_enabled!(log::Level::Trace) {
                 for node in &nodes {
@@ -562,8 +1492,11 @@ RETURN typ;
                 }
             }
 
-            for node in &nodes {
-                pkgtype_to_node.insert(format!("{}:{}", pkg_name, node.short_name()), node.clone());
+            for node in nodes {
+                pkgtype_to_nodes
+                    .entry((pkg_name.clone(), scope, node.short_name()))
+                    .or_default()
+                    .push(node);
             }
         }
 
@@ -572,20 +1505,23 @@ RETURN typ;
 
             for param_type in param_types {
                 if let Some(package_name) = &param_type.package_name {
-                    let mut type_node = pkgtype_to_node.get(&format!(
-                        "{}:{}",
-                        package_name,
-                        param_type.type_name.to_lowercase()
+                    let type_nodes = pkgtype_to_nodes.get(&(
+                        package_name.clone(),
+                        param_type.scope,
+                        param_type.type_name.to_lowercase(),
                     ));
-                    if let (Some(func_node), Some(type_node)) = (func_node, type_node) {
-                        let rel = Edge {
-                            r#type: EdgeType::References,
-                            from: func_node.clone(),
-                            to: type_node.clone(),
-                            import: None,
-                            alias: None,
-                        };
-                        edges.push(rel);
+                    if let (Some(func_node), Some(type_nodes)) = (func_node, type_nodes) {
+                        for type_node in type_nodes {
+                            let rel = Edge {
+                                r#type: EdgeType::References,
+                                from: func_node.clone(),
+                                to: type_node.clone(),
+                                import: None,
+                                alias: None,
+                                properties: IndexMap::new(),
+                            };
+                            edges.push(rel);
+                        }
                     }
                 }
             }
@@ -595,9 +1531,10 @@ RETURN typ;
     }
 
     fn parse_func_param_type(
+        &self,
         from_node_name: &String,
         param_type_name: &String,
-        import_edges: &Vec<Edge>,
+        imported_packages: &ImportedPackages,
     ) -> Option<FuncParamType> {
         // Skip the inline type definitions
         // `f func (...) ...`
@@ -631,34 +1568,22 @@ RETURN typ;
         };
 
         let mut real_package_name: Option<String> = None;
+        let mut scope = ReferenceScope::Package;
         // Find the target package name that the type belongs to.
         if let Some(package_name) = &package_name {
-            for rel in import_edges {
-                if let Some(import) = &rel.import {
-                    if import == package_name {
-                        real_package_name = Some(rel.to.name.clone());
-                        break;
-                    }
-                }
-                if let Some(alias) = &rel.alias {
-                    if alias == package_name {
-                        real_package_name = Some(rel.to.name.clone());
-                        break;
-                    }
-                }
-            }
-
             // If the package name is not found, leave it as None.
+            real_package_name = imported_packages.get(package_name).cloned();
         } else {
-            // Otherwise, the type is defined in the same package as the current file.
-            let mut parent_dir_path = from_node_name.rsplitn(2, '/').nth(1).unwrap_or("");
-            if parent_dir_path.is_empty() {
-                parent_dir_path = ".";
-            }
-            real_package_name = Some(parent_dir_path.to_string());
+            // Otherwise, the type is defined in the same package as the current file (or within the configured scope).
+            let file_name = from_node_name.split(':').next().unwrap_or("");
+            scope = self.reference_scope;
+            real_package_name = Some(match scope {
+                ReferenceScope::File => file_name.to_string(),
+                ReferenceScope::Package | ReferenceScope::Repo => common::package_dir(file_name),
+            });
         }
 
-        if util::is_go_builtin_type(&type_name) {
+        if util::is_builtin_type(&Language::Go, &type_name) {
             return None;
         }
 
@@ -666,6 +1591,7 @@ RETURN typ;
         return Some(FuncParamType {
             type_name,
             package_name: real_package_name,
+            scope,
         });
     }
 }
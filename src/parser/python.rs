@@ -1,42 +1,60 @@
 use glob::Pattern;
 use indexmap::IndexMap;
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use regex::Regex;
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use strum_macros;
 use tree_sitter;
 use tree_sitter::StreamingIterator;
 use tree_sitter_python;
 use walkdir::WalkDir;
 
+use super::common;
+use super::common::PendingImport;
 use crate::util;
 use crate::Database;
-use crate::File;
 use crate::{Edge, EdgeType, Language, Node, NodeType};
+use crate::{File, FuncParamType, ReferenceScope, UnresolvedImport};
 
 /// The tree-sitter definition query source for Python.
 pub const PYTHON_DEFINITIONS_QUERY_SOURCE: &str = include_str!("queries/python-definitions.scm");
 
 pub struct Parser {
     repo_path: PathBuf,
+    stub_paths: Vec<PathBuf>, // The directories where the absolute imports outside of the repository are looked up.
 }
 
 impl Parser {
-    pub fn new(repo_path: PathBuf) -> Self {
-        Self { repo_path }
+    pub fn new(repo_path: PathBuf, stub_paths: Vec<PathBuf>) -> Self {
+        Self {
+            repo_path,
+            stub_paths,
+        }
     }
 
     pub fn parse(
         &self,
         file_node: &Node,
         file: &File,
-    ) -> Result<(IndexMap<String, Node>, Vec<Edge>), Box<dyn std::error::Error>> {
+    ) -> Result<
+        (
+            IndexMap<String, Node>,
+            Vec<Edge>,
+            Vec<PendingImport>,
+            Option<HashMap<String, Vec<FuncParamType>>>,
+        ),
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
         let query_source = PYTHON_DEFINITIONS_QUERY_SOURCE.to_string();
         let mut nodes: IndexMap<String, Node> = IndexMap::new();
         let mut edges: Vec<Edge> = Vec::new();
+        let mut pending_imports: Vec<PendingImport> = Vec::new();
+        // (function node name, parameter type annotation), which are parsed after all the imports are known
+        let mut param_annotations: Vec<(String, String)> = Vec::new();
 
-        let source_code = fs::read(&file.path).expect("Should have been able to read the file");
+        let source_code = file.content;
 
         //println!("[SOURCE]\n\n{}\n", String::from_utf8_lossy(&source_code));
         //println!("[QUERY]\n\n{}\n", query_source);
@@ -47,14 +65,13 @@ impl Parser {
             .set_language(language)
             .expect("Error loading language parser");
 
-        let tree = parser.parse(source_code.clone(), None).unwrap();
+        let tree = parser.parse(source_code, None).unwrap();
         let root_node = tree.root_node();
 
         let mut cursor = tree_sitter::QueryCursor::new();
         let query = tree_sitter::Query::new(language, &query_source).unwrap();
-        let mut captures = cursor.captures(&query, root_node, source_code.as_slice());
+        let mut captures = cursor.captures(&query, root_node, source_code);
 
-        let mut cur_class_node: Option<tree_sitter::Node> = None;
         // 使用 streaming iterator 的正确方式来迭代QueryCaptures
         while let Some((mat, capture_index)) = captures.next() {
             let capture = mat.captures[*capture_index];
@@ -65,7 +82,7 @@ impl Parser {
                 "[CAPTURE]\nname: {capture_name}, start: {}, end: {}, text: {:?}, capture: {:?}",
                 pos_start,
                 pos_end,
-                capture.node.utf8_text(&source_code).unwrap_or(""),
+                capture.node.utf8_text(source_code).unwrap_or(""),
                 capture.node.to_sexp()
             );
 
@@ -73,44 +90,554 @@ impl Parser {
                 "definition.class.name" => {
                     let class_name: String = capture
                         .node
-                        .utf8_text(&source_code)
+                        .utf8_text(source_code)
                         .unwrap_or("")
                         .to_string();
-                    if let Some(class_node) = cur_class_node {
-                        let node = Node {
-                            name: format!(
-                                "{}:{}",
-                                Path::new(&file.path)
-                                    .strip_prefix(&self.repo_path)
-                                    .unwrap_or_else(|_| Path::new(&file.path))
-                                    .to_string_lossy(),
-                                class_name
-                            ),
-                            r#type: NodeType::Class,
-                            language: file_node.language.clone(),
-                            start_line: class_node.start_position().row + 1,
-                            end_line: class_node.end_position().row + 1,
-                            code: class_node.utf8_text(&source_code).unwrap_or("").to_string(),
-                            skeleton_code: "".to_string(),
-                        };
-                        nodes.insert(node.name.clone(), node.clone());
+                    let Some(class_node) = capture.node.parent() else {
+                        continue;
+                    };
+
+                    // Qualify the name with the enclosing classes and functions, e.g. "Outer.Inner".
+                    let scopes = Self::enclosing_scopes(class_node, source_code);
+                    let qualified_name = scopes
+                        .iter()
+                        .map(|(_, name)| name.as_str())
+                        .chain([class_name.as_str()])
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    let node = Node {
+                        name: format!(
+                            "{}:{}",
+                            Path::new(&file.path)
+                                .strip_prefix(&self.repo_path)
+                                .unwrap_or_else(|_| Path::new(&file.path))
+                                .to_string_lossy(),
+                            qualified_name
+                        ),
+                        r#type: NodeType::Class,
+                        language: file_node.language.clone(),
+                        start_line: class_node.start_position().row + 1,
+                        end_line: class_node.end_position().row + 1,
+                        code: class_node.utf8_text(source_code).unwrap_or("").to_string(),
+                        skeleton_code: "".to_string(),
+                        ..Default::default()
+                    };
+                    nodes.insert(node.name.clone(), node.clone());
+
+                    // A class directly nested in another class is contained by that class, while the
+                    // others are contained by the file (since functions can not contain classes in the schema).
+                    let parent_node = match scopes.last() {
+                        Some((NodeType::Class, _)) => {
+                            let parent_name = node.name.rsplit_once('.').unwrap().0;
+                            nodes.get(parent_name).unwrap_or(file_node)
+                        }
+                        _ => file_node,
+                    };
+                    let edge = Edge {
+                        r#type: EdgeType::Contains,
+                        from: parent_node.clone(),
+                        to: node.clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    };
+                    edges.push(edge);
+                }
+                "definition.function.name" => {
+                    let function_name: String = capture
+                        .node
+                        .utf8_text(source_code)
+                        .unwrap_or("")
+                        .to_string();
+                    let Some(function_node) = capture.node.parent() else {
+                        continue;
+                    };
+
+                    // Qualify the name with the enclosing classes and functions, e.g. "Outer.method".
+                    let scopes = Self::enclosing_scopes(function_node, source_code);
+                    let qualified_name = scopes
+                        .iter()
+                        .map(|(_, name)| name.as_str())
+                        .chain([function_name.as_str()])
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    // Keep the `def ...:` signature and replace the body with `...`.
+                    let skeleton_code = match function_node.child_by_field_name("body") {
+                        Some(body) => {
+                            let signature = String::from_utf8_lossy(
+                                &source_code[function_node.start_byte()..body.start_byte()],
+                            );
+                            // Indent the `...` relative to the `def` line, since the code starts from `def`.
+                            // The body may be dedented in the error-recovered trees.
+                            let indent = body
+                                .start_position()
+                                .column
+                                .saturating_sub(function_node.start_position().column);
+                            format!("{}\n{}...", signature.trim_end(), " ".repeat(indent))
+                        }
+                        None => String::new(),
+                    };
+                    let node = Node {
+                        name: format!(
+                            "{}:{}",
+                            Path::new(&file.path)
+                                .strip_prefix(&self.repo_path)
+                                .unwrap_or_else(|_| Path::new(&file.path))
+                                .to_string_lossy(),
+                            qualified_name
+                        ),
+                        r#type: NodeType::Function,
+                        language: file_node.language.clone(),
+                        start_line: function_node.start_position().row + 1,
+                        end_line: function_node.end_position().row + 1,
+                        code: function_node
+                            .utf8_text(source_code)
+                            .unwrap_or("")
+                            .to_string(),
+                        skeleton_code,
+                        ..Default::default()
+                    };
+                    nodes.insert(node.name.clone(), node.clone());
+
+                    // e.g. `user: User` or `user: Optional[User] = None`
+                    if let Some(parameters) = function_node.child_by_field_name("parameters") {
+                        let mut cursor = parameters.walk();
+                        for param in parameters.named_children(&mut cursor) {
+                            if let Some(annotation) = param
+                                .child_by_field_name("type")
+                                .and_then(|t| t.utf8_text(source_code).ok())
+                            {
+                                param_annotations.push((node.name.clone(), annotation.to_string()));
+                            }
+                        }
+                    }
 
-                        let edge = Edge {
-                            r#type: EdgeType::Contains,
-                            from: file_node.clone(),
-                            to: node.clone(),
-                            import: None,
+                    // A method (i.e. a function directly in a class) is contained by its class, while the
+                    // others are contained by the file.
+                    let parent_node = match scopes.last() {
+                        Some((NodeType::Class, _)) => {
+                            let parent_name = node.name.rsplit_once('.').unwrap().0;
+                            nodes.get(parent_name).unwrap_or(file_node)
+                        }
+                        _ => file_node,
+                    };
+                    edges.push(Edge {
+                        r#type: EdgeType::Contains,
+                        from: parent_node.clone(),
+                        to: node,
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+                "reference.import" => {
+                    // import a.b => a/b.py
+                    // import a.b as c => a/b.py as c
+                    let mut cursor = capture.node.walk();
+                    for name in capture.node.children_by_field_name("name", &mut cursor) {
+                        let (module, alias) = Self::name_and_alias(name, source_code);
+                        if let Some(source_path) = self.resolve_module_path(&file.path, &module) {
+                            pending_imports.push(PendingImport {
+                                language: Language::Python,
+                                source_path,
+                                symbol: None,
+                                alias,
+                                reexport: false,
+                            });
+                        }
+                    }
+                }
+                "reference.import_from" => {
+                    // from .a import X => a.py:X
+                    // from .a import X as Y => a.py:X as Y
+                    // from .a import * => a.py
+                    let Some(module_name) = capture.node.child_by_field_name("module_name") else {
+                        continue;
+                    };
+                    let module = module_name.utf8_text(source_code).unwrap_or("");
+                    let Some(source_path) = self.resolve_module_path(&file.path, module) else {
+                        continue;
+                    };
+
+                    let mut cursor = capture.node.walk();
+                    let names: Vec<tree_sitter::Node> = capture
+                        .node
+                        .children_by_field_name("name", &mut cursor)
+                        .collect();
+                    if names.is_empty() {
+                        pending_imports.push(PendingImport {
+                            language: Language::Python,
+                            source_path: source_path.clone(),
+                            symbol: None,
                             alias: None,
+                            reexport: false,
+                        });
+                    }
+                    for name in names {
+                        let (symbol, alias) = Self::name_and_alias(name, source_code);
+                        // `from . import a` imports the submodule `a` of the package, rather than a symbol.
+                        let submodule = if module.ends_with('.') {
+                            format!("{}{}", module, symbol)
+                        } else {
+                            format!("{}.{}", module, symbol)
                         };
-                        edges.push(edge);
+                        let submodule_path = if source_path.ends_with("__init__.py") {
+                            self.resolve_module_path(&file.path, &submodule)
+                        } else {
+                            None
+                        };
+                        pending_imports.push(match submodule_path {
+                            Some(submodule_path) => PendingImport {
+                                language: Language::Python,
+                                source_path: submodule_path,
+                                symbol: None,
+                                alias,
+                                reexport: false,
+                            },
+                            None => PendingImport {
+                                language: Language::Python,
+                                source_path: source_path.clone(),
+                                symbol: Some(symbol),
+                                alias,
+                                reexport: false,
+                            },
+                        });
                     }
                 }
-                "definition.class" => {
-                    cur_class_node = Some(capture.node);
-                }
                 _ => {}
             }
         }
-        Ok((nodes, edges))
+        let mut func_param_types: HashMap<String, Vec<FuncParamType>> = HashMap::new();
+        for (func_node_name, annotation) in param_annotations {
+            func_param_types.entry(func_node_name).or_default().extend(
+                Self::parse_func_param_types(file_node, &annotation, &pending_imports),
+            );
+        }
+
+        common::mark_exported_definitions(
+            &file_node.language,
+            &mut nodes,
+            tree.root_node(),
+            source_code,
+        );
+
+        Ok((nodes, edges, pending_imports, Some(func_param_types)))
+    }
+
+    /// Returns the types in the given parameter type annotation, which are looked up in the imported modules
+    /// (e.g. `User` in `from .models import User`, or `models.User` in `import models`) or in the same file.
+    ///
+    /// Builtin types (e.g. `str`) are skipped, and so are the types of the modules outside of the repository.
+    fn parse_func_param_types(
+        file_node: &Node,
+        annotation: &str,
+        pending_imports: &[PendingImport],
+    ) -> Vec<FuncParamType> {
+        extract_py_types(annotation, true)
+            .into_iter()
+            .map(|type_name| match type_name.rsplit_once('.') {
+                Some((module, name)) => {
+                    // The module is imported as a whole, e.g. `import models` or `import pkg.models as m`.
+                    let module_path = format!("{}.py", module.replace('.', "/"));
+                    let imp = pending_imports.iter().find(|imp| {
+                        imp.symbol.is_none()
+                            && match &imp.alias {
+                                Some(alias) => alias == module,
+                                None => {
+                                    imp.source_path == module_path
+                                        || imp.source_path.ends_with(&format!("/{}", module_path))
+                                }
+                            }
+                    });
+                    FuncParamType {
+                        type_name: name.to_string(),
+                        package_name: imp.map(|imp| imp.source_path.clone()),
+                        scope: ReferenceScope::File,
+                    }
+                }
+                None => {
+                    // The type is either imported by name (e.g. `from .models import User as U`) or defined locally.
+                    let imp = pending_imports
+                        .iter()
+                        .find(|imp| imp.symbol.is_some() && imp.import_name() == type_name);
+                    match imp {
+                        Some(imp) => FuncParamType {
+                            type_name: imp.symbol.clone().unwrap(),
+                            package_name: Some(imp.source_path.clone()),
+                            scope: ReferenceScope::File,
+                        },
+                        None => FuncParamType {
+                            type_name,
+                            package_name: Some(file_node.name.clone()),
+                            scope: ReferenceScope::File,
+                        },
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Resolves the parameter types of the functions into the reference edges to the parsed type nodes.
+    pub fn resolve_func_param_type_edges(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        func_param_types: &HashMap<String, Vec<FuncParamType>>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for (func_node_name, param_types) in func_param_types {
+            let Some(func_node) = nodes.get(func_node_name) else {
+                continue;
+            };
+            for param_type in param_types {
+                let Some(file_node_name) = &param_type.package_name else {
+                    continue;
+                };
+                for param_type_node in common::find_scoped_type_nodes(
+                    nodes,
+                    file_node_name,
+                    param_type.scope,
+                    &param_type.type_name,
+                ) {
+                    edges.push(Edge {
+                        r#type: EdgeType::References,
+                        from: func_node.clone(),
+                        to: param_type_node.clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Returns the imported name and its alias (if any) of the given `dotted_name` or `aliased_import` node.
+    fn name_and_alias(ts_node: tree_sitter::Node, source_code: &[u8]) -> (String, Option<String>) {
+        let text = |node: Option<tree_sitter::Node>| {
+            node.and_then(|n| n.utf8_text(source_code).ok())
+                .unwrap_or("")
+                .to_string()
+        };
+        if ts_node.kind() == "aliased_import" {
+            (
+                text(ts_node.child_by_field_name("name")),
+                Some(text(ts_node.child_by_field_name("alias"))),
+            )
+        } else {
+            (text(Some(ts_node)), None)
+        }
+    }
+
+    /// Resolves the file (relative to the repository) of the given dotted module path, e.g. `pkg/models.py` for
+    /// `pkg.models`, or `pkg/__init__.py` if `pkg` is a package.
+    ///
+    /// Relative imports (e.g. `.models`) are resolved from the directory of the importing file, while absolute
+    /// ones are resolved from the repository root and then the directory of the importing file (e.g. for scripts).
+    /// Modules outside of the repository (e.g. the standard library) are not resolved, unless they are found in
+    /// the stub directories (also as `.pyi` files), in which case they are named by their absolute paths.
+    fn resolve_module_path(&self, file_path: &Path, module: &str) -> Option<String> {
+        let relative_module = module.trim_start_matches('.');
+        let level = module.len() - relative_module.len();
+
+        let file_dir = file_path.parent()?;
+        let base_dirs: Vec<&Path> = if level > 0 {
+            // One dot refers to the current package, and each additional dot refers to its parent package.
+            let mut dir = file_dir;
+            for _ in 1..level {
+                dir = dir.parent()?;
+            }
+            vec![dir]
+        } else {
+            let mut dirs = vec![self.repo_path.as_path(), file_dir];
+            dirs.extend(self.stub_paths.iter().map(|path| path.as_path()));
+            dirs
+        };
+
+        base_dirs.into_iter().find_map(|base_dir| {
+            let is_stub_path = self.stub_paths.iter().any(|path| path == base_dir);
+            let extensions: &[&str] = if is_stub_path {
+                &["py", "pyi"]
+            } else {
+                &["py"]
+            };
+            let module_path = base_dir.join(relative_module.replace('.', "/"));
+            let mut candidates: Vec<PathBuf> = extensions
+                .iter()
+                .map(|ext| module_path.join("__init__").with_extension(ext))
+                .collect();
+            if !relative_module.is_empty() {
+                for (i, ext) in extensions.iter().enumerate() {
+                    candidates.insert(i, module_path.with_extension(ext));
+                }
+            }
+            let path = candidates.into_iter().find(|path| path.is_file())?;
+            let path = match path.strip_prefix(&self.repo_path) {
+                Ok(path) => path,
+                Err(_) if is_stub_path => path.as_path(),
+                Err(_) => return None,
+            };
+            Some(path.to_string_lossy().to_string())
+        })
+    }
+
+    pub fn resolve_pending_imports(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for (file_node_name, pending_imports) in pending_imports {
+            for imp in pending_imports {
+                let file_node = nodes.get(file_node_name);
+                let imported_node = nodes.get(&imp.imported_node_name());
+                if let (Some(file_node), Some(imported_node)) = (file_node, imported_node) {
+                    edges.push(Edge {
+                        r#type: EdgeType::Imports,
+                        from: file_node.clone(),
+                        to: imported_node.clone(),
+                        import: imp.symbol.clone(),
+                        alias: imp.alias.clone(),
+                        properties: IndexMap::new(),
+                    })
+                }
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Same as `resolve_pending_imports`, but also looks up the imported nodes that are not parsed in the database.
+    ///
+    /// Mainly used when indexing a single file or a subdirectory.
+    pub fn resolve_pending_imports_from_db(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let names = pending_imports
+            .values()
+            .flatten()
+            .map(|imp| imp.imported_node_name());
+        let nodes = common::with_indexed_nodes(nodes, names, db)?;
+        self.resolve_pending_imports(&nodes, pending_imports)
+    }
+
+    /// Returns the pending imports whose imported nodes are not found.
+    pub fn unresolved_imports(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) -> Vec<UnresolvedImport> {
+        let mut unresolved_imports: Vec<UnresolvedImport> = Vec::new();
+
+        for (file_node_name, pending_imports) in pending_imports {
+            for imp in pending_imports {
+                if !nodes.contains_key(&imp.imported_node_name()) {
+                    unresolved_imports.push(UnresolvedImport {
+                        file: file_node_name.clone(),
+                        source_path: imp.source_path.clone(),
+                        symbol: imp.symbol.clone(),
+                    });
+                }
+            }
+        }
+
+        unresolved_imports
+    }
+
+    /// Returns the enclosing classes and functions of the given definition, from the outermost to the
+    /// innermost one, by walking up the tree.
+    fn enclosing_scopes(ts_node: tree_sitter::Node, source_code: &[u8]) -> Vec<(NodeType, String)> {
+        let mut scopes: Vec<(NodeType, String)> = Vec::new();
+        let mut parent = ts_node.parent();
+        while let Some(node) = parent {
+            let r#type = match node.kind() {
+                "class_definition" => Some(NodeType::Class),
+                "function_definition" => Some(NodeType::Function),
+                _ => None,
+            };
+            if let (Some(r#type), Some(name)) = (r#type, node.child_by_field_name("name")) {
+                scopes.push((
+                    r#type,
+                    name.utf8_text(source_code).unwrap_or("").to_string(),
+                ));
+            }
+            parent = node.parent();
+        }
+        scopes.reverse();
+        scopes
+    }
+}
+
+/// Extract types from a Python type annotation string
+///
+/// The type arguments of subscripted generics (e.g. `User` in `Dict[str, User]`) and the members of
+/// PEP 604 unions (e.g. `User | None`) are extracted as well. Forward references in quotes
+/// (e.g. `Optional["User"]`) are unquoted, while the values of `Literal[...]` are skipped.
+///
+/// # Arguments
+/// * `type_str` - Python type annotation string
+/// * `exclude_builtin` - Whether to exclude builtin types and `typing` constructs like str, List, Optional, etc.
+///
+/// # Returns
+/// * Array of extracted type strings
+pub fn extract_py_types(type_str: &str, exclude_builtin: bool) -> Vec<String> {
+    static LITERAL_RE: OnceLock<Regex> = OnceLock::new();
+    static NAME_RE: OnceLock<Regex> = OnceLock::new();
+    let literal_re = LITERAL_RE.get_or_init(|| Regex::new(r"Literal\[[^\]]*\]").unwrap());
+    let name_re = NAME_RE.get_or_init(|| {
+        Regex::new(r"[A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z_][A-Za-z0-9_]*)*").unwrap()
+    });
+
+    // The values of `Literal[...]` contain no type names, and quoted forward references are plain names.
+    let type_str = literal_re.replace_all(type_str, "Literal");
+    let type_str = type_str.replace(['"', '\''], "");
+
+    let mut result: Vec<String> = Vec::new();
+    for matched in name_re.find_iter(&type_str) {
+        let type_name = matched.as_str();
+        // `typing.List` is the same as `List`.
+        let short_name = type_name.rsplit('.').next().unwrap_or(type_name);
+        let is_builtin = util::is_builtin_type(&Language::Python, short_name)
+            && (type_name == short_name || type_name.starts_with("typing."));
+        if (!exclude_builtin || !is_builtin) && !result.iter().any(|t| t == type_name) {
+            result.push(type_name.to_string());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_py_types() {
+        let test_cases = vec![
+            ("User", vec!["User"]),
+            ("List[User]", vec!["User"]),
+            ("Dict[str, User]", vec!["User"]),
+            ("Optional[User]", vec!["User"]),
+            ("dict[str, list[User]]", vec!["User"]),
+            ("User | None", vec!["User"]),
+            ("User | Admin", vec!["User", "Admin"]),
+            ("Union[User, Admin, None]", vec!["User", "Admin"]),
+            ("Callable[[User], Address]", vec!["User", "Address"]),
+            ("typing.Optional[models.User]", vec!["models.User"]),
+            (r#"Optional["User"]"#, vec!["User"]),
+            (r#"Literal["admin", "guest"]"#, vec![]),
+            ("Tuple[User, User]", vec!["User"]),
+        ];
+
+        for (case, want) in test_cases {
+            assert_eq!(extract_py_types(case, true), want, "type string: {}", case);
+        }
+        assert_eq!(extract_py_types("List[User]", false), ["List", "User"]);
     }
 }
@@ -1,10 +1,21 @@
-(module (
-  class_definition
-    name: (identifier) @definition.class.name
-    superclasses: (
-      argument_list (
-        (identifier)
-      )
-    )* @definition.class.superclasses
-  ) @definition.class
-)
\ No newline at end of file
+; Pattern 0: Class Definitions
+(class_definition
+  name: (identifier) @definition.class.name
+  superclasses: (
+    argument_list (
+      (identifier)
+    )
+  )* @definition.class.superclasses
+) @definition.class
+
+; Pattern 1: Function Definitions (including methods)
+(function_definition
+  name: (identifier) @definition.function.name
+  body: (block) @definition.function.body
+) @definition.function
+
+; Pattern 2: Import Statements
+(import_statement) @reference.import
+
+; Pattern 3: Import From Statements
+(import_from_statement) @reference.import_from
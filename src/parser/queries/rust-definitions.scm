@@ -0,0 +1,57 @@
+; Pattern 0: Use Declarations
+(use_declaration
+  argument: (_) @reference.import.path
+)
+
+; Pattern 1: Interface Declarations (i.e. Rust Traits)
+(trait_item
+  name: (type_identifier) @definition.interface.name
+) @definition.interface
+
+; Pattern 2: Class Declarations (i.e. Rust Structs)
+(struct_item
+  name: (type_identifier) @definition.class.name
+) @definition.class
+
+; Pattern 3: Enum Declarations
+(enum_item
+  name: (type_identifier) @definition.enum.name
+) @definition.enum
+
+; Pattern 4: Function Declarations (at the top level or in inline modules)
+[
+  (source_file
+    (function_item
+      name: (identifier) @definition.function.name
+      body: (block) @definition.function.body
+    ) @definition.function
+  )
+  (mod_item
+    body: (declaration_list
+      (function_item
+        name: (identifier) @definition.function.name
+        body: (block) @definition.function.body
+      ) @definition.function
+    )
+  )
+]
+
+; Pattern 5: Method Declarations (i.e. Functions in Impl Blocks)
+(impl_item
+  type: [
+    (type_identifier) @definition.method.receiver_type
+    (generic_type type: (type_identifier) @definition.method.receiver_type)
+  ]
+  body: (declaration_list
+    (function_item
+      name: (identifier) @definition.method.name
+      body: (block) @definition.method.body
+    ) @definition.method
+  )
+)
+
+; Pattern 6: Namespace Declarations (i.e. Inline Rust Modules)
+(mod_item
+  name: (identifier) @definition.namespace.name
+  body: (declaration_list) @definition.namespace.body
+) @definition.namespace
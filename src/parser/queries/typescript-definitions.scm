@@ -1,25 +1,36 @@
 ; Pattern 0: Import Statements
-(import_statement
-  (import_clause [
-    (
-      namespace_import (identifier) @reference.namespace_import.alias
-    )
-    (
-      named_imports (
-        import_specifier [
-          name: (identifier) @reference.named_import.name
-          alias: (identifier) @reference.named_import.alias
-        ]
+[
+  (import_statement
+    (import_clause [
+      (
+        namespace_import (identifier) @reference.namespace_import.alias
+      )
+      (
+        named_imports (
+          import_specifier [
+            name: (identifier) @reference.named_import.name
+            alias: (identifier) @reference.named_import.alias
+          ]
+        )
       )
+      (
+        (identifier) @reference.default_import.alias
+      )
+    ])
+    source: (
+      string (string_fragment) @reference.import.source
     )
-    (
-      (identifier) @reference.default_import.alias
+  )
+  ; import X = require('Y')
+  (import_statement
+    (import_require_clause
+      (identifier) @reference.namespace_import.alias
+      source: (
+        string (string_fragment) @reference.import.source
+      )
     )
-  ])
-  source: (
-    string (string_fragment) @reference.import.source
   )
-)
+]
 
 ; Pattern 1: Interface Declarations
 (interface_declaration
@@ -105,4 +116,74 @@
 ; Pattern 6: Type Alias Declarations
 (type_alias_declaration
   name: (type_identifier) @definition.type_alias.name
-) @definition.type_alias
\ No newline at end of file
+) @definition.type_alias
+
+; Pattern 7: Namespace Declarations
+[
+  (internal_module
+    name: (_) @definition.namespace.name
+    body: (statement_block) @definition.namespace.body
+  )
+  (module
+    name: [(identifier) (nested_identifier)] @definition.namespace.name
+    body: (statement_block) @definition.namespace.body
+  )
+] @definition.namespace
+
+; Pattern 8: CommonJS Requires
+(variable_declarator
+  name: [
+    (identifier) @reference.namespace_import.alias
+    (object_pattern
+      (shorthand_property_identifier_pattern) @reference.named_import.name
+    )
+    (object_pattern
+      (pair_pattern
+        key: (property_identifier) @reference.named_import.name
+        value: (identifier) @reference.named_import.alias
+      )
+    )
+  ]
+  value: (
+    call_expression
+      function: (identifier) @reference.require.function
+      arguments: (
+        arguments (string (string_fragment) @reference.import.source)
+      )
+  )
+  (#eq? @reference.require.function "require")
+)
+
+; Pattern 9: Function Declarations without Return Types (e.g. in JavaScript)
+(function_declaration
+  name: (identifier) @definition.function.name
+  parameters: (
+    (formal_parameters
+      [
+        (required_parameter
+          type: (_) @definition.function.param_type
+        )?
+        (optional_parameter
+          type: (_) @definition.function.param_type
+        )?
+      ]
+    )
+  )
+  !return_type
+  body: (statement_block) @definition.function.body
+) @definition.function
+
+; Pattern 10: Re-exports
+; export { X } from 'Y'
+; export { X as x } from 'Y'
+(export_statement
+  (export_clause
+    (export_specifier
+      name: (identifier) @reference.reexport.name
+      alias: (identifier)? @reference.reexport.alias
+    )
+  )
+  source: (
+    string (string_fragment) @reference.import.source
+  )
+)
@@ -0,0 +1,636 @@
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use strum_macros;
+use tree_sitter;
+use tree_sitter::StreamingIterator;
+use tree_sitter_rust;
+
+use super::common;
+use super::common::PendingImport;
+use crate::{Database, File, UnresolvedImport};
+use crate::{Edge, EdgeType, Language, Node, NodeType};
+
+/// The tree-sitter definition query source for Rust.
+pub const RUST_DEFINITIONS_QUERY_SOURCE: &str = include_str!("queries/rust-definitions.scm");
+
+/// Tree-sitter query patterns.
+///
+/// Note that the order of these variants must match the order of the patterns in the query source file.
+#[derive(Debug, Clone, PartialEq, Eq, strum_macros::FromRepr)]
+enum QueryPattern {
+    Import,
+    Interface,
+    Class,
+    Enum,
+    Function,
+    Method,
+    Namespace,
+}
+
+pub struct Parser {
+    repo_path: PathBuf,
+    skeleton_body_lines: usize,
+}
+
+impl Parser {
+    pub fn new(repo_path: PathBuf, skeleton_body_lines: usize) -> Self {
+        Self {
+            repo_path,
+            skeleton_body_lines,
+        }
+    }
+
+    pub fn parse(
+        &self,
+        file_node: &Node,
+        file: &File,
+    ) -> Result<
+        (IndexMap<String, Node>, Vec<Edge>, Vec<PendingImport>),
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
+        let query_source = RUST_DEFINITIONS_QUERY_SOURCE.to_string();
+        let mut nodes: IndexMap<String, Node> = IndexMap::new();
+        let mut edges: Vec<Edge> = Vec::new();
+        let mut pending_imports: Vec<PendingImport> = Vec::new();
+
+        let source_code = file.content;
+        let file_name = Path::new(&file.path)
+            .strip_prefix(&self.repo_path)
+            .unwrap_or_else(|_| Path::new(&file.path))
+            .to_string_lossy()
+            .to_string();
+
+        let mut parser = tree_sitter::Parser::new();
+        let language = &tree_sitter_rust::LANGUAGE.into();
+        parser
+            .set_language(language)
+            .expect("Error loading language parser");
+
+        let tree = parser.parse(source_code, None).unwrap();
+        let root_node = tree.root_node();
+
+        let mut cursor = tree_sitter::QueryCursor::new();
+        let query = tree_sitter::Query::new(language, &query_source).unwrap();
+        let mut matches = cursor.matches(&query, root_node, source_code);
+
+        while let Some(mat) = matches.next() {
+            let Some(pattern) = QueryPattern::from_repr(mat.pattern_index) else {
+                continue;
+            };
+            match pattern {
+                QueryPattern::Import => {
+                    for capture in mat.captures {
+                        let capture_name = query.capture_names()[capture.index as usize];
+                        let capture_node_text: String = capture
+                            .node
+                            .utf8_text(&source_code)
+                            .unwrap_or("")
+                            .to_string();
+                        common::log_capture(&capture, capture_name, &capture_node_text);
+
+                        if capture_name != "reference.import.path" {
+                            continue;
+                        }
+                        // E.g. `use crate::models::User as U;`
+                        let (use_path, alias) = if capture.node.kind() == "use_as_clause" {
+                            let path = capture.node.child_by_field_name("path");
+                            let alias = capture.node.child_by_field_name("alias");
+                            (
+                                path.and_then(|n| n.utf8_text(source_code).ok())
+                                    .unwrap_or("")
+                                    .to_string(),
+                                alias
+                                    .and_then(|n| n.utf8_text(source_code).ok())
+                                    .map(|s| s.to_string()),
+                            )
+                        } else {
+                            (capture_node_text.clone(), None)
+                        };
+
+                        let Some(mod_file_path) = self.resolve_use_path(&file.path, &use_path)
+                        else {
+                            continue;
+                        };
+                        let mod_file_name = mod_file_path.to_string_lossy().to_string();
+                        // Multiple declarations may import from the same module.
+                        if mod_file_name == file_node.name
+                            || pending_imports
+                                .iter()
+                                .any(|imp| imp.source_path == mod_file_name)
+                        {
+                            continue;
+                        }
+                        // The module file may be ignored, so it's resolved after all the files are parsed.
+                        pending_imports.push(PendingImport {
+                            language: Language::Rust,
+                            source_path: mod_file_name,
+                            symbol: None,
+                            alias,
+                            reexport: false,
+                        });
+                    }
+                }
+
+                QueryPattern::Interface => {
+                    let current_node = common::parse_simple_interface(
+                        &query,
+                        &mat,
+                        &self.repo_path,
+                        file_node,
+                        &file.path,
+                        &source_code,
+                    );
+                    if let Some(curr_node) = current_node {
+                        nodes.insert(curr_node.name.clone(), curr_node.clone());
+                        edges.push(Edge {
+                            r#type: EdgeType::Contains,
+                            from: file_node.clone(),
+                            to: curr_node.clone(),
+                            import: None,
+                            alias: None,
+                            properties: IndexMap::new(),
+                        });
+
+                        // Parse the methods of the trait.
+                        let trait_ts_node = mat.captures.iter().find(|c| {
+                            query.capture_names()[c.index as usize] == "definition.interface"
+                        });
+                        if let Some(trait_ts_node) = trait_ts_node {
+                            let method_nodes = self.parse_trait_methods(
+                                &curr_node,
+                                trait_ts_node.node,
+                                source_code,
+                            );
+                            for method_node in method_nodes {
+                                nodes.insert(method_node.name.clone(), method_node.clone());
+                                edges.push(Edge {
+                                    r#type: EdgeType::Contains,
+                                    from: curr_node.clone(),
+                                    to: method_node,
+                                    import: None,
+                                    alias: None,
+                                    properties: IndexMap::new(),
+                                });
+                            }
+                        }
+                    }
+                }
+
+                QueryPattern::Class | QueryPattern::Enum => {
+                    let current_node = if pattern == QueryPattern::Class {
+                        common::parse_simple_class(
+                            &query,
+                            &mat,
+                            &self.repo_path,
+                            file_node,
+                            &file.path,
+                            &source_code,
+                        )
+                    } else {
+                        common::parse_simple_enum(
+                            &query,
+                            &mat,
+                            &self.repo_path,
+                            file_node,
+                            &file.path,
+                            &source_code,
+                        )
+                    };
+                    if let Some(curr_node) = current_node {
+                        nodes.insert(curr_node.name.clone(), curr_node.clone());
+                        edges.push(Edge {
+                            r#type: EdgeType::Contains,
+                            from: file_node.clone(),
+                            to: curr_node.clone(),
+                            import: None,
+                            alias: None,
+                            properties: IndexMap::new(),
+                        });
+                    }
+                }
+
+                QueryPattern::Function | QueryPattern::Method => {
+                    let mut current_node: Option<Node> = None;
+                    let mut current_tree_sitter_main_node: Option<tree_sitter::Node> = None;
+                    let mut receiver_type_name: Option<String> = None;
+
+                    for capture in mat.captures {
+                        let capture_name = query.capture_names()[capture.index as usize];
+                        let capture_node_text: String = capture
+                            .node
+                            .utf8_text(&source_code)
+                            .unwrap_or("")
+                            .to_string();
+                        common::log_capture(&capture, capture_name, &capture_node_text);
+
+                        match capture_name {
+                            "definition.function" | "definition.method" => {
+                                current_node = Some(Node {
+                                    name: "".to_string(), // fill in later
+                                    r#type: NodeType::Function,
+                                    language: file_node.language.clone(),
+                                    start_line: capture.node.start_position().row,
+                                    end_line: capture.node.end_position().row,
+                                    code: capture_node_text,
+                                    ..Default::default()
+                                });
+                                current_tree_sitter_main_node = Some(capture.node);
+                            }
+                            "definition.function.name" | "definition.method.name" => {
+                                if let Some(curr_node) = &mut current_node {
+                                    curr_node.name = format!("{}:{}", file_name, capture_node_text);
+                                }
+                            }
+                            "definition.method.receiver_type" => {
+                                receiver_type_name = Some(capture_node_text);
+                            }
+                            "definition.function.body" | "definition.method.body" => {
+                                if let (Some(main_node), Some(curr_node)) =
+                                    (current_tree_sitter_main_node, &mut current_node)
+                                {
+                                    // Skip the body and keep only the signature.
+                                    curr_node.skeleton_code = common::skeleton_code(
+                                        source_code,
+                                        main_node.start_byte(),
+                                        capture.node,
+                                        self.skeleton_body_lines,
+                                        "{\n...\n}",
+                                    );
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    let (Some(mut curr_node), Some(main_node)) =
+                        (current_node, current_tree_sitter_main_node)
+                    else {
+                        continue;
+                    };
+
+                    let parent_node = match &receiver_type_name {
+                        Some(receiver_type_name) => {
+                            // Change the name of the method to include the type name, along with the name
+                            // of the implemented trait, if any, e.g. `User.Display.fmt` for `impl fmt::Display
+                            // for User`, since the traits may have methods of the same name.
+                            let method_name = curr_node.name.rsplit(':').next().unwrap_or("");
+                            let trait_name = main_node
+                                .parent()
+                                .and_then(|body| body.parent())
+                                .and_then(|impl_node| impl_node.child_by_field_name("trait"))
+                                .and_then(|trait_node| Self::type_name(trait_node, source_code));
+                            curr_node.name = match trait_name {
+                                Some(trait_name) => format!(
+                                    "{}:{}.{}.{}",
+                                    file_name, receiver_type_name, trait_name, method_name
+                                ),
+                                None => {
+                                    format!("{}:{}.{}", file_name, receiver_type_name, method_name)
+                                }
+                            };
+                            // Assume that the struct (or enum) node is defined early in the current file,
+                            // otherwise (e.g. it is defined in another file) the file contains the method.
+                            nodes
+                                .get(&format!("{}:{}", file_name, receiver_type_name))
+                                .cloned()
+                                .unwrap_or_else(|| file_node.clone())
+                        }
+                        None => {
+                            let namespace = Self::enclosing_namespace(main_node, source_code);
+                            Self::nest_in_namespace(&nodes, file_node, &namespace, &mut curr_node)
+                        }
+                    };
+
+                    // The remaining duplicates (e.g. `from` in both `impl From<A> for User` and
+                    // `impl From<B> for User`) are numbered in source order, e.g. `User.From.from#2`.
+                    if nodes.contains_key(&curr_node.name) {
+                        let mut index = 2;
+                        while nodes.contains_key(&format!("{}#{}", curr_node.name, index)) {
+                            index += 1;
+                        }
+                        curr_node.name = format!("{}#{}", curr_node.name, index);
+                    }
+
+                    nodes.insert(curr_node.name.clone(), curr_node.clone());
+                    edges.push(Edge {
+                        r#type: EdgeType::Contains,
+                        from: parent_node,
+                        to: curr_node,
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+
+                QueryPattern::Namespace => {
+                    let mut current_node: Option<Node> = None;
+                    let mut current_tree_sitter_main_node: Option<tree_sitter::Node> = None;
+
+                    for capture in mat.captures {
+                        let capture_name = query.capture_names()[capture.index as usize];
+                        let capture_node_text: String = capture
+                            .node
+                            .utf8_text(&source_code)
+                            .unwrap_or("")
+                            .to_string();
+                        common::log_capture(&capture, capture_name, &capture_node_text);
+
+                        match capture_name {
+                            "definition.namespace" => {
+                                current_node = Some(Node {
+                                    name: "".to_string(), // fill in later
+                                    r#type: NodeType::Namespace,
+                                    language: file_node.language.clone(),
+                                    start_line: capture.node.start_position().row,
+                                    end_line: capture.node.end_position().row,
+                                    code: capture_node_text,
+                                    ..Default::default()
+                                });
+                                current_tree_sitter_main_node = Some(capture.node);
+                            }
+                            "definition.namespace.name" => {
+                                if let Some(curr_node) = &mut current_node {
+                                    curr_node.name = format!("{}:{}", file_name, capture_node_text);
+                                }
+                            }
+                            "definition.namespace.body" => {
+                                if let (Some(main_node), Some(curr_node)) =
+                                    (current_tree_sitter_main_node, &mut current_node)
+                                {
+                                    // Skip the body and keep only the signature.
+                                    curr_node.skeleton_code = common::skeleton_code(
+                                        source_code,
+                                        main_node.start_byte(),
+                                        capture.node,
+                                        self.skeleton_body_lines,
+                                        "{ ... }",
+                                    );
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    let (Some(mut curr_node), Some(main_node)) =
+                        (current_node, current_tree_sitter_main_node)
+                    else {
+                        continue;
+                    };
+                    let namespace = Self::enclosing_namespace(main_node, source_code);
+                    let parent_node =
+                        Self::nest_in_namespace(&nodes, file_node, &namespace, &mut curr_node);
+                    if !nodes.contains_key(&curr_node.name) {
+                        nodes.insert(curr_node.name.clone(), curr_node.clone());
+                        edges.push(Edge {
+                            r#type: EdgeType::Contains,
+                            from: parent_node,
+                            to: curr_node,
+                            import: None,
+                            alias: None,
+                            properties: IndexMap::new(),
+                        });
+                    }
+                }
+            }
+        }
+
+        common::mark_exported_definitions(
+            &file_node.language,
+            &mut nodes,
+            tree.root_node(),
+            source_code,
+        );
+
+        Ok((nodes, edges, pending_imports))
+    }
+
+    /// Resolves the pending imports to the module files that have been parsed.
+    pub fn resolve_pending_imports(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for (file_node_name, pending_imports) in pending_imports {
+            let Some(file_node) = nodes.get(file_node_name) else {
+                continue;
+            };
+            for imp in pending_imports {
+                if let Some(imported_node) = nodes.get(&imp.imported_node_name()) {
+                    edges.push(Edge {
+                        r#type: EdgeType::Imports,
+                        from: file_node.clone(),
+                        to: imported_node.clone(),
+                        import: None,
+                        alias: imp.alias.clone(),
+                        properties: IndexMap::new(),
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Same as `resolve_pending_imports`, but also looks up the module files that are not parsed in the database.
+    ///
+    /// Mainly used when indexing a single file or a subdirectory.
+    pub fn resolve_pending_imports_from_db(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let names = pending_imports
+            .values()
+            .flatten()
+            .map(|imp| imp.imported_node_name());
+        let nodes = common::with_indexed_nodes(nodes, names, db)?;
+        Ok(self.resolve_pending_imports(&nodes, pending_imports))
+    }
+
+    /// Returns the pending imports whose module files are not parsed.
+    pub fn unresolved_imports(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) -> Vec<UnresolvedImport> {
+        let mut unresolved_imports: Vec<UnresolvedImport> = Vec::new();
+
+        for (file_node_name, pending_imports) in pending_imports {
+            for imp in pending_imports {
+                if !nodes.contains_key(&imp.imported_node_name()) {
+                    unresolved_imports.push(UnresolvedImport {
+                        file: file_node_name.clone(),
+                        source_path: imp.source_path.clone(),
+                        symbol: None,
+                    });
+                }
+            }
+        }
+
+        unresolved_imports
+    }
+
+    /// Re-derives the skeleton code of the given function node from its code.
+    pub fn derive_skeleton_code(&self, node: &Node) -> Option<String> {
+        if node.r#type != NodeType::Function {
+            return None;
+        }
+        common::derive_skeleton_code(
+            &tree_sitter_rust::LANGUAGE.into(),
+            "",
+            &node.code,
+            &["block"],
+            self.skeleton_body_lines,
+            "{\n...\n}",
+        )
+    }
+
+    /// Returns the name of the given type node without its path and generic arguments, e.g. `From` for
+    /// `convert::From<String>`.
+    fn type_name(ts_node: tree_sitter::Node, source_code: &[u8]) -> Option<String> {
+        match ts_node.kind() {
+            "generic_type" => Self::type_name(ts_node.child_by_field_name("type")?, source_code),
+            "scoped_type_identifier" => {
+                Self::type_name(ts_node.child_by_field_name("name")?, source_code)
+            }
+            _ => ts_node.utf8_text(source_code).ok().map(|s| s.to_string()),
+        }
+    }
+
+    /// Returns the dot-separated name of the inline modules enclosing the given tree-sitter node, if any.
+    ///
+    /// e.g. `helper` in `mod a { mod b { fn helper() {} } }` => `a.b`
+    fn enclosing_namespace(ts_node: tree_sitter::Node, source_code: &[u8]) -> Option<String> {
+        let mut names: Vec<String> = Vec::new();
+
+        let mut parent = ts_node.parent();
+        while let Some(node) = parent {
+            if node.kind() == "mod_item" {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    names.push(name_node.utf8_text(source_code).unwrap_or("").to_string());
+                }
+            }
+            parent = node.parent();
+        }
+
+        if names.is_empty() {
+            return None;
+        }
+        names.reverse();
+        Some(names.join("."))
+    }
+
+    /// Prefixes the name of the given definition node with its enclosing modules (if any),
+    /// and returns the node which contains the definition (i.e. the namespace node or the file node).
+    fn nest_in_namespace(
+        nodes: &IndexMap<String, Node>,
+        file_node: &Node,
+        namespace: &Option<String>,
+        node: &mut Node,
+    ) -> Node {
+        let Some(namespace) = namespace else {
+            return file_node.clone();
+        };
+
+        if let Some((file_name, name)) = node.name.rsplit_once(':') {
+            node.name = format!("{}:{}.{}", file_name, namespace, name);
+        }
+
+        let namespace_node_name = format!("{}:{}", file_node.name, namespace);
+        nodes.get(&namespace_node_name).cloned().unwrap_or_else(|| {
+            Node::from_type_and_name(NodeType::Namespace, namespace_node_name.clone())
+        })
+    }
+
+    /// Returns the methods declared (or provided) by the given `trait_item` node, e.g. `area` in
+    /// `trait Shape { fn area(&self) -> f64; }`.
+    fn parse_trait_methods(
+        &self,
+        trait_node: &Node,
+        trait_ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> Vec<Node> {
+        let mut method_nodes: Vec<Node> = Vec::new();
+
+        let Some(body) = trait_ts_node.child_by_field_name("body") else {
+            return method_nodes;
+        };
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            if !["function_signature_item", "function_item"].contains(&child.kind()) {
+                // Skip associated types and constants.
+                continue;
+            }
+            let Some(name) = child.child_by_field_name("name") else {
+                continue;
+            };
+            let method_name = name.utf8_text(source_code).unwrap_or("");
+            let code = child.utf8_text(source_code).unwrap_or("").to_string();
+            // Provided methods have a body to skip, while required ones are signatures only.
+            let skeleton_code = match child.child_by_field_name("body") {
+                Some(body) => common::skeleton_code(
+                    source_code,
+                    child.start_byte(),
+                    body,
+                    self.skeleton_body_lines,
+                    "{\n...\n}",
+                ),
+                None => code.clone(),
+            };
+            method_nodes.push(Node {
+                name: format!("{}.{}", trait_node.name, method_name),
+                r#type: NodeType::Function,
+                language: trait_node.language.clone(),
+                start_line: child.start_position().row,
+                end_line: child.end_position().row,
+                code,
+                skeleton_code,
+                ..Default::default()
+            });
+        }
+
+        method_nodes
+    }
+
+    /// Resolves the file (relative to the repository) of the module imported by the given `use` path,
+    /// e.g. `src/models.rs` for `crate::models::{Shape, User}`.
+    ///
+    /// Only the paths starting with `crate::` are resolved, since the others refer to external crates or
+    /// depend on the module tree (e.g. `super::`). The longest prefix of the path that names a module file
+    /// (i.e. `<path>.rs` or `<path>/mod.rs` in the `src` directory of the crate) wins.
+    fn resolve_use_path(&self, file_path: &Path, use_path: &str) -> Option<PathBuf> {
+        let use_path = use_path.strip_prefix("crate::")?;
+        // Drop the trailing use list or glob, e.g. `models::{Shape, User}` => `models::`.
+        let use_path = use_path.split(['{', '*']).next().unwrap_or("");
+        let segments: Vec<&str> = use_path
+            .split("::")
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        // The crate root is the closest directory with a `Cargo.toml` within the repository.
+        let crate_dir = file_path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.repo_path))
+            .find(|dir| dir.join("Cargo.toml").is_file())?;
+        let src_dir = crate_dir.join("src");
+
+        (1..=segments.len()).rev().find_map(|n| {
+            let module_path = src_dir.join(segments[..n].join("/"));
+            [module_path.with_extension("rs"), module_path.join("mod.rs")]
+                .into_iter()
+                .find(|path| path.is_file())
+                .map(|path| {
+                    path.strip_prefix(&self.repo_path)
+                        .map(|p| p.to_path_buf())
+                        .unwrap_or(path)
+                })
+        })
+    }
+}
@@ -4,22 +4,38 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use strum_macros;
 use tree_sitter;
 use tree_sitter::StreamingIterator;
 use tree_sitter_typescript;
 
 use super::common;
-use super::common::PendingImport;
+use super::common::{PendingCall, PendingImport, PendingInherit};
 use crate::util;
 use crate::Database;
-use crate::{Edge, EdgeType, Language, Node, NodeType};
-use crate::{File, FuncParamType};
+use crate::{AccessorKind, Edge, EdgeType, Language, Node, NodeType};
+use crate::{File, FuncParamType, ReferenceScope, UnresolvedImport};
 
 /// The tree-sitter definition query source for TypeScript.
 pub const TYPESCRIPT_DEFINITIONS_QUERY_SOURCE: &str =
     include_str!("queries/typescript-definitions.scm");
 
+/// The maximum number of re-exports followed for a type, which guards against cyclic re-exports.
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Returns the tree-sitter grammar for the file at the given path, i.e. TSX for `.tsx` files, and TypeScript
+/// for the others.
+///
+/// JavaScript files (i.e. `.js` and `.jsx`) are parsed as TSX too, since the syntax of JavaScript (including JSX)
+/// is a subset of TSX, which makes the TypeScript queries work for them.
+pub fn grammar_for_path(path: &Path) -> tree_sitter::Language {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("tsx") | Some("js") | Some("jsx") => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        _ => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+    }
+}
+
 /// Tree-sitter query patterns.
 ///
 /// Note that the order of these variants must match the order of the patterns in the query source file.
@@ -32,16 +48,38 @@ enum QueryPattern {
     Method,
     Enum,
     TypeAlias,
+    Namespace,
+    Require,
+    UntypedFunction,
+    ReExport,
 }
 
 pub struct Parser {
     repo_path: PathBuf,
+    reference_scope: ReferenceScope,
+    skeleton_body_lines: usize,
+    case_insensitive_imports: bool,
+    // The workspace packages keyed by package name, which are only collected on the first non-relative import.
+    workspace_packages: OnceLock<HashMap<String, PathBuf>>,
+    // The directories where the non-relative imports outside of the repository are looked up.
+    stub_paths: Vec<PathBuf>,
 }
 
 impl Parser {
-    pub fn new(repo_path: PathBuf) -> Self {
+    pub fn new(
+        repo_path: PathBuf,
+        reference_scope: ReferenceScope,
+        skeleton_body_lines: usize,
+        case_insensitive_imports: bool,
+        stub_paths: Vec<PathBuf>,
+    ) -> Self {
         Self {
             repo_path: repo_path.clone(),
+            reference_scope,
+            skeleton_body_lines,
+            case_insensitive_imports,
+            workspace_packages: OnceLock::new(),
+            stub_paths,
         }
     }
 
@@ -55,21 +93,27 @@ impl Parser {
             Vec<Edge>,
             Vec<PendingImport>,
             Option<HashMap<String, Vec<FuncParamType>>>,
+            Vec<PendingCall>,
+            Vec<PendingInherit>,
         ),
-        Box<dyn std::error::Error>,
+        Box<dyn std::error::Error + Send + Sync>,
     > {
         let query_source = TYPESCRIPT_DEFINITIONS_QUERY_SOURCE.to_string();
         let mut nodes: IndexMap<String, Node> = IndexMap::new();
         let mut edges: Vec<Edge> = Vec::new();
         let mut pending_imports: Vec<PendingImport> = Vec::new();
+        let mut pending_calls: Vec<PendingCall> = Vec::new();
+        // (class node name, base type name) of the `extends` and `implements` clauses
+        let mut class_bases: Vec<(String, String)> = Vec::new();
+        let mut pending_inherits: Vec<PendingInherit> = Vec::new();
         let mut func_param_types: HashMap<String, Vec<FuncParamType>> = HashMap::new();
 
-        let mut import_name_to_source_path: HashMap<String, String> = HashMap::new(); // Maps import names to their corresponding source paths
+        let mut import_name_to_import: HashMap<String, PendingImport> = HashMap::new(); // Maps import names to their corresponding imports
 
         let source_code = file.content;
 
         let mut parser = tree_sitter::Parser::new();
-        let language = &tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let language = &grammar_for_path(file.path);
         parser
             .set_language(language)
             .expect("Error loading language parser");
@@ -84,12 +128,13 @@ impl Parser {
         while let Some(mat) = matches.next() {
             if let Some(pattern) = QueryPattern::from_repr(mat.pattern_index) {
                 match pattern {
-                    QueryPattern::Import => {
+                    QueryPattern::Import | QueryPattern::Require | QueryPattern::ReExport => {
                         let mut import = PendingImport {
                             language: Language::TypeScript,
                             source_path: "".to_string(),
                             symbol: None,
                             alias: None,
+                            reexport: pattern == QueryPattern::ReExport,
                         };
 
                         for capture in mat.captures {
@@ -104,15 +149,19 @@ impl Parser {
                             match capture_name {
                                 "reference.namespace_import.alias" => {
                                     // import * as X from 'Y' => X
+                                    // import X = require('Y') => X
+                                    // const X = require('Y') => X
                                     import.alias = Some(capture_node_text);
                                 }
                                 "reference.named_import.name" => {
                                     // import { X } from 'Y' => X
                                     // import { X as x } from 'Y' => X
+                                    // const { X, X: x } = require('Y') => X
                                     import.symbol = Some(capture_node_text);
                                 }
                                 "reference.named_import.alias" => {
                                     // import { X as x } from 'Y' => x
+                                    // const { X: x } = require('Y') => x
                                     import.alias = Some(capture_node_text);
                                 }
                                 "reference.default_import.alias" => {
@@ -120,43 +169,39 @@ impl Parser {
                                     import.symbol = Some("export default".to_string()); // a special symbol to represent the default export
                                     import.alias = Some(capture_node_text);
                                 }
+                                "reference.reexport.name" => {
+                                    // export { X } from 'Y' => X
+                                    // export { X as x } from 'Y' => X
+                                    import.symbol = Some(capture_node_text);
+                                }
+                                "reference.reexport.alias" => {
+                                    // export { X as x } from 'Y' => x
+                                    import.alias = Some(capture_node_text);
+                                }
                                 "reference.import.source" => {
                                     // import X from 'Y' => Y
                                     // import { X } from 'Y' => Y
                                     // import * as X from 'Y' => Y
 
-                                    // Only handle relative imports for now.
-                                    if capture_node_text.starts_with("./")
+                                    // Handle relative imports, imports of the workspace packages in the repository,
+                                    // and imports of the stubbed modules.
+                                    let import_file_path = if capture_node_text.starts_with("./")
                                         || capture_node_text.starts_with("../")
                                     {
                                         // Get the absolute path of the imported file.
                                         let current_file_dir = file.path.parent().unwrap();
                                         let import_path = Path::new(&capture_node_text);
-                                        let mut import_file_path =
-                                            current_file_dir.join(import_path);
-
-                                        // If the import path is a directory, append 'index.d.ts', 'index.ts' or 'index.js' to it
-                                        if import_file_path.is_dir() {
-                                            let index_d_ts = import_file_path.join("index.d.ts");
-                                            let index_ts = import_file_path.join("index.ts");
-                                            let index_js = import_file_path.join("index.js");
-                                            if index_d_ts.exists() {
-                                                import_file_path = index_d_ts;
-                                            } else if index_ts.exists() {
-                                                import_file_path = index_ts;
-                                            } else if index_js.exists() {
-                                                import_file_path = index_js;
-                                            }
-                                        } else {
-                                            let file_ts = import_file_path.with_extension("ts");
-                                            let file_js = import_file_path.with_extension("js");
-                                            if file_ts.exists() {
-                                                import_file_path = file_ts;
-                                            } else if file_js.exists() {
-                                                import_file_path = file_js;
-                                            }
-                                        }
-
+                                        Some(self.resolve_import_path(
+                                            current_file_dir.join(import_path),
+                                        ))
+                                    } else {
+                                        self.resolve_workspace_import_path(&capture_node_text)
+                                            .or_else(|| {
+                                                self.resolve_stub_import_path(&capture_node_text)
+                                            })
+                                    };
+
+                                    if let Some(mut import_file_path) = import_file_path {
                                         // Remove ./ or ../ from the import path
                                         let canonical_file_path = import_file_path
                                             .canonicalize()
@@ -177,8 +222,10 @@ impl Parser {
                         if !import.source_path.is_empty() {
                             pending_imports.push(import.clone());
 
-                            import_name_to_source_path
-                                .insert(import.import_name(), import.source_path.clone());
+                            // The re-exported names are not visible within the current file.
+                            if !import.reexport {
+                                import_name_to_import.insert(import.import_name(), import.clone());
+                            }
                         }
                     }
 
@@ -191,14 +238,23 @@ impl Parser {
                             &file.path,
                             &source_code,
                         );
-                        if let Some(curr_node) = current_node {
+                        if let Some(mut curr_node) = current_node {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
+                            let parent_node = Self::nest_in_namespace(
+                                &nodes,
+                                file_node,
+                                &namespace,
+                                &mut curr_node,
+                            );
                             nodes.insert(curr_node.name.clone(), curr_node.clone());
                             edges.push(Edge {
                                 r#type: EdgeType::Contains,
-                                from: file_node.clone(),
+                                from: parent_node,
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
@@ -225,7 +281,7 @@ impl Parser {
                                         start_line: capture.node.start_position().row,
                                         end_line: capture.node.end_position().row,
                                         code: capture_node_text,
-                                        skeleton_code: String::new(),
+                                        ..Default::default()
                                     });
                                     current_tree_sitter_main_node = Some(capture.node);
                                 }
@@ -242,15 +298,15 @@ impl Parser {
                                     if let Some(current_tree_sitter_main_node) =
                                         current_tree_sitter_main_node
                                     {
-                                        let start_byte = current_tree_sitter_main_node.start_byte();
-                                        let body_start_byte = capture.node.start_byte();
                                         if let Some(curr_node) = &mut current_node {
                                             // Skip the body and keep only the signature.
-                                            curr_node.skeleton_code = String::from_utf8_lossy(
-                                                &source_code[start_byte..body_start_byte],
-                                            )
-                                            .to_string()
-                                                + "{ ... }";
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{ ... }",
+                                            );
                                         }
                                     }
                                 }
@@ -258,19 +314,34 @@ impl Parser {
                             }
                         }
 
-                        if let Some(curr_node) = current_node {
+                        if let Some(mut curr_node) = current_node {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
+                            let parent_node = Self::nest_in_namespace(
+                                &nodes,
+                                file_node,
+                                &namespace,
+                                &mut curr_node,
+                            );
                             nodes.insert(curr_node.name.clone(), curr_node.clone());
                             edges.push(Edge {
                                 r#type: EdgeType::Contains,
-                                from: file_node.clone(),
+                                from: parent_node,
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
+
+                            if let Some(main_node) = current_tree_sitter_main_node {
+                                for base_name in Self::class_bases(main_node, source_code) {
+                                    class_bases.push((curr_node.name.clone(), base_name));
+                                }
+                            }
                         }
                     }
 
-                    QueryPattern::Function => {
+                    QueryPattern::Function | QueryPattern::UntypedFunction => {
                         let mut current_node: Option<Node> = None;
                         let mut current_tree_sitter_main_node: Option<tree_sitter::Node> = None;
                         let mut param_type_names: Vec<String> = Vec::new();
@@ -293,7 +364,7 @@ impl Parser {
                                         start_line: capture.node.start_position().row,
                                         end_line: capture.node.end_position().row,
                                         code: capture_node_text,
-                                        skeleton_code: String::new(),
+                                        ..Default::default()
                                     });
                                     current_tree_sitter_main_node = Some(capture.node);
                                 }
@@ -313,15 +384,15 @@ impl Parser {
                                     if let Some(current_tree_sitter_main_node) =
                                         current_tree_sitter_main_node
                                     {
-                                        let start_byte = current_tree_sitter_main_node.start_byte();
-                                        let body_start_byte = capture.node.start_byte();
                                         if let Some(curr_node) = &mut current_node {
                                             // Skip the body and keep only the signature.
-                                            curr_node.skeleton_code = String::from_utf8_lossy(
-                                                &source_code[start_byte..body_start_byte],
-                                            )
-                                            .to_string()
-                                                + "{ ... }";
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{ ... }",
+                                            );
                                         }
                                     }
                                 }
@@ -330,12 +401,17 @@ impl Parser {
                         }
 
                         if let Some(curr_node) = &mut current_node {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
+                            let parent_node =
+                                Self::nest_in_namespace(&nodes, file_node, &namespace, curr_node);
+
                             // Parse the parameter types of the current function.
                             for param_type_name in param_type_names {
-                                let param_types = Self::parse_func_param_types(
+                                let param_types = self.parse_func_param_types(
                                     &curr_node.name,
                                     &param_type_name,
-                                    &import_name_to_source_path,
+                                    &import_name_to_import,
                                 );
                                 for param_type in param_types {
                                     func_param_types
@@ -353,11 +429,24 @@ impl Parser {
                                 nodes.insert(curr_node.name.clone(), curr_node.clone());
                                 edges.push(Edge {
                                     r#type: EdgeType::Contains,
-                                    from: file_node.clone(),
+                                    from: parent_node,
                                     to: curr_node.clone(),
                                     import: None,
                                     alias: None,
+                                    properties: IndexMap::new(),
                                 });
+                                if let Some(main_node) = current_tree_sitter_main_node {
+                                    pending_calls.extend(Self::jsx_component_usages(
+                                        &curr_node.name,
+                                        main_node,
+                                        source_code,
+                                    ));
+                                    pending_calls.extend(Self::function_calls(
+                                        &curr_node.name,
+                                        main_node,
+                                        source_code,
+                                    ));
+                                }
                             }
                         }
                     }
@@ -385,6 +474,8 @@ impl Parser {
                                     parent_class_name = Some(capture_node_text);
                                 }
                                 "definition.method" => {
+                                    let (accessor_kind, is_static) =
+                                        Self::method_modifiers(capture.node);
                                     current_node = Some(Node {
                                         name: "".to_string(), // fill in later
                                         r#type: NodeType::Function,
@@ -392,7 +483,9 @@ impl Parser {
                                         start_line: capture.node.start_position().row,
                                         end_line: capture.node.end_position().row,
                                         code: capture_node_text,
-                                        skeleton_code: String::new(),
+                                        accessor_kind,
+                                        is_static,
+                                        ..Default::default()
                                     });
                                     current_tree_sitter_main_node = Some(capture.node);
                                 }
@@ -406,15 +499,15 @@ impl Parser {
                                     if let Some(current_tree_sitter_main_node) =
                                         current_tree_sitter_main_node
                                     {
-                                        let start_byte = current_tree_sitter_main_node.start_byte();
-                                        let body_start_byte = capture.node.start_byte();
                                         if let Some(curr_node) = &mut current_node {
                                             // Skip the body and keep only the signature.
-                                            curr_node.skeleton_code = String::from_utf8_lossy(
-                                                &source_code[start_byte..body_start_byte],
-                                            )
-                                            .to_string()
-                                                + "{ ... }";
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{ ... }",
+                                            );
                                         }
                                     }
                                 }
@@ -425,23 +518,30 @@ impl Parser {
                         if let (Some(curr_node), Some(parent_class_name), Some(method_name)) =
                             (&mut current_node, parent_class_name, method_name)
                         {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
                             let parent_class_node_name = format!(
                                 "{}:{}",
                                 file_node.name.clone(),
-                                parent_class_name.clone(),
+                                Self::qualified_name(&namespace, &parent_class_name),
                             );
                             curr_node.name = format!(
                                 "{}.{}",
                                 parent_class_node_name.clone(),
                                 method_name.clone(),
                             );
+                            if curr_node.accessor_kind == AccessorKind::Setter {
+                                // A setter usually shares its name with a getter, so distinguish it,
+                                // e.g. "a.ts:A.x" (getter) and "a.ts:A.x#set" (setter).
+                                curr_node.name.push_str("#set");
+                            }
 
                             // Parse the parameter types of the current method.
                             for param_type_name in param_type_names {
-                                let param_types = Self::parse_func_param_types(
+                                let param_types = self.parse_func_param_types(
                                     &curr_node.name,
                                     &param_type_name,
-                                    &import_name_to_source_path,
+                                    &import_name_to_import,
                                 );
                                 for param_type in param_types {
                                     func_param_types
@@ -468,8 +568,21 @@ impl Parser {
                                         to: curr_node.clone(),
                                         import: None,
                                         alias: None,
+                                        properties: IndexMap::new(),
                                     });
                                 }
+                                if let Some(main_node) = current_tree_sitter_main_node {
+                                    pending_calls.extend(Self::jsx_component_usages(
+                                        &curr_node.name,
+                                        main_node,
+                                        source_code,
+                                    ));
+                                    pending_calls.extend(Self::function_calls(
+                                        &curr_node.name,
+                                        main_node,
+                                        source_code,
+                                    ));
+                                }
                             }
                         }
                     }
@@ -483,14 +596,23 @@ impl Parser {
                             &file.path,
                             &source_code,
                         );
-                        if let Some(curr_node) = current_node {
+                        if let Some(mut curr_node) = current_node {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
+                            let parent_node = Self::nest_in_namespace(
+                                &nodes,
+                                file_node,
+                                &namespace,
+                                &mut curr_node,
+                            );
                             nodes.insert(curr_node.name.clone(), curr_node.clone());
                             edges.push(Edge {
                                 r#type: EdgeType::Contains,
-                                from: file_node.clone(),
+                                from: parent_node,
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
@@ -504,30 +626,718 @@ impl Parser {
                             &file.path,
                             &source_code,
                         );
-                        if let Some(curr_node) = current_node {
+                        if let Some(mut curr_node) = current_node {
+                            let namespace =
+                                Self::enclosing_namespace(mat.captures[0].node, source_code);
+                            let parent_node = Self::nest_in_namespace(
+                                &nodes,
+                                file_node,
+                                &namespace,
+                                &mut curr_node,
+                            );
                             nodes.insert(curr_node.name.clone(), curr_node.clone());
                             edges.push(Edge {
                                 r#type: EdgeType::Contains,
-                                from: file_node.clone(),
+                                from: parent_node,
                                 to: curr_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
+
+                    QueryPattern::Namespace => {
+                        let mut current_node: Option<Node> = None;
+                        let mut current_tree_sitter_main_node: Option<tree_sitter::Node> = None;
+
+                        for capture in mat.captures {
+                            let capture_name = query.capture_names()[capture.index as usize];
+                            let capture_node_text: String = capture
+                                .node
+                                .utf8_text(&source_code)
+                                .unwrap_or("")
+                                .to_string();
+                            common::log_capture(&capture, capture_name, &capture_node_text);
+
+                            match capture_name {
+                                "definition.namespace" => {
+                                    current_node = Some(Node {
+                                        name: "".to_string(), // fill in later
+                                        r#type: NodeType::Namespace,
+                                        language: file_node.language.clone(),
+                                        start_line: capture.node.start_position().row,
+                                        end_line: capture.node.end_position().row,
+                                        code: capture_node_text,
+                                        ..Default::default()
+                                    });
+                                    current_tree_sitter_main_node = Some(capture.node);
+                                }
+                                "definition.namespace.name" => {
+                                    if let Some(curr_node) = &mut current_node {
+                                        curr_node.name = format!(
+                                            "{}:{}",
+                                            file_node.name.clone(),
+                                            capture_node_text
+                                        );
+                                    }
+                                }
+                                "definition.namespace.body" => {
+                                    if let Some(current_tree_sitter_main_node) =
+                                        current_tree_sitter_main_node
+                                    {
+                                        if let Some(curr_node) = &mut current_node {
+                                            // Skip the body and keep only the signature.
+                                            curr_node.skeleton_code = common::skeleton_code(
+                                                source_code,
+                                                current_tree_sitter_main_node.start_byte(),
+                                                capture.node,
+                                                self.skeleton_body_lines,
+                                                "{ ... }",
+                                            );
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        if let (Some(curr_node), Some(current_tree_sitter_main_node)) =
+                            (&mut current_node, current_tree_sitter_main_node)
+                        {
+                            let namespace = Self::enclosing_namespace(
+                                current_tree_sitter_main_node,
+                                source_code,
+                            );
+                            let parent_node =
+                                Self::nest_in_namespace(&nodes, file_node, &namespace, curr_node);
+
+                            // A namespace might be declared multiple times (i.e. declaration merging),
+                            // in which case we only keep the first one.
+                            if !nodes.contains_key(&curr_node.name) {
+                                nodes.insert(curr_node.name.clone(), curr_node.clone());
+                                edges.push(Edge {
+                                    r#type: EdgeType::Contains,
+                                    from: parent_node,
+                                    to: curr_node.clone(),
+                                    import: None,
+                                    alias: None,
+                                    properties: IndexMap::new(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Resolve the base types of the classes, which are either defined in the same file or imported by name.
+        // The imported ones are resolved after all the files are parsed (see `resolve_pending_inherits`).
+        for (class_name, base_name) in class_bases {
+            let Some(class_node) = nodes.get(&class_name) else {
+                continue;
+            };
+            match nodes.get(&format!("{}:{}", file_node.name, base_name)) {
+                Some(base_node) => {
+                    if !matches!(base_node.r#type, NodeType::Class | NodeType::Interface) {
+                        continue;
+                    }
+                    edges.push(Edge {
+                        r#type: EdgeType::Inherits,
+                        from: class_node.clone(),
+                        to: base_node.clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+                None => {
+                    let Some(imp) = pending_imports.iter().find(|imp| {
+                        !imp.reexport
+                            && imp.symbol.as_ref().is_some_and(|s| s != "export default")
+                            && imp.import_name() == base_name
+                    }) else {
+                        continue;
+                    };
+                    pending_inherits.push(PendingInherit {
+                        from: class_name,
+                        base: FuncParamType {
+                            type_name: imp.symbol.clone().unwrap_or_default(),
+                            package_name: Some(imp.source_path.clone()),
+                            scope: ReferenceScope::File,
+                        },
+                    });
+                }
+            }
+        }
+
+        common::mark_exported_definitions(
+            &file_node.language,
+            &mut nodes,
+            tree.root_node(),
+            source_code,
+        );
+
+        Ok((
+            nodes,
+            edges,
+            pending_imports,
+            Some(func_param_types),
+            pending_calls,
+            pending_inherits,
+        ))
+    }
+
+    /// Returns the names of the base types in the `extends` and `implements` clauses of the given class
+    /// declaration, e.g. `Animal` and `Pet` in `class Dog extends Animal implements Pet`.
+    ///
+    /// Qualified names (e.g. `React.Component`) are skipped.
+    fn class_bases(class_ts_node: tree_sitter::Node, source_code: &[u8]) -> Vec<String> {
+        let mut bases: Vec<String> = Vec::new();
+
+        let mut cursor = class_ts_node.walk();
+        let Some(heritage) = class_ts_node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "class_heritage")
+        else {
+            return bases;
+        };
+
+        let mut cursor = heritage.walk();
+        for clause in heritage.named_children(&mut cursor) {
+            let base_kind = match clause.kind() {
+                "extends_clause" => "identifier",
+                "implements_clause" => "type_identifier",
+                _ => continue,
+            };
+            let mut clause_cursor = clause.walk();
+            for base in clause.named_children(&mut clause_cursor) {
+                // e.g. `Repository<User>` => Repository
+                let base = match base.kind() {
+                    "generic_type" => base.child_by_field_name("name").unwrap_or(base),
+                    _ => base,
+                };
+                if base.kind() == base_kind {
+                    let name = base.utf8_text(source_code).unwrap_or("").to_string();
+                    bases.push(name);
+                }
+            }
+        }
+
+        bases
+    }
+
+    /// Returns the usages of the components in the JSX elements of the given function (or method), e.g.
+    /// `UserCard` in `<UserCard user={user} />`, as pending calls from the function.
+    ///
+    /// Intrinsic elements (e.g. `<div>`) are skipped, since their names start with a lowercase letter.
+    fn jsx_component_usages(
+        caller: &str,
+        ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> Vec<PendingCall> {
+        let mut usages: Vec<PendingCall> = Vec::new();
+
+        let mut stack = vec![ts_node];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "jsx_opening_element" || node.kind() == "jsx_self_closing_element" {
+                if let Some(name) = node
+                    .child_by_field_name("name")
+                    .filter(|name| name.kind() == "identifier")
+                    .and_then(|name| name.utf8_text(source_code).ok())
+                {
+                    if name.starts_with(|c: char| c.is_ascii_uppercase())
+                        && !usages.iter().any(|usage| usage.callee == name)
+                    {
+                        usages.push(PendingCall {
+                            caller: caller.to_string(),
+                            callee: name.to_string(),
+                            receiver_type: None,
+                            package: None,
+                            reference: true,
+                        });
+                    }
+                }
+            }
+            // Push the children in reverse order, to visit them in the source order.
+            let mut cursor = node.walk();
+            let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+
+        usages
+    }
+
+    /// Returns the calls of the given function (or method) to other functions, e.g. `format` in `format(name)`
+    /// and `utils.format` in `utils.format(name)`, as pending calls from the function.
+    ///
+    /// Calls on other expressions (e.g. `this.save()` or `console.log()`) are skipped.
+    fn function_calls(
+        caller: &str,
+        ts_node: tree_sitter::Node,
+        source_code: &[u8],
+    ) -> Vec<PendingCall> {
+        let mut calls: Vec<PendingCall> = Vec::new();
+
+        let mut stack = vec![ts_node];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "call_expression" {
+                let callee = node.child_by_field_name("function").filter(|function| {
+                    function.kind() == "identifier"
+                        || (function.kind() == "member_expression"
+                            && function
+                                .child_by_field_name("object")
+                                .is_some_and(|object| object.kind() == "identifier"))
+                });
+                if let Some(callee) = callee.and_then(|callee| callee.utf8_text(source_code).ok()) {
+                    if !calls.iter().any(|call| call.callee == callee) {
+                        calls.push(PendingCall {
+                            caller: caller.to_string(),
+                            callee: callee.to_string(),
+                            receiver_type: None,
+                            package: None,
+                            reference: false,
+                        });
+                    }
+                }
+            }
+            // Push the children in reverse order, to visit them in the source order.
+            let mut cursor = node.walk();
+            let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+
+        calls
+    }
+
+    /// Resolves the imported base types of the classes into inherits edges, as long as the imported classes
+    /// (or interfaces) have been parsed.
+    pub fn resolve_pending_inherits(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_inherits: &Vec<PendingInherit>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for inherit in pending_inherits {
+            let Some(from_node) = nodes.get(&inherit.from) else {
+                continue;
+            };
+            let Some(package_name) = &inherit.base.package_name else {
+                continue;
+            };
+            let base_nodes = common::find_scoped_type_nodes(
+                nodes,
+                package_name,
+                inherit.base.scope,
+                &inherit.base.type_name,
+            );
+            for base_node in base_nodes {
+                if base_node.language != Language::TypeScript
+                    || !matches!(base_node.r#type, NodeType::Class | NodeType::Interface)
+                {
+                    continue;
+                }
+                edges.push(Edge {
+                    r#type: EdgeType::Inherits,
+                    from: from_node.clone(),
+                    to: base_node.clone(),
+                    import: None,
+                    alias: None,
+                    properties: IndexMap::new(),
+                });
+            }
+        }
+
+        edges
+    }
+
+    /// Resolves the pending calls into the edges from the calling functions to the called ones, which are either
+    /// defined in the same file or imported, i.e. by name (e.g. `format`) or by module (e.g. `utils.format`).
+    ///
+    /// The component usages (see `jsx_component_usages`) are resolved into reference edges, while the function
+    /// calls (see `function_calls`) are resolved into call edges. Unresolved calls (e.g. to builtins) are dropped.
+    pub fn resolve_pending_calls(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_calls: &Vec<PendingCall>,
+        pending_imports: Option<&HashMap<String, Vec<PendingImport>>>,
+    ) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+        let lowercase_index = self.lowercase_node_index(nodes);
+
+        let mut resolved: HashSet<(String, String, bool)> = HashSet::new();
+        for call in pending_calls {
+            let Some(caller_node) = nodes.get(&call.caller) else {
+                continue;
+            };
+            let Some((file_name, _)) = call.caller.split_once(':') else {
+                continue;
+            };
+
+            let imports = pending_imports.and_then(|imports| imports.get(file_name));
+            let callee_node = if let Some((module_name, func_name)) = call.callee.split_once('.') {
+                // The function of a module imported as a whole, e.g. `import * as utils from './utils'`
+                imports
+                    .and_then(|imports| {
+                        imports.iter().find(|imp| {
+                            imp.symbol.is_none() && imp.alias.as_deref() == Some(module_name)
+                        })
+                    })
+                    .and_then(|imp| {
+                        self.find_imported_node(
+                            nodes,
+                            &lowercase_index,
+                            &format!("{}:{}", imp.source_path, func_name),
+                        )
+                    })
+            } else {
+                nodes
+                    .get(&format!("{}:{}", file_name, call.callee))
+                    .or_else(|| {
+                        let imp = imports?.iter().find(|imp| {
+                            (imp.symbol.is_some() || imp.alias.is_some())
+                                && imp.import_name() == call.callee
+                        })?;
+                        self.find_imported_node(nodes, &lowercase_index, &imp.imported_node_name())
+                    })
+            };
+            // Only functions can be called, e.g. not the classes or the namespaces.
+            let callee_node =
+                callee_node.filter(|node| call.reference || node.r#type == NodeType::Function);
+
+            if let Some(callee_node) = callee_node {
+                if resolved.insert((
+                    caller_node.name.clone(),
+                    callee_node.name.clone(),
+                    call.reference,
+                )) {
+                    edges.push(Edge {
+                        r#type: if call.reference {
+                            EdgeType::References
+                        } else {
+                            EdgeType::Calls
+                        },
+                        from: caller_node.clone(),
+                        to: callee_node.clone(),
+                        import: None,
+                        alias: None,
+                        properties: IndexMap::new(),
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Same as `resolve_pending_calls`, but also looks up the imported functions that are not parsed in the
+    /// database.
+    ///
+    /// Mainly used when indexing a single file or a subdirectory.
+    pub fn resolve_pending_calls_from_db(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_calls: &Vec<PendingCall>,
+        pending_imports: Option<&HashMap<String, Vec<PendingImport>>>,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut names: Vec<String> = Vec::new();
+        for call in pending_calls {
+            let Some((file_name, _)) = call.caller.split_once(':') else {
+                continue;
+            };
+            let Some(imports) = pending_imports.and_then(|imports| imports.get(file_name)) else {
+                continue;
+            };
+            for imp in imports {
+                match call.callee.split_once('.') {
+                    // The function of a module imported as a whole, e.g. `utils.format`
+                    Some((module_name, func_name)) => {
+                        if imp.symbol.is_none() && imp.alias.as_deref() == Some(module_name) {
+                            names.push(format!("{}:{}", imp.source_path, func_name));
+                        }
+                    }
+                    None => {
+                        if imp.import_name() == call.callee {
+                            names.push(imp.imported_node_name());
+                        }
+                    }
+                }
+            }
+        }
+        let nodes = common::with_indexed_nodes(nodes, names, db)?;
+        Ok(self.resolve_pending_calls(&nodes, pending_calls, pending_imports))
+    }
+
+    /// Re-derives the skeleton code of the given node from its code, if it is a function, method, class or
+    /// namespace with a body.
+    pub fn derive_skeleton_code(&self, node: &Node) -> Option<String> {
+        let body_kinds: &[&str] = match node.r#type {
+            NodeType::Function | NodeType::Namespace => &["statement_block"],
+            NodeType::Class => &["class_body"],
+            _ => return None,
+        };
+        // Methods (e.g. "a.ts:A.method") are not valid on their own, so they are parsed within a class.
+        let is_method = node.r#type == NodeType::Function
+            && node
+                .name
+                .rsplit(':')
+                .next()
+                .is_some_and(|attr| attr.contains('.'));
+        let prefix = if is_method { "class __ {\n" } else { "" };
+        let file_name = node.name.split(':').next().unwrap_or("");
+        common::derive_skeleton_code(
+            &grammar_for_path(Path::new(file_name)),
+            prefix,
+            &node.code,
+            body_kinds,
+            self.skeleton_body_lines,
+            "{ ... }",
+        )
+    }
+
+    /// Resolves the path of an imported module to an existing file if possible.
+    ///
+    /// If the path is a directory, its index file (e.g. `index.d.ts`, `index.ts` or `index.js`) is used.
+    fn resolve_import_path(&self, import_file_path: PathBuf) -> PathBuf {
+        if import_file_path.is_dir() {
+            for index in [
+                "index.d.ts",
+                "index.ts",
+                "index.tsx",
+                "index.js",
+                "index.jsx",
+            ] {
+                let index_file_path = import_file_path.join(index);
+                if self.path_exists(&index_file_path) {
+                    return index_file_path;
+                }
+            }
+            return import_file_path;
+        }
+        self.resolve_import_file_path(import_file_path)
+    }
+
+    /// Resolves a non-relative import (e.g. `@myorg/pkg` or `@myorg/pkg/utils`) of a workspace package in the
+    /// repository to the entry file declared by the `exports`, `types` or `main` field of its `package.json`.
+    ///
+    /// Returns None if the imported package is not in the repository (e.g. a dependency in `node_modules`).
+    fn resolve_workspace_import_path(&self, import_source: &str) -> Option<PathBuf> {
+        let packages = self
+            .workspace_packages
+            .get_or_init(|| util::get_ts_workspace_packages(&self.repo_path));
+
+        // Prefer the longest matching package name, e.g. `@myorg/pkg-utils` over `@myorg/pkg`.
+        let (package_name, package_dir) = packages
+            .iter()
+            .filter(|(name, _)| {
+                import_source == name.as_str() || import_source.starts_with(&format!("{}/", name))
+            })
+            .max_by_key(|(name, _)| name.len())?;
+        let subpath = match &import_source[package_name.len()..] {
+            "" => ".".to_string(),
+            rest => format!(".{}", rest),
+        };
+
+        let package_json = fs::read_to_string(package_dir.join("package.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())?;
+        util::get_package_json_entries(&package_json, &subpath)
+            .into_iter()
+            .map(|entry| self.resolve_import_path(package_dir.join(entry)))
+            .find(|path| path.is_file())
+    }
+
+    /// Resolves a non-relative import (e.g. `axios`) to a declaration file in the stub directories, e.g.
+    /// `<stub_path>/axios.d.ts` or `<stub_path>/axios/index.d.ts`.
+    fn resolve_stub_import_path(&self, import_source: &str) -> Option<PathBuf> {
+        self.stub_paths.iter().find_map(|stub_path| {
+            let import_path = stub_path.join(import_source);
+            let mut declaration_path = import_path.clone().into_os_string();
+            declaration_path.push(".d.ts");
+            [
+                PathBuf::from(declaration_path),
+                self.resolve_import_path(import_path),
+            ]
+            .into_iter()
+            .find(|path| path.is_file())
+        })
+    }
+
+    /// Resolves the path of an imported (non-directory) module to an existing file if possible.
+    ///
+    /// e.g. `./types` => `./types.ts`, and `./types.js` => `./types.ts` (ESM-style imports in TypeScript).
+    fn resolve_import_file_path(&self, import_file_path: PathBuf) -> PathBuf {
+        if import_file_path.is_file() {
+            return import_file_path;
+        }
+
+        // The import path has no extension, or the extension is part of the file name (e.g. `./user.service`).
+        for ext in ["ts", "tsx", "js", "jsx"] {
+            let mut file_path = import_file_path.clone().into_os_string();
+            file_path.push(".");
+            file_path.push(ext);
+            let file_path = PathBuf::from(file_path);
+            if self.path_exists(&file_path) {
+                return file_path;
+            }
+        }
+
+        // An explicit `.js` extension might refer to a sibling TypeScript file.
+        if import_file_path.extension().is_some_and(|ext| ext == "js") {
+            for ext in ["ts", "tsx"] {
+                let file_path = import_file_path.with_extension(ext);
+                if self.path_exists(&file_path) {
+                    return file_path;
+                }
+            }
+        }
+
+        import_file_path
+    }
+
+    /// Returns whether the given path exists, where the file name is matched case-insensitively if
+    /// `case_insensitive_imports` is enabled (e.g. `./Types.ts` for `./types.ts`), like on macOS and Windows.
+    fn path_exists(&self, path: &Path) -> bool {
+        if path.exists() {
+            return true;
+        }
+        if !self.case_insensitive_imports {
+            return false;
+        }
+        let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+        fs::read_dir(dir).is_ok_and(|entries| {
+            entries.flatten().any(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(&file_name)
+            })
+        })
+    }
+
+    /// Returns the node with the given name, where the file part of the name (e.g. `Types.ts` in `Types.ts:User`)
+    /// is matched case-insensitively, via the index built by `lowercase_node_index`, if
+    /// `case_insensitive_imports` is enabled.
+    fn find_imported_node<'a>(
+        &self,
+        nodes: &'a IndexMap<String, Node>,
+        lowercase_index: &HashMap<String, &'a Node>,
+        name: &str,
+    ) -> Option<&'a Node> {
+        nodes
+            .get(name)
+            .or_else(|| lowercase_index.get(&lowercase_file_part(name)).copied())
+    }
+
+    /// Returns the nodes keyed by their names with the file parts lowercased, e.g. `types.ts:User` for
+    /// `Types.ts:User`, which is built once per resolution pass (see `find_imported_node`).
+    ///
+    /// The index is empty if `case_insensitive_imports` is disabled.
+    fn lowercase_node_index<'a>(
+        &self,
+        nodes: &'a IndexMap<String, Node>,
+    ) -> HashMap<String, &'a Node> {
+        let mut index: HashMap<String, &'a Node> = HashMap::new();
+        if !self.case_insensitive_imports {
+            return index;
+        }
+        for node in nodes.values() {
+            // The first node wins if the names only differ in case.
+            index.entry(lowercase_file_part(&node.name)).or_insert(node);
+        }
+        index
+    }
+
+    /// Returns the accessor kind of the given method definition, and whether it is static.
+    ///
+    /// e.g. `static get x() {}` => (Getter, true)
+    fn method_modifiers(ts_node: tree_sitter::Node) -> (AccessorKind, bool) {
+        let mut accessor_kind = AccessorKind::None;
+        let mut is_static = false;
+
+        // The modifiers are anonymous keywords, unlike a method named e.g. `get`.
+        let mut cursor = ts_node.walk();
+        for child in ts_node.children(&mut cursor) {
+            if child.is_named() {
+                continue;
+            }
+            match child.kind() {
+                "get" => accessor_kind = AccessorKind::Getter,
+                "set" => accessor_kind = AccessorKind::Setter,
+                "static" => is_static = true,
+                _ => {}
+            }
+        }
+        (accessor_kind, is_static)
+    }
+
+    /// Returns the dot-separated name of the namespaces enclosing the given tree-sitter node, if any.
+    ///
+    /// e.g. `Point` in `namespace Geo { namespace Shapes { class Point {} } }` => `Geo.Shapes`
+    fn enclosing_namespace(ts_node: tree_sitter::Node, source_code: &[u8]) -> Option<String> {
+        let mut names: Vec<String> = Vec::new();
+
+        let mut parent = ts_node.parent();
+        while let Some(node) = parent {
+            if node.kind() == "internal_module" || node.kind() == "module" {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    // Skip ambient module declarations (e.g. `declare module "foo" { ... }`).
+                    if name_node.kind() != "string" {
+                        names.push(name_node.utf8_text(source_code).unwrap_or("").to_string());
+                    }
                 }
             }
+            parent = node.parent();
+        }
+
+        if names.is_empty() {
+            return None;
+        }
+        names.reverse();
+        Some(names.join("."))
+    }
+
+    fn qualified_name(namespace: &Option<String>, name: &str) -> String {
+        match namespace {
+            Some(namespace) => format!("{}.{}", namespace, name),
+            None => name.to_string(),
         }
+    }
 
-        Ok((nodes, edges, pending_imports, Some(func_param_types)))
+    /// Prefixes the name of the given definition node with its enclosing namespace (if any),
+    /// and returns the node which contains the definition (i.e. the namespace node or the file node).
+    fn nest_in_namespace(
+        nodes: &IndexMap<String, Node>,
+        file_node: &Node,
+        namespace: &Option<String>,
+        node: &mut Node,
+    ) -> Node {
+        let Some(namespace) = namespace else {
+            return file_node.clone();
+        };
+
+        if let Some((file_name, name)) = node.name.rsplit_once(':') {
+            node.name = format!("{}:{}.{}", file_name, namespace, name);
+        }
+
+        let namespace_node_name = format!("{}:{}", file_node.name, namespace);
+        nodes.get(&namespace_node_name).cloned().unwrap_or_else(|| {
+            Node::from_type_and_name(NodeType::Namespace, namespace_node_name.clone())
+        })
     }
 
     pub fn resolve_pending_imports(
         &self,
         nodes: &IndexMap<String, Node>,
         pending_imports: &HashMap<String, Vec<PendingImport>>,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
         let mut edges: Vec<Edge> = Vec::new();
+        let lowercase_index = self.lowercase_node_index(nodes);
 
         for (file_node_name, pending_imports) in pending_imports {
             for imp in pending_imports {
@@ -538,12 +1348,9 @@ impl Parser {
                     imp.alias
                 );
 
-                let mut imported_node_name = imp.source_path.clone();
-                if let Some(imp_symbol) = &imp.symbol {
-                    imported_node_name = format!("{}:{}", imp.source_path, imp_symbol);
-                }
                 let file_node = nodes.get(file_node_name);
-                let imported_node = nodes.get(&imported_node_name);
+                let imported_node =
+                    self.find_imported_node(nodes, &lowercase_index, &imp.imported_node_name());
                 if let (Some(file_node), Some(imported_node)) = (file_node, imported_node) {
                     edges.push(Edge {
                         r#type: EdgeType::Imports,
@@ -551,6 +1358,7 @@ impl Parser {
                         to: imported_node.clone(),
                         import: imp.symbol.clone(),
                         alias: imp.alias.clone(),
+                        properties: IndexMap::new(),
                     })
                 }
             }
@@ -559,13 +1367,57 @@ impl Parser {
         Ok(edges)
     }
 
+    /// Same as `resolve_pending_imports`, but also looks up the imported nodes that are not parsed in the database.
+    ///
+    /// Mainly used when indexing a single file or a subdirectory.
+    pub fn resolve_pending_imports_from_db(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+        db: &mut Database,
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
+        let names = pending_imports
+            .values()
+            .flatten()
+            .map(|imp| imp.imported_node_name());
+        let nodes = common::with_indexed_nodes(nodes, names, db)?;
+        self.resolve_pending_imports(&nodes, pending_imports)
+    }
+
+    /// Returns the pending imports whose imported nodes are not found.
+    pub fn unresolved_imports(
+        &self,
+        nodes: &IndexMap<String, Node>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) -> Vec<UnresolvedImport> {
+        let mut unresolved_imports: Vec<UnresolvedImport> = Vec::new();
+        let lowercase_index = self.lowercase_node_index(nodes);
+
+        for (file_node_name, pending_imports) in pending_imports {
+            for imp in pending_imports {
+                if self
+                    .find_imported_node(nodes, &lowercase_index, &imp.imported_node_name())
+                    .is_none()
+                {
+                    unresolved_imports.push(UnresolvedImport {
+                        file: file_node_name.clone(),
+                        source_path: imp.source_path.clone(),
+                        symbol: imp.symbol.clone(),
+                    });
+                }
+            }
+        }
+
+        unresolved_imports
+    }
+
     // Mainly used when indexing all the repo (for performance reasons).
     pub fn resolve_func_param_type_edges(
         &self,
         nodes: &IndexMap<String, Node>,
         func_param_types: &HashMap<String, Vec<FuncParamType>>,
         db: &mut Database,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
         let mut edges: Vec<Edge> = Vec::new();
 
         for (func_node_name, param_types) in func_param_types {
@@ -573,19 +1425,25 @@ impl Parser {
             if let Some(func_node) = func_node {
                 for param_type in param_types {
                     if let Some(file_node_name) = &param_type.package_name {
-                        let type_node_name = format!("{}:{}", file_node_name, param_type.type_name);
-                        let param_type_node = nodes.get(type_node_name.as_str());
+                        let param_type_nodes = common::find_scoped_type_nodes(
+                            nodes,
+                            file_node_name,
+                            param_type.scope,
+                            &param_type.type_name,
+                        );
                         log::trace!(
-                            "type_node_name: {type_node_name}, param_type_node: {:?}",
-                            param_type_node
+                            "file_node_name: {file_node_name}, type_name: {}, param_type_nodes: {:?}",
+                            param_type.type_name,
+                            param_type_nodes
                         );
-                        if let Some(param_type_node) = param_type_node {
+                        for param_type_node in param_type_nodes {
                             edges.push(Edge {
                                 r#type: EdgeType::References,
                                 from: func_node.clone(),
                                 to: param_type_node.clone(),
                                 import: None,
                                 alias: None,
+                                properties: IndexMap::new(),
                             });
                         }
                     }
@@ -602,45 +1460,33 @@ impl Parser {
         nodes: &IndexMap<String, Node>,
         func_param_types: &HashMap<String, Vec<FuncParamType>>,
         db: &mut Database,
-    ) -> Result<Vec<Edge>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<Edge>, Box<dyn std::error::Error + Send + Sync>> {
         let mut edges: Vec<Edge> = Vec::new();
 
-        let mut file_types: IndexMap<String, HashSet<String>> = IndexMap::new();
+        let mut file_types: IndexMap<(String, ReferenceScope), HashSet<String>> = IndexMap::new();
         for (func_node_name, param_types) in func_param_types {
             for param_type in param_types {
                 if let Some(file_node_name) = &param_type.package_name {
                     file_types
-                        .entry(file_node_name.clone())
+                        .entry((file_node_name.clone(), param_type.scope))
                         .or_insert_with(HashSet::new)
-                        .insert(param_type.type_name.clone());
+                        .insert(leaf_type_name(&param_type.type_name).to_string());
                 };
             }
         }
 
-        let mut filetype_to_node = IndexMap::new(); // "{file_node_name}:{type_name}" => type_node
-        for (file_node_name, type_names) in file_types {
-            let quoted_type_names: Vec<String> = type_names
-                .iter()
-                .map(|s| format!("\"{}\"", s.to_lowercase()))
-                .collect();
-            let type_names_str = format!("[{}]", quoted_type_names.join(", "));
-            let stmt = format!(
-                r#"
-MATCH (file {{ name: "{}" }})
-MATCH (file)-[:CONTAINS]->(typ)
-WHERE typ.short_name IN {}
-RETURN typ;
-                "#,
-                file_node_name, type_names_str,
-            );
-            log::trace!("Query Stmt: {:}", stmt);
-            let type_nodes = db.query_nodes(stmt.as_str())?;
-
-            for node in &type_nodes {
-                filetype_to_node.insert(
-                    format!("{}:{}", file_node_name, node.short_name()),
-                    node.clone(),
-                );
+        // (file_node_name, scope, type_name) => type_nodes
+        let mut filetype_to_nodes: HashMap<(String, ReferenceScope, String), Vec<Node>> =
+            HashMap::new();
+        for ((file_node_name, scope), type_names) in file_types {
+            let type_nodes =
+                common::query_scoped_type_nodes(db, &file_node_name, scope, &type_names)?;
+
+            for node in type_nodes {
+                filetype_to_nodes
+                    .entry((file_node_name.clone(), scope, node.short_name()))
+                    .or_default()
+                    .push(node);
             }
         }
 
@@ -649,19 +1495,32 @@ RETURN typ;
 
             for param_type in param_types {
                 if let Some(file_node_name) = &param_type.package_name {
-                    let mut param_type_node = filetype_to_node.get(&format!(
-                        "{}:{}",
-                        file_node_name,
-                        param_type.type_name.to_lowercase()
+                    let param_type_nodes = filetype_to_nodes.get(&(
+                        file_node_name.clone(),
+                        param_type.scope,
+                        leaf_type_name(&param_type.type_name).to_lowercase(),
                     ));
-                    if let (Some(func_node), Some(param_type_node)) = (func_node, param_type_node) {
-                        edges.push(Edge {
-                            r#type: EdgeType::References,
-                            from: func_node.clone(),
-                            to: param_type_node.clone(),
-                            import: None,
-                            alias: None,
-                        });
+                    if let (Some(func_node), Some(param_type_nodes)) = (func_node, param_type_nodes)
+                    {
+                        for param_type_node in param_type_nodes {
+                            // The type nodes are looked up by their leaf names, so the qualified
+                            // names (e.g. `Geo.Point`) must match as well.
+                            if param_type.type_name.contains('.')
+                                && !param_type_node
+                                    .name
+                                    .ends_with(&format!(":{}", param_type.type_name))
+                            {
+                                continue;
+                            }
+                            edges.push(Edge {
+                                r#type: EdgeType::References,
+                                from: func_node.clone(),
+                                to: param_type_node.clone(),
+                                import: None,
+                                alias: None,
+                                properties: IndexMap::new(),
+                            });
+                        }
                     }
                 }
             }
@@ -670,47 +1529,121 @@ RETURN typ;
         Ok(edges)
     }
 
+    /// Follows the re-exports for the function parameter types looked up in the re-exporting files, e.g.
+    /// `Account` in `index.ts` is resolved as `User` in `types.ts`, where `export { User as Account } from './types'`
+    /// in `index.ts`.
+    pub fn resolve_reexported_param_types(
+        &self,
+        func_param_types: &mut HashMap<String, Vec<FuncParamType>>,
+        pending_imports: &HashMap<String, Vec<PendingImport>>,
+    ) {
+        // (file node name, exported name) => re-export
+        let mut reexports: HashMap<(String, String), &PendingImport> = HashMap::new();
+        for (file_node_name, imports) in pending_imports {
+            for imp in imports.iter().filter(|imp| imp.reexport) {
+                reexports.insert((file_node_name.clone(), imp.import_name()), imp);
+            }
+        }
+        if reexports.is_empty() {
+            return;
+        }
+
+        for param_type in func_param_types.values_mut().flatten() {
+            // Follow the chain of re-exports, which is bounded in case of cycles.
+            for _ in 0..MAX_REEXPORT_DEPTH {
+                let Some(package_name) = &param_type.package_name else {
+                    break;
+                };
+                if param_type.scope != ReferenceScope::File {
+                    break;
+                }
+                // Only the first segment of a member path is exported, e.g. `Geo` in `Geo.Point`.
+                let (head, rest) = match param_type.type_name.split_once('.') {
+                    Some((head, rest)) => (head, Some(rest)),
+                    None => (param_type.type_name.as_str(), None),
+                };
+                let Some(imp) = reexports.get(&(package_name.clone(), head.to_string())) else {
+                    break;
+                };
+                let Some(symbol) = &imp.symbol else {
+                    break;
+                };
+                let type_name = match rest {
+                    Some(rest) => format!("{}.{}", symbol, rest),
+                    None => symbol.clone(),
+                };
+                param_type.type_name = type_name;
+                param_type.package_name = Some(imp.source_path.clone());
+            }
+        }
+    }
+
     fn parse_func_param_types(
+        &self,
         from_node_name: &String,
         param_type_name: &String,
-        import_name_to_source_path: &HashMap<String, String>,
+        import_name_to_import: &HashMap<String, PendingImport>,
     ) -> Vec<FuncParamType> {
         let mut param_types: Vec<FuncParamType> = Vec::new();
 
-        for (import_name, source_path) in import_name_to_source_path {
+        for (import_name, import) in import_name_to_import {
             log::trace!(
                 "imported_name: {import_name}, source_path: {:?}",
-                source_path
+                import.source_path
             );
         }
 
+        // The type is looked up in the same file (or within the configured scope) if it is not imported.
+        let local_source = || {
+            let from_file_node_name = from_node_name.splitn(2, ":").next()?;
+            Some(match self.reference_scope {
+                ReferenceScope::File => from_file_node_name.to_string(),
+                ReferenceScope::Package | ReferenceScope::Repo => {
+                    common::package_dir(from_file_node_name)
+                }
+            })
+        };
+
         let param_type_names = extract_ts_types(param_type_name.as_str(), true);
         for param_type_name in param_type_names {
-            let type_parts: Vec<&str> = param_type_name.splitn(2, '.').collect();
-            let (module_name, type_name) = match type_parts.len() {
+            // Member paths are split at the first dot, e.g. `ns.inner.Type` => `ns` and `inner.Type`.
+            let (module_name, mut type_name) = match param_type_name.split_once('.') {
                 // no pacakge
-                1 => (None, type_parts[0].to_string()),
+                None => (None, param_type_name.clone()),
                 // package and type
-                2 => (Some(type_parts[0].to_string()), type_parts[1].to_string()),
-                _ => unreachable!(),
+                Some((module_name, type_name)) => {
+                    (Some(module_name.to_string()), type_name.to_string())
+                }
             };
 
             let mut source_node_name: Option<String> = None;
+            let mut scope = ReferenceScope::File;
             if let Some(module_name) = &module_name {
                 // Find the target module name that the type belongs to.
-                // Set it to be None if not found.
-                if let Some(source_path) = import_name_to_source_path.get(module_name) {
-                    source_node_name = Some(source_path.clone());
+                if let Some(import) = import_name_to_import.get(module_name) {
+                    source_node_name = Some(import.source_path.clone());
+                    // A namespace might be imported by name, e.g. `Geo.Shapes.Point` where
+                    // `import { Geo } from './geo'`, whose members are qualified by the namespace.
+                    if let Some(symbol) = &import.symbol {
+                        if symbol != "export default" {
+                            type_name = format!("{}.{}", symbol, type_name);
+                        }
+                    }
+                } else {
+                    // Otherwise, the module might be a namespace declared locally, e.g. `Geo.Point`
+                    // where `namespace Geo { ... }`, whose members are qualified by the whole path.
+                    type_name = param_type_name.clone();
+                    scope = self.reference_scope;
+                    source_node_name = local_source();
                 }
             } else {
                 // Otherwise, the type might has been directly imported.
-                if let Some(source_path) = import_name_to_source_path.get(&type_name) {
-                    source_node_name = Some(source_path.clone());
+                if let Some(import) = import_name_to_import.get(&type_name) {
+                    source_node_name = Some(import.source_path.clone());
                 } else {
-                    // Finally, the type might be defined in the same file.
-                    if let Some(from_file_node_name) = from_node_name.splitn(2, ":").next() {
-                        source_node_name = Some(from_file_node_name.into());
-                    }
+                    // Finally, the type might be defined in the same file (or within the configured scope).
+                    scope = self.reference_scope;
+                    source_node_name = local_source();
                 }
             }
 
@@ -724,6 +1657,7 @@ RETURN typ;
             param_types.push(FuncParamType {
                 type_name,
                 package_name: source_node_name,
+                scope,
             });
         }
 
@@ -731,8 +1665,25 @@ RETURN typ;
     }
 }
 
+/// Returns the given node name with the file part lowercased, e.g. `types.ts:User` for `Types.ts:User`.
+fn lowercase_file_part(name: &str) -> String {
+    match name.split_once(':') {
+        Some((file_name, symbol)) => format!("{}:{}", file_name.to_ascii_lowercase(), symbol),
+        None => name.to_ascii_lowercase(),
+    }
+}
+
+/// Returns the last segment of the given member path, e.g. `Point` for `Geo.Shapes.Point`.
+fn leaf_type_name(type_name: &str) -> &str {
+    type_name.rsplit('.').next().unwrap_or(type_name)
+}
+
 /// Extract types from TypeScript type string
 ///
+/// All the type names in the type string are extracted, including the type arguments of
+/// builtin generic types (e.g. `User` in `Record<string, User>`), while the names of object
+/// keys and index signature parameters (e.g. `k` in `{ [k: string]: User }`) are skipped.
+///
 /// # Arguments
 /// * `type_str` - TypeScript type expression string
 /// * `exclude_builtin` - Whether to exclude builtin types like string, number, etc.
@@ -740,51 +1691,47 @@ RETURN typ;
 /// # Returns
 /// * Array of extracted type strings
 pub fn extract_ts_types(type_str: &str, exclude_builtin: bool) -> Vec<String> {
-    // Builtin types list
-    let builtin_types: HashSet<&str> = [
-        // Primitive types
-        "string",
-        "number",
-        "boolean",
-        "any",
-        "void",
-        "null",
-        "undefined",
-        "unknown",
-        "never",
-        "object",
-        "bigint",
-        "symbol",
-        "function",
-        // Composite types
-        "Map",
-        "Promise",
-        "Array",
-        "Record",
-        "Partial",
+    // Keywords that might appear in type expressions
+    let keywords: HashSet<&str> = [
+        "extends", "keyof", "typeof", "infer", "in", "is", "as", "readonly", "unique", "asserts",
+        "new", "true", "false",
     ]
     .iter()
     .cloned()
     .collect();
 
-    // Compile regex pattern
-    let re = Regex::new(r"(^|[<,\s])([A-Za-z_][A-Za-z0-9_]*)(?:\[\])*(>|,|\s|$|&|\|)?")
+    // Compile regex patterns
+    let literal_re = Regex::new(r#"'[^']*'|"[^"]*"|`[^`]*`"#).expect("Invalid regex pattern");
+    let re = Regex::new(r"[A-Za-z_$][A-Za-z0-9_$]*(?:\.[A-Za-z_$][A-Za-z0-9_$]*)*")
         .expect("Invalid regex pattern");
 
+    // String literal types (e.g. `Omit<User, 'id'>`) contain no type names.
+    let type_str = literal_re.replace_all(type_str, "''");
+
     let mut result = Vec::new();
     let mut found_types = HashSet::new();
 
-    for cap in re.captures_iter(type_str) {
-        if let Some(matched) = cap.get(2) {
-            let type_name = matched.as_str();
+    for matched in re.find_iter(&type_str) {
+        let type_name = matched.as_str();
+
+        // Skip keys like `name: T`, `name?: T` and `[k: string]: T`, but keep the true branch
+        // of conditional types like `A extends B ? X : Y`.
+        let prev_char = type_str[..matched.start()].trim_end().chars().last();
+        let next_str = type_str[matched.end()..].trim_start();
+        let is_key =
+            (next_str.starts_with(':') || next_str.starts_with("?:")) && prev_char != Some('?');
+        // Skip type parameters of mapped types like `{ [K in keyof T]: X }`.
+        let is_mapped_param = next_str.starts_with("in ");
+        if is_key || is_mapped_param || keywords.contains(type_name) {
+            continue;
+        }
 
-            // Handle type name filtering logic
-            if (!exclude_builtin || !builtin_types.contains(type_name))
-                && !found_types.contains(type_name)
-            {
-                result.push(type_name.to_string());
-                found_types.insert(type_name);
-            }
+        // Handle type name filtering logic
+        if (!exclude_builtin || !util::is_builtin_type(&Language::TypeScript, type_name))
+            && !found_types.contains(type_name)
+        {
+            result.push(type_name.to_string());
+            found_types.insert(type_name);
         }
     }
 
@@ -826,4 +1773,28 @@ mod tests {
             println!();
         }
     }
+
+    #[test]
+    fn test_extract_ts_types_from_builtin_generics() {
+        let test_cases = vec![
+            ("Record<string, User>", vec!["User"]),
+            ("Record<string,User>", vec!["User"]),
+            ("Promise<Map<string, User[]>>", vec!["User"]),
+            ("{ [k: string]: User }", vec!["User"]),
+            ("{ [K in keyof User]?: Address }", vec!["User", "Address"]),
+            ("Partial<Omit<User, 'id'>>", vec!["User"]),
+            ("Pick<User, 'id' | 'name'>", vec!["User"]),
+            ("Exclude<Role, Admin>", vec!["Role", "Admin"]),
+            ("(user: User) => boolean", vec!["User"]),
+            ("models.User | null", vec!["models.User"]),
+            (
+                "T extends User ? Admin : Guest",
+                vec!["T", "User", "Admin", "Guest"],
+            ),
+        ];
+
+        for (case, want) in test_cases {
+            assert_eq!(extract_ts_types(case, true), want, "type string: {}", case);
+        }
+    }
 }
@@ -3,12 +3,14 @@ CREATE NODE TABLE IF NOT EXISTS Unparsed (
     name STRING,
     type STRING,
     short_name STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS Directory (
     name STRING,
     type STRING,
     short_name STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS File (
@@ -18,6 +20,16 @@ CREATE NODE TABLE IF NOT EXISTS File (
     language STRING,
     code STRING,
     skeleton_code STRING,
+    grammar_version STRING,
+    content_hash STRING,
+    imported_files STRING[],
+    unresolved_imports STRING[],
+    generated BOOLEAN,
+    function_count UINT32,
+    class_count UINT32,
+    import_count UINT32,
+    metadata STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS Interface (
@@ -29,6 +41,10 @@ CREATE NODE TABLE IF NOT EXISTS Interface (
     skeleton_code STRING,
     start_line UINT32,
     end_line UINT32,
+    doc_comment STRING,
+    exported BOOLEAN,
+    metadata STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS Class (
@@ -40,6 +56,10 @@ CREATE NODE TABLE IF NOT EXISTS Class (
     skeleton_code STRING,
     start_line UINT32,
     end_line UINT32,
+    doc_comment STRING,
+    exported BOOLEAN,
+    metadata STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS Function (
@@ -51,6 +71,13 @@ CREATE NODE TABLE IF NOT EXISTS Function (
     skeleton_code STRING,
     start_line UINT32,
     end_line UINT32,
+    doc_comment STRING,
+    exported BOOLEAN,
+    accessor_kind STRING,
+    is_static BOOLEAN,
+    receiver STRING,
+    metadata STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS OtherType (
@@ -62,6 +89,25 @@ CREATE NODE TABLE IF NOT EXISTS OtherType (
     skeleton_code STRING,
     start_line UINT32,
     end_line UINT32,
+    doc_comment STRING,
+    exported BOOLEAN,
+    metadata STRING,
+    full_name STRING,
+    PRIMARY KEY(name)
+);
+CREATE NODE TABLE IF NOT EXISTS Namespace (
+    name STRING,
+    type STRING,
+    short_name STRING,
+    language STRING,
+    code STRING,
+    skeleton_code STRING,
+    start_line UINT32,
+    end_line UINT32,
+    doc_comment STRING,
+    exported BOOLEAN,
+    metadata STRING,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 CREATE NODE TABLE IF NOT EXISTS Variable (
@@ -72,6 +118,7 @@ CREATE NODE TABLE IF NOT EXISTS Variable (
     code STRING,
     start_line UINT32,
     end_line UINT32,
+    full_name STRING,
     PRIMARY KEY(name)
 );
 
@@ -84,10 +131,19 @@ CREATE REL TABLE IF NOT EXISTS CONTAINS (
     From File To Function,
     From File To OtherType,
     From File To Variable,
+    From File To Namespace,
+    From Namespace To Namespace,
+    From Namespace To Interface,
+    From Namespace To Class,
+    From Namespace To Function,
+    From Namespace To OtherType,
     From Interface To Function,
+    From Class To Class,
     From Class To Function,
     From OtherType To Function,
-    type STRING
+    From Function To OtherType, // TODO comments within functions
+    type STRING,
+    properties STRING
 );
 CREATE REL TABLE IF NOT EXISTS IMPORTS (
     From File To Directory, // import directory as package
@@ -97,15 +153,20 @@ CREATE REL TABLE IF NOT EXISTS IMPORTS (
     From File To Function,
     From File To OtherType,
     From File To Variable,
+    From File To Namespace,
     From File To Unparsed,
     type STRING,
     import STRING,
-    alias STRING
+    alias STRING,
+    properties STRING
 );
 CREATE REL TABLE IF NOT EXISTS INHERITS (
     From Class To Unparsed,
     From Class To Class,
-    type STRING
+    From Class To Interface,
+    From Interface To Interface,
+    type STRING,
+    properties STRING
 );
 CREATE REL TABLE IF NOT EXISTS REFERENCES (
     From Class To Interface,
@@ -125,5 +186,11 @@ CREATE REL TABLE IF NOT EXISTS REFERENCES (
     From Variable To OtherType,
     From Variable To Variable,
     From Variable To Unparsed,
-    type STRING
+    type STRING,
+    properties STRING
+);
+CREATE REL TABLE IF NOT EXISTS CALLS (
+    From Function To Function,
+    type STRING,
+    properties STRING
 );
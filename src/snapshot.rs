@@ -0,0 +1,96 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::{CodeGraphError, Edge, EdgeType, Node};
+
+/// The version of the snapshot format, which is bumped whenever the layout of `Snapshot` changes.
+///
+/// It's written as a fixed `u32` header before the body, so that it can be checked before decoding the body,
+/// whose layout depends on the version.
+const SNAPSHOT_VERSION: u32 = 4;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    nodes: Vec<Node>,
+    edges: Vec<SnapshotEdge>,
+}
+
+/// An edge whose nodes are referred to by name, since they are already in the snapshot.
+#[derive(Serialize, Deserialize)]
+struct SnapshotEdge {
+    r#type: EdgeType,
+    from: String,
+    to: String,
+    import: Option<String>,
+    alias: Option<String>,
+    // The properties as a JSON string, since bincode cannot decode self-describing values
+    properties: String,
+}
+
+/// Writes the nodes and edges to the given file as a compact binary snapshot.
+pub fn write_snapshot(path: &Path, nodes: &[Node], edges: &[Edge]) -> Result<(), CodeGraphError> {
+    let snapshot = Snapshot {
+        nodes: nodes.to_vec(),
+        edges: edges
+            .iter()
+            .map(|edge| SnapshotEdge {
+                r#type: edge.r#type.clone(),
+                from: edge.from.name.clone(),
+                to: edge.to.name.clone(),
+                import: edge.import.clone(),
+                alias: edge.alias.clone(),
+                properties: serde_json::to_string(&edge.properties).unwrap_or_default(),
+            })
+            .collect(),
+    };
+
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    bincode::serialize_into(&mut writer, &SNAPSHOT_VERSION)?;
+    bincode::serialize_into(&mut writer, &snapshot)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the nodes and edges from the given snapshot file (see `write_snapshot`).
+///
+/// The edges between unknown nodes are ignored.
+pub fn read_snapshot(path: &Path) -> Result<(Vec<Node>, Vec<Edge>), CodeGraphError> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let version: u32 = bincode::deserialize_from(&mut reader)?;
+    if version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported snapshot version {} (expected {})",
+            version, SNAPSHOT_VERSION
+        )
+        .into());
+    }
+    let snapshot: Snapshot = bincode::deserialize_from(reader)?;
+
+    let nodes_by_name: IndexMap<&str, &Node> = snapshot
+        .nodes
+        .iter()
+        .map(|node| (node.name.as_str(), node))
+        .collect();
+    let mut edges: Vec<Edge> = Vec::new();
+    for edge in &snapshot.edges {
+        let (Some(from), Some(to)) = (
+            nodes_by_name.get(edge.from.as_str()),
+            nodes_by_name.get(edge.to.as_str()),
+        ) else {
+            continue;
+        };
+        edges.push(Edge {
+            r#type: edge.r#type.clone(),
+            from: (*from).clone(),
+            to: (*to).clone(),
+            import: edge.import.clone(),
+            alias: edge.alias.clone(),
+            properties: serde_json::from_str(&edge.properties).unwrap_or_default(),
+        });
+    }
+
+    Ok((snapshot.nodes, edges))
+}
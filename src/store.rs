@@ -0,0 +1,261 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::{CodeGraphError, Database, Edge, Node};
+
+/// A storage backend of the code graph.
+///
+/// The statements of `query_nodes` and `query_edges` are Cypher, although stores other than Kuzu may only
+/// support a subset of it.
+pub trait GraphStore {
+    fn upsert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), CodeGraphError>;
+
+    fn upsert_edges(&mut self, edges: &Vec<Edge>) -> Result<(), CodeGraphError>;
+
+    fn query_nodes(&mut self, stmt: &str) -> Result<Vec<Node>, CodeGraphError>;
+
+    fn query_edges(&mut self, stmt: &str) -> Result<Vec<Edge>, CodeGraphError>;
+
+    /// Deletes the nodes with the given names, as well as all of their edges.
+    fn delete_nodes(&mut self, names: &Vec<String>) -> Result<(), CodeGraphError>;
+
+    /// Removes all the nodes and edges. If `delete` is true, the underlying storage is also deleted.
+    fn clean(&mut self, delete: bool) -> Result<(), CodeGraphError>;
+
+    /// Returns the Kuzu database if the store is backed by Kuzu, which is required for bulk loading and
+    /// resolving the edges across files against the stored nodes (default is None).
+    fn as_kuzu(&mut self) -> Option<&mut Database> {
+        None
+    }
+}
+
+impl GraphStore for Database {
+    fn upsert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), CodeGraphError> {
+        Database::upsert_nodes(self, nodes)
+    }
+
+    fn upsert_edges(&mut self, edges: &Vec<Edge>) -> Result<(), CodeGraphError> {
+        Database::upsert_edges(self, edges)
+    }
+
+    fn query_nodes(&mut self, stmt: &str) -> Result<Vec<Node>, CodeGraphError> {
+        Database::query_nodes(self, stmt)
+    }
+
+    fn query_edges(&mut self, stmt: &str) -> Result<Vec<Edge>, CodeGraphError> {
+        Database::query_edges(self, stmt)
+    }
+
+    fn delete_nodes(&mut self, names: &Vec<String>) -> Result<(), CodeGraphError> {
+        Database::delete_nodes(self, names)
+    }
+
+    fn clean(&mut self, delete: bool) -> Result<(), CodeGraphError> {
+        Database::clean(self, delete)
+    }
+
+    fn as_kuzu(&mut self) -> Option<&mut Database> {
+        Some(self)
+    }
+}
+
+/// An in-memory store, which is mainly intended for fast unit tests.
+///
+/// Only the following forms of statements are supported, where the label and the `WHERE` clause are optional:
+///
+/// ```text
+/// MATCH (n:Function) WHERE n.name = "main.go:main" RETURN n
+/// MATCH (a)-[e:CALLS]->(b) RETURN a.name, b.name, e
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    nodes: IndexMap<String, Node>,
+    // (type, from, to) -> edge
+    edges: IndexMap<(String, String, String), Edge>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GraphStore for MemoryStore {
+    fn upsert_nodes(&mut self, nodes: &Vec<Node>) -> Result<(), CodeGraphError> {
+        for node in nodes {
+            self.nodes.insert(node.name.clone(), node.clone());
+        }
+        Ok(())
+    }
+
+    fn upsert_edges(&mut self, edges: &Vec<Edge>) -> Result<(), CodeGraphError> {
+        for edge in edges {
+            // Like Kuzu, the edges between unknown nodes are ignored.
+            if !self.nodes.contains_key(&edge.from.name) || !self.nodes.contains_key(&edge.to.name)
+            {
+                continue;
+            }
+            let key = (
+                edge.r#type.to_string(),
+                edge.from.name.clone(),
+                edge.to.name.clone(),
+            );
+            self.edges.insert(key, edge.clone());
+        }
+        Ok(())
+    }
+
+    fn query_nodes(&mut self, stmt: &str) -> Result<Vec<Node>, CodeGraphError> {
+        static NODES_RE: OnceLock<Regex> = OnceLock::new();
+        let re = NODES_RE.get_or_init(|| {
+            Regex::new(
+                r#"(?is)^\s*MATCH\s*\((?P<var>\w+)(?::(?P<label>\w+))?\)\s*(?:WHERE\s+(?P<where_var>\w+)\.name\s*=\s*"(?P<name>[^"]*)"\s*)?RETURN\s+(?P<return_var>\w+)\s*;?\s*$"#,
+            )
+            .unwrap()
+        });
+
+        let caps = re
+            .captures(stmt)
+            .filter(|caps| {
+                let var = &caps["var"];
+                &caps["return_var"] == var
+                    && caps.name("where_var").map_or(true, |v| v.as_str() == var)
+            })
+            .ok_or_else(|| format!("Unsupported statement for the in-memory store: {}", stmt))?;
+        let label = caps.name("label").map(|m| m.as_str());
+        let name = caps.name("name").map(|m| m.as_str());
+
+        Ok(self
+            .nodes
+            .values()
+            .filter(|node| label.map_or(true, |l| node.r#type.to_string().eq_ignore_ascii_case(l)))
+            .filter(|node| name.map_or(true, |n| node.name == n))
+            .cloned()
+            .collect())
+    }
+
+    fn query_edges(&mut self, stmt: &str) -> Result<Vec<Edge>, CodeGraphError> {
+        static EDGES_RE: OnceLock<Regex> = OnceLock::new();
+        let re = EDGES_RE.get_or_init(|| {
+            Regex::new(
+                r#"(?is)^\s*MATCH\s*\((?P<from>\w+)\)\s*-\[(?P<var>\w+)(?::(?P<label>\w+))?\]->\s*\((?P<to>\w+)\)\s*RETURN\s+(?P<return_from>\w+)\.name\s*,\s*(?P<return_to>\w+)\.name\s*,\s*(?P<return_var>\w+)\s*;?\s*$"#,
+            )
+            .unwrap()
+        });
+
+        let caps = re
+            .captures(stmt)
+            .filter(|caps| {
+                caps["return_from"] == caps["from"]
+                    && caps["return_to"] == caps["to"]
+                    && caps["return_var"] == caps["var"]
+            })
+            .ok_or_else(|| format!("Unsupported statement for the in-memory store: {}", stmt))?;
+        let label = caps.name("label").map(|m| m.as_str());
+
+        Ok(self
+            .edges
+            .values()
+            .filter(|edge| label.map_or(true, |l| edge.r#type.to_string().eq_ignore_ascii_case(l)))
+            .cloned()
+            .collect())
+    }
+
+    fn delete_nodes(&mut self, names: &Vec<String>) -> Result<(), CodeGraphError> {
+        for name in names {
+            self.nodes.shift_remove(name);
+        }
+        self.edges
+            .retain(|(_, from, to), _| !names.contains(from) && !names.contains(to));
+        Ok(())
+    }
+
+    fn clean(&mut self, _delete: bool) -> Result<(), CodeGraphError> {
+        self.nodes.clear();
+        self.edges.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EdgeType, NodeType};
+
+    fn edge(r#type: EdgeType, from: &Node, to: &Node) -> Edge {
+        Edge {
+            r#type,
+            from: from.clone(),
+            to: to.clone(),
+            import: None,
+            alias: None,
+            properties: IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let file = Node::from_type_and_name(NodeType::File, "main.go".to_string());
+        let main = Node::from_type_and_name(NodeType::Function, "main.go:main".to_string());
+        let helper = Node::from_type_and_name(NodeType::Function, "main.go:helper".to_string());
+        let unknown = Node::from_type_and_name(NodeType::Function, "other.go:unknown".to_string());
+
+        let mut store = MemoryStore::new();
+        store
+            .upsert_nodes(&vec![file.clone(), main.clone(), helper.clone()])
+            .unwrap();
+        store
+            .upsert_edges(&vec![
+                edge(EdgeType::Contains, &file, &main),
+                edge(EdgeType::Contains, &file, &helper),
+                edge(EdgeType::Calls, &main, &helper),
+                // Upserting the same edge again does not duplicate it.
+                edge(EdgeType::Calls, &main, &helper),
+                // The edges to unknown nodes are ignored.
+                edge(EdgeType::Calls, &main, &unknown),
+            ])
+            .unwrap();
+
+        let names = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(store.query_nodes("MATCH (n:Function) RETURN n;").unwrap()),
+            vec!["main.go:main", "main.go:helper"],
+        );
+        assert_eq!(
+            names(
+                store
+                    .query_nodes(r#"MATCH (n) WHERE n.name = "main.go" RETURN n"#)
+                    .unwrap()
+            ),
+            vec!["main.go"],
+        );
+        assert_eq!(
+            store
+                .query_edges("MATCH (a)-[e:CALLS]->(b) RETURN a.name, b.name, e")
+                .unwrap()
+                .len(),
+            1,
+        );
+        assert!(store
+            .query_nodes("MATCH (n) WHERE n.start_line > 1 RETURN n")
+            .is_err());
+
+        // Deleting a node also deletes its edges.
+        store
+            .delete_nodes(&vec!["main.go:helper".to_string()])
+            .unwrap();
+        assert_eq!(
+            names(store.query_nodes("MATCH (n) RETURN n").unwrap()),
+            vec!["main.go", "main.go:main"],
+        );
+        let edges = store
+            .query_edges("MATCH (a)-[e]->(b) RETURN a.name, b.name, e")
+            .unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].to.name, "main.go:main");
+
+        store.clean(false).unwrap();
+        assert!(store.query_nodes("MATCH (n) RETURN n").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The number of files in each directory of the synthetic repository.
+const FILES_PER_DIR: usize = 10;
+
+/// Generates a synthetic TypeScript repository with `num_files` files under `dir`, e.g. for benchmarking.
+///
+/// The files are spread over directories of 10 files each (i.e. `pkg0/mod0.ts`, ..., `pkg1/mod10.ts`, ...),
+/// and each file imports the types and functions of the previous one, so that the resolution of imports,
+/// references and calls across files is exercised as well.
+pub fn generate_synthetic_repo(dir: &Path, num_files: usize) -> io::Result<()> {
+    for i in 0..num_files {
+        let pkg_dir = dir.join(format!("pkg{}", i / FILES_PER_DIR));
+        fs::create_dir_all(&pkg_dir)?;
+        fs::write(pkg_dir.join(format!("mod{}.ts", i)), synthetic_file(i))?;
+    }
+    Ok(())
+}
+
+fn synthetic_file(i: usize) -> String {
+    let mut content = String::new();
+    if i > 0 {
+        let prev = i - 1;
+        let import_path = if prev / FILES_PER_DIR == i / FILES_PER_DIR {
+            format!("./mod{}", prev)
+        } else {
+            format!("../pkg{}/mod{}", prev / FILES_PER_DIR, prev)
+        };
+        content.push_str(&format!(
+            "import {{ Item{prev}, process{prev} }} from \"{import_path}\";\n\n"
+        ));
+    }
+
+    let parent_field = if i > 0 {
+        format!("  parent?: Item{};\n", i - 1)
+    } else {
+        String::new()
+    };
+    let process_body = if i > 0 {
+        format!(
+            "  return item.parent ? process{}(item.parent) + item.id : item.id;\n",
+            i - 1
+        )
+    } else {
+        "  return item.id;\n".to_string()
+    };
+    content.push_str(&format!(
+        r#"export interface Item{i} {{
+  id: number;
+  name: string;
+{parent_field}}}
+
+export class Service{i} {{
+  private items: Item{i}[] = [];
+
+  get(id: number): Item{i} | undefined {{
+    return this.items.find((item) => item.id === id);
+  }}
+
+  save(item: Item{i}): void {{
+    this.items.push(item);
+  }}
+}}
+
+export function process{i}(item: Item{i}): number {{
+{process_body}}}
+"#
+    ));
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EdgeType, NodeType, Parser, ParserConfig};
+
+    #[test]
+    fn test_generate_synthetic_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_path_buf();
+        generate_synthetic_repo(&repo_path, 25).unwrap();
+
+        let mut parser = Parser::new(repo_path.clone(), ParserConfig::default());
+        let (nodes, _) = parser.parse(&repo_path, None).unwrap();
+        let count = |r#type: NodeType| nodes.values().filter(|n| n.r#type == r#type).count();
+        assert_eq!(count(NodeType::File), 25);
+        assert_eq!(count(NodeType::Directory), 4); // The root directory and `pkg0` to `pkg2`.
+        assert_eq!(count(NodeType::Interface), 25);
+        assert_eq!(count(NodeType::Class), 25);
+
+        // Each file (except the first one) imports two symbols of the previous one.
+        let edges = parser.resolve_pending_edges(None).unwrap();
+        let imports = edges
+            .iter()
+            .filter(|e| matches!(e.r#type, EdgeType::Imports))
+            .count();
+        assert_eq!(imports, 24 * 2);
+    }
+}
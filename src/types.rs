@@ -1,13 +1,23 @@
 use indexmap::IndexMap;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use strum_macros;
 
 #[derive(
-    Debug, Clone, PartialEq, Eq, strum_macros::EnumString, strum_macros::Display, serde::Serialize,
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub enum NodeType {
+    #[default]
     #[strum(serialize = "Unparsed")]
     Unparsed,
     #[strum(serialize = "Directory")]
@@ -22,9 +32,18 @@ pub enum NodeType {
     Function, // function, method
     #[strum(serialize = "OtherType")]
     OtherType, // enum, type alias, etc
+    #[strum(serialize = "Namespace")]
+    Namespace, // namespace, module
 }
 
-#[derive(Debug, Clone, strum_macros::Display, strum_macros::EnumString, serde::Serialize)]
+#[derive(
+    Debug,
+    Clone,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum EdgeType {
     #[strum(serialize = "contains")]
     Contains,
@@ -34,6 +53,8 @@ pub enum EdgeType {
     Inherits,
     #[strum(serialize = "references")]
     References,
+    #[strum(serialize = "calls")]
+    Calls,
 }
 
 #[derive(
@@ -42,15 +63,21 @@ pub enum EdgeType {
     PartialEq,
     Debug,
     Clone,
+    Default,
     strum_macros::Display,
     strum_macros::EnumString,
     serde::Serialize,
+    serde::Deserialize,
 )]
 pub enum Language {
+    #[default]
     Text,
     Go,
     TypeScript,
     Python,
+    Rust,
+    Yaml,
+    Json,
     // JavaScript,
 }
 
@@ -60,15 +87,64 @@ impl Language {
 
         match ext {
             Some("go") => Language::Go,
-            Some("ts") => Language::TypeScript,
-            Some("py") => Language::Python,
-            // Some("js") => Language::JavaScript,
+            // JavaScript (with JSX) is parsed by the TypeScript parser.
+            Some("ts") | Some("tsx") | Some("js") | Some("jsx") => Language::TypeScript,
+            // Stub files (`.pyi`) are only parsed when imported from the stub directories.
+            Some("py") | Some("pyi") => Language::Python,
+            Some("rs") => Language::Rust,
+            Some("yaml") | Some("yml") => Language::Yaml,
+            Some("json") => Language::Json,
             _ => Language::Text,
         }
     }
+
+    /// Returns the name and version of the tree-sitter grammar used to parse the language
+    /// (e.g. `tree-sitter-go@0.23.4`), or an empty string if the language is not parsed.
+    pub fn grammar_version(&self) -> &'static str {
+        match self {
+            Language::Go => concat!("tree-sitter-go@", env!("TREE_SITTER_GO_VERSION")),
+            Language::TypeScript => concat!(
+                "tree-sitter-typescript@",
+                env!("TREE_SITTER_TYPESCRIPT_VERSION")
+            ),
+            Language::Python => concat!("tree-sitter-python@", env!("TREE_SITTER_PYTHON_VERSION")),
+            Language::Rust => concat!("tree-sitter-rust@", env!("TREE_SITTER_RUST_VERSION")),
+            Language::Yaml | Language::Json | Language::Text => "",
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// The aggregate counts of a file, which are computed at index time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileSummary {
+    /// The number of functions (including methods)
+    pub function_count: usize,
+    /// The number of classes (or structs)
+    pub class_count: usize,
+    /// The number of imports within the repository (e.g. of other files or packages)
+    pub import_count: usize,
+}
+
+/// The kind of an accessor method, e.g. `get x()` in TypeScript.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum AccessorKind {
+    /// Not an accessor, e.g. a plain method
+    #[default]
+    None,
+    Getter,
+    Setter,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Node {
     /// File path
     pub name: String,
@@ -84,6 +160,31 @@ pub struct Node {
     pub code: String,
     /// The skeleton code text
     pub skeleton_code: String,
+    /// The doc comment (or docstring) without the comment markers (only for definition nodes)
+    pub doc_comment: String,
+    /// Whether the definition is visible outside of its file, or its package for Go (only for definition nodes)
+    pub exported: bool,
+    /// The tree-sitter grammar used to parse the file (only for file nodes)
+    pub grammar_version: String,
+    /// The hash of the file content, along with the grammar version and the parsing options, to skip re-indexing
+    /// the unchanged file (only for file nodes)
+    pub content_hash: String,
+    /// The names of the files imported by the file (only for file nodes, and only if enabled)
+    pub imported_files: Vec<String>,
+    /// The imports of the file whose targets have not been indexed yet, e.g. `types.ts:User as U` (only for file nodes)
+    pub unresolved_imports: Vec<String>,
+    /// Whether the file is generated, e.g. by protoc (only for file nodes)
+    pub generated: bool,
+    /// The aggregate counts of the definitions and imports in the file (only for file nodes)
+    pub summary: FileSummary,
+    /// Arbitrary key/value annotations from users, e.g. `owner: team-x` (not for directory nodes)
+    pub metadata: BTreeMap<String, String>,
+    /// Whether the method is a getter or a setter (only for function nodes)
+    pub accessor_kind: AccessorKind,
+    /// Whether the method is a static member of its class (only for function nodes)
+    pub is_static: bool,
+    /// The receiver type of the method, e.g. `User` for `func (u *User) Save()` (only for Go methods)
+    pub receiver: String,
 }
 
 impl Node {
@@ -91,40 +192,97 @@ impl Node {
         Self {
             name,
             r#type,
-            language: Language::Text,
-            start_line: 0,
-            end_line: 0,
-            code: String::new(),
-            skeleton_code: String::new(),
+            ..Default::default()
         }
     }
 
-    pub fn from_dict(data: &HashMap<String, serde_json::Value>) -> Self {
-        Self {
-            name: data.get("name").unwrap().as_str().unwrap().to_string(),
-            r#type: match data.get("type").unwrap().as_str().unwrap() {
-                "Unparsed" => NodeType::Unparsed,
-                "Class" => NodeType::Class,
-                _ => NodeType::Unparsed, // 默认值
+    /// Creates the node from the dictionary of `to_dict`, where only the name and the type are required.
+    pub fn from_dict(
+        data: &HashMap<String, serde_json::Value>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let string = |key: &str| {
+            data.get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_default()
+        };
+        let line = |key: &str| data.get(key).and_then(|v| v.as_u64()).unwrap_or_default() as usize;
+
+        Ok(Self {
+            name: data
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing name field")?
+                .to_string(),
+            r#type: data
+                .get("type")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing type field")?
+                .parse()?,
+            language: string("language").parse().unwrap_or(Language::Text),
+            start_line: line("start_line"),
+            end_line: line("end_line"),
+            code: string("code"),
+            skeleton_code: string("skeleton_code"),
+            doc_comment: string("doc_comment"),
+            exported: data
+                .get("exported")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default(),
+            grammar_version: string("grammar_version"),
+            content_hash: string("content_hash"),
+            imported_files: data
+                .get("imported_files")
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            unresolved_imports: data
+                .get("unresolved_imports")
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            generated: data
+                .get("generated")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default(),
+            summary: FileSummary {
+                function_count: data
+                    .get("function_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or_default() as usize,
+                class_count: data
+                    .get("class_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or_default() as usize,
+                import_count: data
+                    .get("import_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or_default() as usize,
             },
-            language: data
-                .get("lanuage")
-                .unwrap()
-                .as_str()
-                .unwrap()
-                .parse()
-                .unwrap(),
-            start_line: data.get("start_line").unwrap().as_u64().unwrap() as usize,
-            end_line: data.get("end_line").unwrap().as_u64().unwrap() as usize,
-            code: data
-                .get("code")
-                .map(|v| v.as_str().unwrap().to_string())
+            metadata: data
+                .get("metadata")
+                .and_then(|v| v.as_str())
+                .and_then(|s| serde_json::from_str(s).ok())
                 .unwrap_or_default(),
-            skeleton_code: data
-                .get("skeleton_code")
-                .map(|v| v.as_str().unwrap().to_string())
+            accessor_kind: data
+                .get("accessor_kind")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse().ok())
                 .unwrap_or_default(),
-        }
+            is_static: data
+                .get("is_static")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default(),
+            receiver: string("receiver"),
+        })
     }
 
     pub fn short_name(&self) -> String {
@@ -154,6 +312,11 @@ impl Node {
         }
     }
 
+    /// Returns the metadata as a JSON string, which is how it is stored in the database.
+    fn metadata_value(&self) -> serde_json::Value {
+        serde_json::Value::String(serde_json::to_string(&self.metadata).unwrap_or_default())
+    }
+
     /// 将Node转换为字典格式，包含基本字段和short_names字段
     ///
     /// Due to the limitation of kuzu CSV import,
@@ -205,8 +368,55 @@ impl Node {
                     "skeleton_code".to_string(),
                     serde_json::Value::String(self.skeleton_code.clone()),
                 );
+                dict.insert(
+                    "grammar_version".to_string(),
+                    serde_json::Value::String(self.grammar_version.clone()),
+                );
+                dict.insert(
+                    "content_hash".to_string(),
+                    serde_json::Value::String(self.content_hash.clone()),
+                );
+                dict.insert(
+                    "imported_files".to_string(),
+                    serde_json::Value::Array(
+                        self.imported_files
+                            .iter()
+                            .map(|f| serde_json::Value::String(f.clone()))
+                            .collect(),
+                    ),
+                );
+                dict.insert(
+                    "unresolved_imports".to_string(),
+                    serde_json::Value::Array(
+                        self.unresolved_imports
+                            .iter()
+                            .map(|i| serde_json::Value::String(i.clone()))
+                            .collect(),
+                    ),
+                );
+                dict.insert(
+                    "generated".to_string(),
+                    serde_json::Value::Bool(self.generated),
+                );
+                dict.insert(
+                    "function_count".to_string(),
+                    serde_json::Value::from(self.summary.function_count),
+                );
+                dict.insert(
+                    "class_count".to_string(),
+                    serde_json::Value::from(self.summary.class_count),
+                );
+                dict.insert(
+                    "import_count".to_string(),
+                    serde_json::Value::from(self.summary.import_count),
+                );
+                dict.insert("metadata".to_string(), self.metadata_value());
             }
-            NodeType::Interface | NodeType::Class | NodeType::Function | NodeType::OtherType => {
+            NodeType::Interface
+            | NodeType::Class
+            | NodeType::Function
+            | NodeType::OtherType
+            | NodeType::Namespace => {
                 dict.insert(
                     "language".to_string(),
                     serde_json::Value::String(self.language.to_string()),
@@ -227,13 +437,141 @@ impl Node {
                     "end_line".to_string(),
                     serde_json::Value::Number(serde_json::Number::from(self.end_line)),
                 );
+                dict.insert(
+                    "doc_comment".to_string(),
+                    serde_json::Value::String(self.doc_comment.clone()),
+                );
+                dict.insert(
+                    "exported".to_string(),
+                    serde_json::Value::Bool(self.exported),
+                );
+                if self.r#type == NodeType::Function {
+                    dict.insert(
+                        "accessor_kind".to_string(),
+                        serde_json::Value::String(self.accessor_kind.to_string()),
+                    );
+                    dict.insert(
+                        "is_static".to_string(),
+                        serde_json::Value::Bool(self.is_static),
+                    );
+                    dict.insert(
+                        "receiver".to_string(),
+                        serde_json::Value::String(self.receiver.clone()),
+                    );
+                }
+                dict.insert("metadata".to_string(), self.metadata_value());
             }
         }
 
+        // The human-readable name, which is useful when the primary key (i.e. `name`) is hashed.
+        dict.insert(
+            "full_name".to_string(),
+            serde_json::Value::String(self.name.clone()),
+        );
+
         dict
     }
 }
 
+/// A tree of the functions reachable from a root function via `Calls` edges.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallTree {
+    /// The name of the function node
+    pub name: String,
+    /// The functions called by this function
+    pub calls: Vec<CallTree>,
+}
+
+/// The schema of the underlying Kuzu database, for diagnostics (e.g. of schema drift).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaInfo {
+    /// The version of Kuzu
+    pub version: String,
+    /// The node tables, ordered by name
+    pub node_tables: Vec<TableInfo>,
+    /// The relationship tables, ordered by name
+    pub rel_tables: Vec<TableInfo>,
+}
+
+/// A node or relationship table in the database.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableInfo {
+    /// The table name
+    pub name: String,
+    /// The names and types of the columns, in schema order
+    pub columns: Vec<(String, String)>,
+}
+
+/// The numbers of nodes and edges in the database, by table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GraphStats {
+    /// The number of nodes by node table (e.g. `Function`)
+    pub nodes: BTreeMap<String, u64>,
+    /// The number of edges by relationship table (e.g. `CALLS`)
+    pub edges: BTreeMap<String, u64>,
+}
+
+impl GraphStats {
+    /// Get the total number of nodes.
+    pub fn node_count(&self) -> u64 {
+        self.nodes.values().sum()
+    }
+
+    /// Get the total number of edges.
+    pub fn edge_count(&self) -> u64 {
+        self.edges.values().sum()
+    }
+}
+
+/// The progress of indexing, e.g. streamed by `CodeGraph::index_async`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum IndexProgress {
+    /// A file (relative to the repository) has been parsed, along with the number of files parsed so far.
+    FileParsed { file: String, parsed_files: usize },
+    /// All the nodes and edges have been stored.
+    Done,
+    /// Indexing failed with the given error.
+    Failed(String),
+}
+
+/// A change of a file handled by `CodeGraph::watch`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum WatchEvent {
+    /// A file (relative to the repository) has been created or modified, and then indexed.
+    Indexed(String),
+    /// A file (relative to the repository) has been removed, and then its nodes deleted.
+    Deleted(String),
+    /// Handling the change of a file (relative to the repository) failed with the given error.
+    Failed { file: String, error: String },
+}
+
+/// The error returned when the indexing is cancelled via the `cancel_flag` of the config.
+///
+/// The parsing stops before anything is written, so the database is left as it was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexCancelled;
+
+impl std::fmt::Display for IndexCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Indexing cancelled")
+    }
+}
+
+impl std::error::Error for IndexCancelled {}
+
+/// The coupling metrics of a directory (i.e. package), based on the imports crossing its boundary.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DirMetric {
+    /// The directory path relative to the repo path, "." for the root directory
+    pub dir: String,
+    /// Afferent coupling (Ca), i.e. the number of imports from the files outside the directory
+    pub afferent: usize,
+    /// Efferent coupling (Ce), i.e. the number of imports of the files outside the directory
+    pub efferent: usize,
+    /// Instability, i.e. Ce / (Ca + Ce), which is 0 if the directory is not coupled at all
+    pub instability: f64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Edge {
     /// 关系类型
@@ -246,13 +584,15 @@ pub struct Edge {
     pub import: Option<String>,
     /// 别名（可选）
     pub alias: Option<String>,
+    /// Custom properties, e.g. the call-site line of a `Calls` edge, which are stored as a JSON string
+    pub properties: IndexMap<String, serde_json::Value>,
 }
 
 impl Edge {
     /// 从字典数据创建关系
     pub fn from_dict(
         data: &HashMap<String, serde_json::Value>,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let type_str = data
             .get("_label")
             .and_then(|v| v.as_str())
@@ -277,8 +617,8 @@ impl Edge {
             language: Language::Text,
             start_line: 0,
             end_line: 0,
-            code: String::new(),
             skeleton_code: String::from(""),
+            ..Default::default()
         };
 
         let to_node = Node {
@@ -287,8 +627,8 @@ impl Edge {
             language: Language::Text,
             start_line: 0,
             end_line: 0,
-            code: String::new(),
             skeleton_code: String::from(""),
+            ..Default::default()
         };
 
         let import = data
@@ -301,12 +641,19 @@ impl Edge {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let properties = data
+            .get("properties")
+            .and_then(|v| v.as_str())
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+
         Ok(Edge {
             r#type: edge_type,
             from: from_node,
             to: to_node,
             import,
             alias,
+            properties,
         })
     }
 
@@ -351,9 +698,16 @@ impl Edge {
             _ => {}
         }
 
+        dict.insert("properties".to_string(), self.properties_value());
+
         dict
     }
 
+    /// Returns the properties as a JSON string, which is how they are stored in the database.
+    fn properties_value(&self) -> serde_json::Value {
+        serde_json::Value::String(serde_json::to_string(&self.properties).unwrap_or_default())
+    }
+
     /// 获取from_to字符串表示
     pub fn from_to(&self) -> String {
         format!(
@@ -1,7 +1,11 @@
 use duct;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::Language;
 
 pub fn get_repo_module_file_path(
     repo_path: &PathBuf,
@@ -39,39 +43,308 @@ pub fn get_go_repo_module_path(repo_path: &PathBuf) -> Option<String> {
         .map(|m| m.as_str().trim().to_string())
 }
 
-/// 判断是否为 Go 语言的基础类型
+/// Returns the (workspace) packages in the repository, i.e. the directories with a named `package.json`,
+/// keyed by the package name. Dependencies in `node_modules` and hidden directories are skipped.
+pub fn get_ts_workspace_packages(repo_path: &PathBuf) -> HashMap<String, PathBuf> {
+    let mut packages: HashMap<String, PathBuf> = HashMap::new();
+
+    let mut dirs = vec![repo_path.clone()];
+    while let Some(dir) = dirs.pop() {
+        let package_json = read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
+        if let Some(name) = package_json
+            .as_ref()
+            .and_then(|v| v.get("name"))
+            .and_then(|v| v.as_str())
+        {
+            packages.insert(name.to_string(), dir.clone());
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name == "node_modules" || file_name.starts_with('.') {
+                continue;
+            }
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                dirs.push(entry.path());
+            }
+        }
+    }
+
+    packages
+}
+
+/// Returns the candidate entry files (relative to the package directory) of the `subpath` (e.g. "." or "./utils")
+/// of a package, according to its `package.json`, in order of preference.
 ///
-/// # Arguments
-/// * `type_name` - 类型名称
+/// The `exports` field takes precedence over `types`, `typings` and `main`, and the `types` condition is preferred
+/// in conditional exports.
+pub fn get_package_json_entries(package_json: &serde_json::Value, subpath: &str) -> Vec<String> {
+    fn conditional_entries(value: &serde_json::Value, entries: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => entries.push(s.clone()),
+            serde_json::Value::Object(map) => {
+                for condition in ["types", "import", "default", "require"] {
+                    if let Some(v) = map.get(condition) {
+                        conditional_entries(v, entries);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut entries: Vec<String> = Vec::new();
+    match package_json.get("exports") {
+        Some(serde_json::Value::Object(map)) if map.keys().any(|k| k.starts_with('.')) => {
+            if let Some(v) = map.get(subpath) {
+                conditional_entries(v, &mut entries);
+            }
+        }
+        // A single export (or the conditions of it) for the package root.
+        Some(exports) if subpath == "." => conditional_entries(exports, &mut entries),
+        _ => {}
+    }
+
+    if subpath == "." {
+        for field in ["types", "typings", "main"] {
+            if let Some(entry) = package_json.get(field).and_then(|v| v.as_str()) {
+                entries.push(entry.to_string());
+            }
+        }
+        entries.push("index".to_string());
+    } else {
+        entries.push(subpath.to_string());
+    }
+
+    entries
+}
+
+/// Returns the content with CRLF line endings converted to LF. Lone CRs are kept as is.
+pub fn normalize_line_endings(content: &[u8]) -> Vec<u8> {
+    let mut normalized = Vec::with_capacity(content.len());
+    for (i, &byte) in content.iter().enumerate() {
+        if byte == b'\r' && content.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        normalized.push(byte);
+    }
+    normalized
+}
+
+/// The builtin types of Go, which are never defined in the repository.
+const GO_BUILTIN_TYPES: &[&str] = &[
+    // Basic types
+    "bool",
+    "byte",
+    "rune",
+    "string",
+    "error",
+    // Integer types
+    "int",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "uint",
+    "uint8",
+    "uint16",
+    "uint32",
+    "uint64",
+    "uintptr",
+    // Floating-point and complex types
+    "float32",
+    "float64",
+    "complex64",
+    "complex128",
+    // Special types
+    "interface{}",
+    "any",
+    "comparable",
+];
+
+/// The builtin types of TypeScript, including the common global generic types (e.g. `Promise`).
+const TYPESCRIPT_BUILTIN_TYPES: &[&str] = &[
+    // Primitive types
+    "string",
+    "number",
+    "boolean",
+    "any",
+    "void",
+    "null",
+    "undefined",
+    "unknown",
+    "never",
+    "object",
+    "bigint",
+    "symbol",
+    "function",
+    // Composite types
+    "Map",
+    "Promise",
+    "Array",
+    "Record",
+    "Partial",
+    // Utility types
+    "Required",
+    "Readonly",
+    "Pick",
+    "Omit",
+    "Exclude",
+    "Extract",
+    "NonNullable",
+    "Parameters",
+    "ConstructorParameters",
+    "ReturnType",
+    "InstanceType",
+    "Awaited",
+    "ThisParameterType",
+    "OmitThisParameter",
+    "ThisType",
+    "Uppercase",
+    "Lowercase",
+    "Capitalize",
+    "Uncapitalize",
+];
+
+/// The builtin types of Python, as well as the constructs of the `typing` module (e.g. `Optional`).
+const PYTHON_BUILTIN_TYPES: &[&str] = &[
+    // Builtin types
+    "str",
+    "int",
+    "float",
+    "complex",
+    "bool",
+    "bytes",
+    "bytearray",
+    "object",
+    "None",
+    "list",
+    "dict",
+    "set",
+    "frozenset",
+    "tuple",
+    "type",
+    // `typing` constructs
+    "Any",
+    "Optional",
+    "Union",
+    "List",
+    "Dict",
+    "Set",
+    "FrozenSet",
+    "Tuple",
+    "Type",
+    "Callable",
+    "Iterable",
+    "Iterator",
+    "Sequence",
+    "Mapping",
+    "MutableMapping",
+    "Generator",
+    "AsyncIterator",
+    "Awaitable",
+    "Coroutine",
+    "Annotated",
+    "ClassVar",
+    "Final",
+    "Literal",
+];
+
+/// Returns whether the given type name is a builtin type of the language (e.g. `string` in Go, `number` in
+/// TypeScript or `str` in Python), which is filtered out from the referenced types.
 ///
-/// # Returns
-/// * `bool` - 如果是基础类型返回 true，否则返回 false
-pub fn is_go_builtin_type(type_name: &str) -> bool {
-    match type_name {
-        // 基础数据类型
-        "bool" | "byte" | "rune" |
-        // 整数类型
-        "int" | "int8" | "int16" | "int32" | "int64" |
-        "uint" | "uint8" | "uint16" | "uint32" | "uint64" | "uintptr" |
-        // 浮点类型
-        "float32" | "float64" |
-        // 复数类型
-        "complex64" | "complex128" |
-        // 字符串类型
-        "string" |
-        // 特殊类型
-        "error" | "interface{}" | "any" => true,
-        _ => false,
+/// Languages without a list of builtin types have none.
+pub fn is_builtin_type(language: &Language, type_name: &str) -> bool {
+    let builtin_types = match language {
+        Language::Go => GO_BUILTIN_TYPES,
+        Language::TypeScript => TYPESCRIPT_BUILTIN_TYPES,
+        Language::Python => PYTHON_BUILTIN_TYPES,
+        _ => &[],
+    };
+    builtin_types.contains(&type_name)
+}
+
+/// The file name suffixes of the well-known generated files.
+const GENERATED_FILE_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    "_gen.go",
+    ".gen.go",
+    "_generated.go",
+    ".g.ts",
+    ".gen.ts",
+    ".generated.ts",
+    "_pb2.py",
+];
+
+/// Checks whether the given file is generated, by its name suffix (e.g. `.pb.go`) or its header comment
+/// (e.g. `// Code generated by protoc-gen-go. DO NOT EDIT.`, see https://go.dev/s/generatedcode).
+pub fn is_generated_file(file_name: &str, content: &[u8]) -> bool {
+    if GENERATED_FILE_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+    {
+        return true;
+    }
+
+    // The header comment is expected to be at the top of the file.
+    static GENERATED_HEADER: OnceLock<Regex> = OnceLock::new();
+    let re = GENERATED_HEADER.get_or_init(|| {
+        Regex::new(r"(?m)^(//|#|/\*)\s*(Code generated .* DO NOT EDIT\.|@generated)").unwrap()
+    });
+    let header = &content[..content.len().min(1024)];
+    re.is_match(&String::from_utf8_lossy(header))
+}
+
+/// Returns a stable hash (i.e. 128-bit FNV-1a in hex) of the given string.
+///
+/// Unlike `std::hash`, the result is guaranteed to be the same across Rust versions and platforms.
+pub fn stable_hash(s: &str) -> String {
+    stable_hash_bytes(s.as_bytes())
+}
+
+/// Returns a stable hash of the given bytes, like `stable_hash`.
+pub fn stable_hash_bytes(bytes: &[u8]) -> String {
+    const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in bytes {
+        hash ^= *byte as u128;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("{:032x}", hash)
+}
+
+/// Returns the given string (e.g. a node name or path) as a double-quoted Cypher string literal, to be
+/// interpolated into a query.
+pub fn cypher_string_literal(s: &str) -> String {
+    let mut literal = String::with_capacity(s.len() + 2);
+    literal.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            c => literal.push(c),
+        }
     }
+    literal.push('"');
+    literal
 }
 
-fn get_go_root() -> Result<String, Box<dyn std::error::Error>> {
+pub fn get_go_root() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let go_root = duct::cmd!("go", "env", "GOROOT").read()?.trim().to_string();
 
     Ok(go_root)
 }
 
-fn get_go_path() -> Result<String, Box<dyn std::error::Error>> {
+fn get_go_path() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let go_root = duct::cmd!("go", "env", "GOPATH").read()?.trim().to_string();
 
     Ok(go_root)
@@ -93,4 +366,35 @@ mod tests {
             Some(expected_path)
         );
     }
+
+    #[test]
+    fn test_is_generated_file() {
+        assert!(is_generated_file("api/user.pb.go", b"package api\n"));
+        assert!(is_generated_file(
+            "mock.go",
+            b"// Code generated by MockGen. DO NOT EDIT.\n\npackage mock\n"
+        ));
+        assert!(!is_generated_file(
+            "main.go",
+            b"// Code generated by hand. Please edit.\npackage main\n"
+        ));
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        assert_eq!(
+            stable_hash("main.go:User"),
+            "aa97279f261df13c70f55917d3f0ef91"
+        );
+        assert_ne!(stable_hash("main.go:User"), stable_hash("main.go:user"));
+    }
+
+    #[test]
+    fn test_cypher_string_literal() {
+        assert_eq!(cypher_string_literal("main.go:User"), r#""main.go:User""#);
+        assert_eq!(
+            cypher_string_literal("say \"hi\"\\\nbye.go"),
+            r#""say \"hi\"\\\nbye.go""#
+        );
+    }
 }